@@ -0,0 +1,451 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths::app_base_dir;
+
+/// Environment variable that sets the data directory, like `--data-dir`.
+pub const DATA_DIR_ENV: &str = "QUICK_LAUNCHER_DATA_DIR";
+pub const DATA_DIR_FLAG: &str = "--data-dir";
+/// Marker file next to the executable that forces portable mode.
+pub const PORTABLE_FLAG: &str = "portable.flag";
+/// Marker left in `data/` next to the executable once the data was moved to app data,
+/// so a database there that couldn't be removed isn't picked up again.
+const MOVED_FLAG: &str = "moved.flag";
+
+const APP_IDENTIFIER: &str = "com.cat.quick-launcher";
+const DB_FILE: &str = "launcher.db";
+const ICON_CACHE_DIR: &str = "icon-cache";
+
+/// Why the data directory is where it is, in order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataDirSource {
+    Flag,
+    Env,
+    Portable,
+    ExeDir,
+    AppData,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataLocation {
+    pub dir: PathBuf,
+    pub source: DataDirSource,
+}
+
+impl DataLocation {
+    pub fn db_path(&self) -> PathBuf {
+        self.dir.join(DB_FILE)
+    }
+
+    pub fn icon_cache_dir(&self) -> PathBuf {
+        self.dir.join(ICON_CACHE_DIR)
+    }
+
+    /// Set from outside the app, so it can't be moved from the settings.
+    pub fn is_forced(&self) -> bool {
+        matches!(self.source, DataDirSource::Flag | DataDirSource::Env)
+    }
+}
+
+/// Where `move_data` can put the data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataDirTarget {
+    /// `data/` next to the executable, pinned there with `portable.flag`.
+    Portable,
+    /// The per-user application data directory.
+    AppData,
+}
+
+static LOCATION: OnceLock<DataLocation> = OnceLock::new();
+
+/// Resolves the data directory once per process. Call it at startup with the
+/// `--data-dir` value, before anything opens the database.
+pub fn init(flag: Option<PathBuf>) -> &'static DataLocation {
+    LOCATION.get_or_init(|| resolve(flag))
+}
+
+pub fn current() -> &'static DataLocation {
+    init(None)
+}
+
+/// The value of `--data-dir <dir>` or `--data-dir=<dir>` in `args`.
+pub fn flag_from_args(args: &[String]) -> Option<PathBuf> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == DATA_DIR_FLAG {
+            return iter.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg
+            .strip_prefix(DATA_DIR_FLAG)
+            .and_then(|r| r.strip_prefix('='))
+        {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+/// `args` without `--data-dir` and its value.
+pub fn strip_flag(args: &[String]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == DATA_DIR_FLAG {
+            iter.next();
+        } else if !arg.starts_with(&format!("{}=", DATA_DIR_FLAG)) {
+            out.push(arg.clone());
+        }
+    }
+    out
+}
+
+/// The per-user application data directory, as Tauri's `app_data_dir` would return it.
+pub fn app_data_dir() -> Option<PathBuf> {
+    let env_dir = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    #[cfg(target_os = "windows")]
+    let base = env_dir("APPDATA");
+    #[cfg(target_os = "macos")]
+    let base = env_dir("HOME").map(|h| h.join("Library").join("Application Support"));
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let base = env_dir("XDG_DATA_HOME").or_else(|| env_dir("HOME").map(|h| h.join(".local/share")));
+    base.map(|b| b.join(APP_IDENTIFIER))
+}
+
+/// `data/` next to the executable.
+pub fn portable_dir() -> Option<PathBuf> {
+    app_base_dir().map(|b| b.join("data"))
+}
+
+fn is_dir_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".write-test-{}", std::process::id()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// The places the data can live in, as seen from one executable.
+struct Dirs {
+    exe_dir: Option<PathBuf>,
+    portable: Option<PathBuf>,
+    app_data: Option<PathBuf>,
+}
+
+impl Dirs {
+    fn of_this_exe() -> Self {
+        Dirs {
+            exe_dir: app_base_dir(),
+            portable: portable_dir(),
+            app_data: app_data_dir(),
+        }
+    }
+}
+
+fn resolve(flag: Option<PathBuf>) -> DataLocation {
+    if let Some(dir) = flag.filter(|d| !d.as_os_str().is_empty()) {
+        return DataLocation {
+            dir,
+            source: DataDirSource::Flag,
+        };
+    }
+    if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|v| !v.is_empty()) {
+        return DataLocation {
+            dir: PathBuf::from(dir),
+            source: DataDirSource::Env,
+        };
+    }
+    resolve_in(Dirs::of_this_exe())
+}
+
+fn resolve_in(dirs: Dirs) -> DataLocation {
+    let Dirs {
+        exe_dir,
+        portable,
+        app_data,
+    } = dirs;
+    if let (Some(exe_dir), Some(portable)) = (&exe_dir, &portable) {
+        if exe_dir.join(PORTABLE_FLAG).exists() {
+            return DataLocation {
+                dir: portable.clone(),
+                source: DataDirSource::Portable,
+            };
+        }
+    }
+    // Data moved to app data stays there even though the exe directory is writable.
+    let app_data_in_use = app_data.as_ref().is_some_and(|d| d.join(DB_FILE).exists());
+    if let (Some(exe_dir), Some(portable)) = (&exe_dir, &portable) {
+        let exe_in_use = portable.join(DB_FILE).exists() && !portable.join(MOVED_FLAG).exists();
+        if (exe_in_use || !app_data_in_use) && is_dir_writable(exe_dir) {
+            return DataLocation {
+                dir: portable.clone(),
+                source: DataDirSource::ExeDir,
+            };
+        }
+    }
+    match (app_data, portable) {
+        (Some(dir), _) => DataLocation {
+            dir,
+            source: DataDirSource::AppData,
+        },
+        (None, Some(dir)) => DataLocation {
+            dir,
+            source: DataDirSource::ExeDir,
+        },
+        (None, None) => DataLocation {
+            dir: PathBuf::from("data"),
+            source: DataDirSource::ExeDir,
+        },
+    }
+}
+
+fn copy_dir_files(from: &Path, to: &Path) -> Result<(), String> {
+    if !from.is_dir() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(to).map_err(|e| e.to_string())?;
+    for entry in std::fs::read_dir(from).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            std::fs::copy(entry.path(), to.join(entry.file_name())).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes a consistent copy of the database at `from`, including anything still in the WAL.
+pub(crate) fn copy_db(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    crate::storage::open(from)?
+        .execute("VACUUM INTO ?1", rusqlite::params![to.to_string_lossy()])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn remove_db(path: &Path) -> Result<(), String> {
+    let name = path.as_os_str().to_string_lossy();
+    for suffix in ["", "-wal", "-shm"] {
+        let file = format!("{}{}", name, suffix);
+        match std::fs::remove_file(&file) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("{}: {}", file, e))
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Moves the databases of every profile and the icon cache from `from` to `target` and updates the
+/// `portable.flag` marker so the next start resolves to the new place. Returns the
+/// new directory. The old files are only removed once the copy is complete.
+pub fn move_data(from: &DataLocation, target: DataDirTarget) -> Result<PathBuf, String> {
+    move_data_in(
+        from,
+        target,
+        Dirs::of_this_exe(),
+        &crate::profiles::profile_db_paths(),
+    )
+}
+
+fn move_data_in(
+    from: &DataLocation,
+    target: DataDirTarget,
+    dirs: Dirs,
+    profile_dbs: &[PathBuf],
+) -> Result<PathBuf, String> {
+    if from.is_forced() {
+        return Err(format!(
+            "the data directory is set by {} or {}",
+            DATA_DIR_FLAG, DATA_DIR_ENV
+        ));
+    }
+    let exe_dir = dirs.exe_dir.ok_or("Cannot determine exe directory")?;
+    let to = match target {
+        DataDirTarget::Portable => dirs.portable,
+        DataDirTarget::AppData => dirs.app_data,
+    }
+    .ok_or("Cannot determine the target directory")?;
+    if to == from.dir {
+        return Err("the data is already there".to_string());
+    }
+    let target_db = to.join(DB_FILE);
+    let moved_flag = to.join(MOVED_FLAG);
+    // What an earlier move to app data couldn't remove is out of date
+    if target == DataDirTarget::Portable && moved_flag.exists() {
+        remove_db(&target_db)?;
+        for path in profile_dbs {
+            if let Ok(relative) = path.strip_prefix(&from.dir) {
+                remove_db(&to.join(relative))?;
+            }
+        }
+    }
+    if target_db.exists() {
+        return Err(format!("{} already exists", target_db.display()));
+    }
+    std::fs::create_dir_all(&to).map_err(|e| e.to_string())?;
+
+    copy_db(&from.db_path(), &target_db)?;
+    for path in profile_dbs {
+        if let Ok(relative) = path.strip_prefix(&from.dir) {
+            copy_db(path, &to.join(relative))?;
+        }
+    }
+    let registry = from.dir.join(crate::profiles::REGISTRY_FILE);
+    if registry.exists() {
+        std::fs::copy(&registry, to.join(crate::profiles::REGISTRY_FILE))
+            .map_err(|e| e.to_string())?;
+    }
+    copy_dir_files(&from.icon_cache_dir(), &to.join(ICON_CACHE_DIR))?;
+
+    let flag = exe_dir.join(PORTABLE_FLAG);
+    match target {
+        DataDirTarget::Portable => {
+            if moved_flag.exists() {
+                std::fs::remove_file(&moved_flag).map_err(|e| e.to_string())?;
+            }
+            std::fs::write(&flag, b"").map_err(|e| e.to_string())?
+        }
+        DataDirTarget::AppData => {
+            // Written before anything is removed, so a database left behind in `data/`
+            // can't make the next start go back to it
+            std::fs::write(from.dir.join(MOVED_FLAG), b"").map_err(|e| e.to_string())?;
+            if flag.exists() {
+                std::fs::remove_file(&flag).map_err(|e| e.to_string())?
+            }
+        }
+    }
+
+    // The data is in place and the markers point at it, so a file that can't be removed
+    // yet, e.g. one still open elsewhere, is only left over
+    let _ = remove_db(&from.db_path());
+    for path in profile_dbs {
+        let _ = remove_db(path);
+    }
+    let _ = std::fs::remove_file(registry);
+    let _ = std::fs::remove_dir_all(from.icon_cache_dir());
+    Ok(to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::{app, state, TempDir};
+
+    fn dirs(root: &Path) -> Dirs {
+        Dirs {
+            exe_dir: Some(root.join("exe")),
+            portable: Some(root.join("exe").join("data")),
+            app_data: Some(root.join("appdata")),
+        }
+    }
+
+    fn save_db(path: &Path, app_id: &str) {
+        let mut conn = crate::storage::open(path).unwrap();
+        crate::storage::write_launcher_state(&mut conn, &state(vec![app(app_id, "/bin/a")]))
+            .unwrap();
+    }
+
+    fn saved_app(path: &Path) -> String {
+        let conn = crate::storage::open_existing(path).unwrap();
+        let state = crate::storage::read_launcher_state(&conn).unwrap().unwrap();
+        state.groups[0].apps[0].id.clone()
+    }
+
+    fn a_profile_db(dir: &Path) -> PathBuf {
+        dir.join("profiles").join("work").join(DB_FILE)
+    }
+
+    #[test]
+    fn data_moves_to_app_data_and_back() {
+        let root = TempDir::new("move");
+        let portable = root.0.join("exe").join("data");
+        let app_data = root.0.join("appdata");
+        std::fs::create_dir_all(&portable).unwrap();
+        save_db(&portable.join(DB_FILE), "main");
+        save_db(&a_profile_db(&portable), "work");
+        std::fs::create_dir_all(portable.join(ICON_CACHE_DIR)).unwrap();
+        std::fs::write(portable.join(ICON_CACHE_DIR).join("x.png"), b"png").unwrap();
+
+        let here = resolve_in(dirs(&root.0));
+        assert_eq!(
+            (here.dir.clone(), here.source),
+            (portable.clone(), DataDirSource::ExeDir)
+        );
+
+        let moved = move_data_in(
+            &here,
+            DataDirTarget::AppData,
+            dirs(&root.0),
+            &[a_profile_db(&portable)],
+        )
+        .unwrap();
+        assert_eq!(moved, app_data);
+        assert_eq!(saved_app(&app_data.join(DB_FILE)), "main");
+        assert_eq!(saved_app(&a_profile_db(&app_data)), "work");
+        assert!(app_data.join(ICON_CACHE_DIR).join("x.png").exists());
+        assert!(!portable.join(DB_FILE).exists());
+        assert!(!a_profile_db(&portable).exists());
+        let there = resolve_in(dirs(&root.0));
+        assert_eq!(
+            (there.dir.clone(), there.source),
+            (app_data.clone(), DataDirSource::AppData)
+        );
+
+        let back = move_data_in(
+            &there,
+            DataDirTarget::Portable,
+            dirs(&root.0),
+            &[a_profile_db(&app_data)],
+        )
+        .unwrap();
+        assert_eq!(back, portable);
+        assert!(root.0.join("exe").join(PORTABLE_FLAG).exists());
+        assert!(!app_data.join(DB_FILE).exists());
+        assert_eq!(saved_app(&portable.join(DB_FILE)), "main");
+        assert_eq!(saved_app(&a_profile_db(&portable)), "work");
+        let again = resolve_in(dirs(&root.0));
+        assert_eq!(
+            (again.dir, again.source),
+            (portable, DataDirSource::Portable)
+        );
+    }
+
+    #[test]
+    fn a_database_left_behind_does_not_undo_a_move_to_app_data() {
+        let root = TempDir::new("move-leftover");
+        let portable = root.0.join("exe").join("data");
+        let app_data = root.0.join("appdata");
+        std::fs::create_dir_all(&portable).unwrap();
+        save_db(&portable.join(DB_FILE), "main");
+
+        let here = resolve_in(dirs(&root.0));
+        move_data_in(&here, DataDirTarget::AppData, dirs(&root.0), &[]).unwrap();
+        // As if the old file had still been open and couldn't be removed
+        save_db(&portable.join(DB_FILE), "stale");
+
+        let there = resolve_in(dirs(&root.0));
+        assert_eq!(
+            (there.dir.clone(), there.source),
+            (app_data, DataDirSource::AppData)
+        );
+
+        // Moving back replaces the stale copy with the current data
+        move_data_in(&there, DataDirTarget::Portable, dirs(&root.0), &[]).unwrap();
+        assert_eq!(saved_app(&portable.join(DB_FILE)), "main");
+        assert!(!portable.join(MOVED_FLAG).exists());
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::model::{ChangeLogEntry, LauncherState};
+use crate::sync::{Library, LibraryApp, LibraryGroup};
+
+/// Which side of a logged change to restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Back to the old values.
+    Undo,
+    /// Forward to the new values again.
+    Redo,
+}
+
+/// A whole entity as `change_log` stores it on creation and deletion.
+fn parse_fields(json: &str) -> Result<serde_json::Map<String, Value>, String> {
+    match serde_json::from_str::<Value>(json).map_err(|e| e.to_string())? {
+        Value::Object(map) => Ok(map),
+        _ => Err(format!("not an entity snapshot: {}", json)),
+    }
+}
+
+fn field_str(map: &serde_json::Map<String, Value>, key: &str) -> String {
+    map.get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn parse_position(value: &str) -> Result<i64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("bad position: {}", value))
+}
+
+fn set_group_field(group: &mut LibraryGroup, field: &str, value: &str) -> Result<(), String> {
+    match field {
+        "name" => group.name = value.to_string(),
+        "position" => group.position = parse_position(value)?,
+        _ => return Err(format!("unknown group field: {}", field)),
+    }
+    Ok(())
+}
+
+fn set_app_field(app: &mut LibraryApp, field: &str, value: &str) -> Result<(), String> {
+    match field {
+        "groupId" => app.group_id = value.to_string(),
+        "name" => app.name = value.to_string(),
+        "path" => app.path = value.to_string(),
+        "args" => app.args = value.to_string(),
+        "pinned" => app.pinned = value == "true",
+        "position" => app.position = parse_position(value)?,
+        "kind" => app.kind = value.to_string(),
+        "browser" => app.browser = value.to_string(),
+        "workingDir" => app.working_dir = value.to_string(),
+        "description" => app.description = value.to_string(),
+        "iconLocation" => app.icon_location = value.to_string(),
+        _ => return Err(format!("unknown app field: {}", field)),
+    }
+    Ok(())
+}
+
+fn apply_group(
+    library: &mut Library,
+    change: &ChangeLogEntry,
+    value: Option<&str>,
+) -> Result<(), String> {
+    let index = library.groups.iter().position(|g| g.id == change.entity_id);
+    match (change.field.as_str(), value, index) {
+        ("*", None, Some(i)) => {
+            library.groups.remove(i);
+        }
+        ("*", None, None) => {}
+        ("*", Some(json), _) => {
+            let fields = parse_fields(json)?;
+            let group = LibraryGroup {
+                id: change.entity_id.clone(),
+                name: field_str(&fields, "name"),
+                position: parse_position(&field_str(&fields, "position"))?,
+                updated_at: 0,
+            };
+            match index {
+                Some(i) => library.groups[i] = group,
+                None => library.groups.push(group),
+            }
+        }
+        (field, Some(value), Some(i)) => set_group_field(&mut library.groups[i], field, value)?,
+        // The group is gone by now; nothing left to change
+        (_, _, None) | (_, None, Some(_)) => {}
+    }
+    Ok(())
+}
+
+fn apply_app(
+    library: &mut Library,
+    change: &ChangeLogEntry,
+    value: Option<&str>,
+) -> Result<(), String> {
+    let index = library.apps.iter().position(|a| a.id == change.entity_id);
+    match (change.field.as_str(), value, index) {
+        ("*", None, Some(i)) => {
+            library.apps.remove(i);
+        }
+        ("*", None, None) => {}
+        ("*", Some(json), _) => {
+            let fields = parse_fields(json)?;
+            let app = LibraryApp {
+                id: change.entity_id.clone(),
+                group_id: field_str(&fields, "groupId"),
+                name: field_str(&fields, "name"),
+                path: field_str(&fields, "path"),
+                args: field_str(&fields, "args"),
+                pinned: field_str(&fields, "pinned") == "true",
+                position: parse_position(&field_str(&fields, "position"))?,
+                // Snapshots from before `addedAt` was kept fall back to the change time
+                added_at: field_str(&fields, "addedAt")
+                    .parse()
+                    .unwrap_or(change.changed_at),
+                updated_at: 0,
+                kind: field_str(&fields, "kind"),
+                browser: field_str(&fields, "browser"),
+                working_dir: field_str(&fields, "workingDir"),
+                description: field_str(&fields, "description"),
+                icon_location: field_str(&fields, "iconLocation"),
+                icon_hash: Some(field_str(&fields, "iconHash")).filter(|h| !h.is_empty()),
+            };
+            match index {
+                Some(i) => library.apps[i] = app,
+                None => library.apps.push(app),
+            }
+        }
+        (field, Some(value), Some(i)) => set_app_field(&mut library.apps[i], field, value)?,
+        (_, _, None) | (_, None, Some(_)) => {}
+    }
+    Ok(())
+}
+
+fn apply_setting(
+    settings: &mut Value,
+    change: &ChangeLogEntry,
+    value: Option<&str>,
+) -> Result<(), String> {
+    let Value::Object(map) = settings else {
+        return Ok(());
+    };
+    match value {
+        Some(json) => {
+            let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+            map.insert(change.field.clone(), value);
+        }
+        None => {
+            map.remove(&change.field);
+        }
+    }
+    Ok(())
+}
+
+/// `state` with the logged `changes` of one write taken back, or made again. Changes are
+/// applied field by field, so edits made since then to other fields or entities stay.
+/// Logged positions go back where they were; siblings whose position was never logged
+/// only shifted around them, so they keep their order and fill the free slots.
+fn settle(entries: Vec<(&mut i64, bool)>) {
+    let taken: HashSet<i64> = entries
+        .iter()
+        .filter(|(_, placed)| *placed)
+        .map(|(position, _)| **position)
+        .collect();
+    let mut rest: Vec<&mut i64> = entries
+        .into_iter()
+        .filter(|(_, placed)| !placed)
+        .map(|(position, _)| position)
+        .collect();
+    rest.sort_by_key(|position| **position);
+    let mut slot = 0;
+    for position in rest {
+        while taken.contains(&slot) {
+            slot += 1;
+        }
+        *position = slot;
+        slot += 1;
+    }
+}
+
+pub fn apply_changes(
+    state: &LauncherState,
+    changes: &[ChangeLogEntry],
+    direction: Direction,
+) -> Result<LauncherState, String> {
+    let mut library = Library::from_state(state);
+    let mut settings = serde_json::to_value(&state.settings).map_err(|e| e.to_string())?;
+    let ordered: Box<dyn Iterator<Item = &ChangeLogEntry>> = match direction {
+        Direction::Undo => Box::new(changes.iter().rev()),
+        Direction::Redo => Box::new(changes.iter()),
+    };
+    // Entities this step gives an explicit position
+    let mut placed: HashSet<(&str, &str)> = HashSet::new();
+    for change in ordered {
+        let value = match direction {
+            Direction::Undo => change.old_value.as_deref(),
+            Direction::Redo => change.new_value.as_deref(),
+        };
+        if value.is_some() && matches!(change.field.as_str(), "*" | "position") {
+            placed.insert((change.entity.as_str(), change.entity_id.as_str()));
+        }
+        match change.entity.as_str() {
+            "group" => apply_group(&mut library, change, value)?,
+            "app" => apply_app(&mut library, change, value)?,
+            "settings" => apply_setting(&mut settings, change, value)?,
+            other => return Err(format!("unknown entity: {}", other)),
+        }
+    }
+    settle(
+        library
+            .groups
+            .iter_mut()
+            .map(|g| {
+                let is_placed = placed.contains(&("group", g.id.as_str()));
+                (&mut g.position, is_placed)
+            })
+            .collect(),
+    );
+    let group_ids: HashSet<String> = library.apps.iter().map(|a| a.group_id.clone()).collect();
+    for group_id in &group_ids {
+        settle(
+            library
+                .apps
+                .iter_mut()
+                .filter(|a| a.group_id == *group_id)
+                .map(|a| {
+                    let is_placed = placed.contains(&("app", a.id.as_str()));
+                    (&mut a.position, is_placed)
+                })
+                .collect(),
+        );
+    }
+    library.normalize();
+
+    let groups = library.into_groups();
+    let active_group_id = Some(state.active_group_id.clone())
+        .filter(|id| groups.iter().any(|g| g.id == *id))
+        .or_else(|| groups.first().map(|g| g.id.clone()))
+        .unwrap_or_default();
+    Ok(LauncherState {
+        version: state.version,
+        active_group_id,
+        groups,
+        settings: serde_json::from_value(settings).map_err(|e| e.to_string())?,
+        revision: state.revision,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::{app, state, TempDir};
+    use crate::storage::{
+        open, read_launcher_state, read_revision_changes, write_launcher_state, write_undo_floor,
+    };
+
+    fn blobs(conn: &rusqlite::Connection, hash: &str) -> i64 {
+        conn.query_row(
+            "SELECT COUNT(1) FROM icon_blobs WHERE hash = ?1",
+            [hash],
+            |r| r.get(0),
+        )
+        .unwrap()
+    }
+
+    /// Saves one app with an icon and then deletes it; returns the icon's hash and the
+    /// deletion's changes.
+    fn delete_app_with_icon(conn: &mut rusqlite::Connection) -> (String, Vec<ChangeLogEntry>) {
+        let mut first = state(vec![app("a", "/a"), app("b", "/b")]);
+        first.groups[0].apps[0].added_at = 42;
+        first.groups[0].apps[0].icon = Some("data:image/png;base64,iVBORw0KGgo=".to_string());
+        write_launcher_state(conn, &first).unwrap();
+        let stored = read_launcher_state(conn).unwrap().unwrap();
+        let hash = stored.groups[0].apps[0].icon_hash.clone().unwrap();
+        let (_, changes) = save(conn, |s| {
+            s.groups[0].apps.remove(0);
+        });
+        (hash, changes)
+    }
+
+    fn ids(state: &LauncherState) -> Vec<&str> {
+        state.groups[0].apps.iter().map(|a| a.id.as_str()).collect()
+    }
+
+    /// Saves `edit` applied to the stored state; returns the state before it and its changes.
+    fn save(
+        conn: &mut rusqlite::Connection,
+        edit: impl FnOnce(&mut LauncherState),
+    ) -> (LauncherState, Vec<ChangeLogEntry>) {
+        let before = read_launcher_state(conn).unwrap().unwrap();
+        let mut next = before.clone();
+        edit(&mut next);
+        let revision = write_launcher_state(conn, &next).unwrap();
+        (before, read_revision_changes(conn, revision).unwrap())
+    }
+
+    #[test]
+    fn undo_restores_deleted_and_moved_apps_in_order() {
+        let dir = TempDir::new("undo-order");
+        let mut conn = open(&dir.db()).unwrap();
+        let first = state(vec![
+            app("a", "/a"),
+            app("b", "/b"),
+            app("c", "/c"),
+            app("d", "/d"),
+        ]);
+        write_launcher_state(&mut conn, &first).unwrap();
+
+        // Two deletions in one save: only the deleted apps are logged
+        let (before, changes) = save(&mut conn, |s| {
+            s.groups[0].apps.retain(|a| a.id != "a" && a.id != "c");
+        });
+        let current = read_launcher_state(&conn).unwrap().unwrap();
+        assert_eq!(ids(&current), ["b", "d"]);
+        let undone = apply_changes(&current, &changes, Direction::Undo).unwrap();
+        assert_eq!(ids(&undone), ids(&before));
+        let redone = apply_changes(&undone, &changes, Direction::Redo).unwrap();
+        assert_eq!(ids(&redone), ["b", "d"]);
+
+        // A move to the front
+        let (before, changes) = save(&mut conn, |s| {
+            let d = s.groups[0].apps.pop().unwrap();
+            s.groups[0].apps.insert(0, d);
+        });
+        let current = read_launcher_state(&conn).unwrap().unwrap();
+        assert_eq!(ids(&current), ["d", "b"]);
+        let undone = apply_changes(&current, &changes, Direction::Undo).unwrap();
+        assert_eq!(ids(&undone), ids(&before));
+    }
+
+    #[test]
+    fn undoing_a_deletion_brings_back_when_it_was_added_and_its_icon() {
+        let dir = TempDir::new("undo-delete");
+        let mut conn = open(&dir.db()).unwrap();
+        let (hash, changes) = delete_app_with_icon(&mut conn);
+        // The deleted app's icon outlives it while undo can still use it
+        assert_eq!(blobs(&conn, &hash), 1);
+
+        let current = read_launcher_state(&conn).unwrap().unwrap();
+        let mut undone = apply_changes(&current, &changes, Direction::Undo).unwrap();
+        undone.revision = write_launcher_state(&mut conn, &undone).unwrap();
+        let restored = read_launcher_state(&conn).unwrap().unwrap();
+        assert_eq!(ids(&restored), ["a", "b"]);
+        assert_eq!(restored.groups[0].apps[0].added_at, 42);
+        assert_eq!(
+            restored.groups[0].apps[0].icon_hash.as_deref(),
+            Some(hash.as_str())
+        );
+    }
+
+    #[test]
+    fn a_deleted_apps_icon_goes_once_undo_cannot_reach_the_deletion() {
+        let dir = TempDir::new("undo-floor");
+        let mut conn = open(&dir.db()).unwrap();
+        let (hash, changes) = delete_app_with_icon(&mut conn);
+        let deleted_in = changes[0].revision;
+
+        write_undo_floor(&conn, deleted_in).unwrap();
+        save(&mut conn, |s| {
+            s.groups[0].apps[0].name = "Renamed".to_string()
+        });
+        assert_eq!(blobs(&conn, &hash), 1);
+
+        // The deletion dropped out of the history
+        write_undo_floor(&conn, deleted_in + 1).unwrap();
+        save(&mut conn, |s| s.groups[0].apps[0].pinned = true);
+        assert_eq!(blobs(&conn, &hash), 0);
+    }
+}
@@ -0,0 +1,87 @@
+//! Bookmark exports in the Netscape HTML format every browser writes: folders are `<H3>`
+//! headings followed by a `<DL>` list of `<A HREF>` links.
+
+use std::path::Path;
+
+use super::markup::{tokens, Token};
+use super::{fallback_name, has_extension, push_entry, Importer};
+use crate::scan::{self, ScanGroup};
+
+/// Group for links outside any folder.
+const TOP_LEVEL: &str = "Bookmarks";
+
+pub(crate) struct BookmarksImporter;
+
+impl Importer for BookmarksImporter {
+    fn id(&self) -> &'static str {
+        "bookmarks"
+    }
+
+    fn detect(&self, path: &Path, text: &str) -> bool {
+        text.contains("NETSCAPE-Bookmark-file")
+            || (has_extension(path, &["html", "htm"]) && text.to_lowercase().contains("<dl"))
+    }
+
+    /// Each folder becomes a group named by its path, e.g. `Bookmarks bar / Work`.
+    fn import(&self, text: &str) -> Result<Vec<ScanGroup>, String> {
+        let mut groups = Vec::new();
+        let mut folders: Vec<String> = Vec::new();
+        // Heading text waiting for the list it names
+        let mut heading: Option<String> = None;
+        let mut in_heading = false;
+        // How many folders were open when each open list started
+        let mut lists: Vec<usize> = Vec::new();
+        let mut link: Option<(String, String)> = None;
+
+        for token in tokens(text) {
+            match token {
+                Token::Start { name, attrs, .. } => match name.as_str() {
+                    "h3" => {
+                        in_heading = true;
+                        heading = Some(String::new());
+                    }
+                    "dl" => {
+                        lists.push(folders.len());
+                        folders.extend(heading.take().filter(|h| !h.is_empty()));
+                    }
+                    "a" => {
+                        let href = attrs
+                            .iter()
+                            .find(|(k, _)| k == "href")
+                            .map(|(_, v)| v.trim().to_string())
+                            .filter(|v| !v.is_empty() && !v.starts_with("javascript:"));
+                        link = href.map(|href| (href, String::new()));
+                    }
+                    _ => {}
+                },
+                Token::Text(text) => {
+                    if in_heading {
+                        heading.get_or_insert_with(String::new).push_str(&text);
+                    } else if let Some((_, title)) = link.as_mut() {
+                        title.push_str(&text);
+                    }
+                }
+                Token::End { name } => match name.as_str() {
+                    "h3" => in_heading = false,
+                    "dl" => folders.truncate(lists.pop().unwrap_or(0)),
+                    "a" => {
+                        if let Some((href, title)) = link.take() {
+                            let group = if folders.is_empty() {
+                                TOP_LEVEL.to_string()
+                            } else {
+                                folders.join(" / ")
+                            };
+                            let title = match title.trim() {
+                                "" => fallback_name(&href),
+                                t => t.to_string(),
+                            };
+                            push_entry(&mut groups, &group, scan::new_entry(title, href, None));
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+        Ok(groups)
+    }
+}
@@ -0,0 +1,103 @@
+//! INI configurations with a `[section]` per group. Items are either `Name=path` lines,
+//! optionally `Name=path|args`, or numbered keys such as `Name1=`, `Path1=`, `Args1=`.
+
+use std::path::Path;
+
+use super::{entry_from_fields, has_extension, Importer};
+use crate::scan::{self, ScanGroup};
+
+/// Sections holding the launcher's own settings rather than a group.
+const SETTINGS_SECTIONS: &[&str] = &["general", "settings", "config", "options", "window"];
+
+pub(crate) struct IniImporter;
+
+/// `Path3` as `("path", 3)`.
+fn numbered(key: &str) -> Option<(String, u32)> {
+    let digits = key.len() - key.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || digits == key.len() {
+        return None;
+    }
+    let (name, number) = key.split_at(key.len() - digits);
+    Some((name.to_lowercase(), number.parse().ok()?))
+}
+
+/// Whether a value looks like something to launch rather than a setting.
+fn looks_like_path(value: &str) -> bool {
+    let value = value.trim().trim_matches('"');
+    value.contains(['\\', '/']) || value.to_lowercase().ends_with(".exe")
+}
+
+fn import_section(name: &str, lines: &[(String, String)]) -> ScanGroup {
+    let mut apps = Vec::new();
+    let mut items: Vec<(u32, Vec<(String, String)>)> = Vec::new();
+    for (key, value) in lines {
+        if let Some((field, number)) = numbered(key) {
+            match items.iter_mut().find(|(n, _)| *n == number) {
+                Some((_, fields)) => fields.push((field, value.clone())),
+                None => items.push((number, vec![(field, value.clone())])),
+            }
+        } else if looks_like_path(value) {
+            let (path, args) = match value.split_once('|') {
+                Some((path, args)) => (path, Some(args.trim().to_string())),
+                None => (value.as_str(), None),
+            };
+            let path = path.trim().trim_matches('"').to_string();
+            apps.push(scan::new_entry(
+                key.clone(),
+                path,
+                args.filter(|a| !a.is_empty()),
+            ));
+        }
+    }
+    items.sort_by_key(|(n, _)| *n);
+    apps.extend(
+        items
+            .iter()
+            .filter_map(|(_, fields)| entry_from_fields(fields)),
+    );
+    ScanGroup {
+        name: name.to_string(),
+        group_id: None,
+        apps,
+    }
+}
+
+impl Importer for IniImporter {
+    fn id(&self) -> &'static str {
+        "ini"
+    }
+
+    fn detect(&self, path: &Path, text: &str) -> bool {
+        has_extension(path, &["ini", "cfg", "conf"]) || text.trim_start().starts_with('[')
+    }
+
+    fn import(&self, text: &str) -> Result<Vec<ScanGroup>, String> {
+        let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with([';', '#']) {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                sections.push((name.trim().to_string(), Vec::new()));
+                continue;
+            }
+            let (Some((_, lines)), Some((key, value))) =
+                (sections.last_mut(), line.split_once('='))
+            else {
+                continue;
+            };
+            lines.push((key.trim().to_string(), value.trim().to_string()));
+        }
+        let groups: Vec<ScanGroup> = sections
+            .iter()
+            .filter(|(name, _)| !SETTINGS_SECTIONS.contains(&name.to_lowercase().as_str()))
+            .map(|(name, lines)| import_section(name, lines))
+            .filter(|g| !g.apps.is_empty())
+            .collect();
+        if groups.is_empty() {
+            return Err("no items with a path found".to_string());
+        }
+        Ok(groups)
+    }
+}
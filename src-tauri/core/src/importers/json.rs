@@ -0,0 +1,129 @@
+//! JSON configurations: a list of groups, an object holding one under `groups`, `tabs`
+//! or `categories`, or an object mapping group names to item lists. Items are objects
+//! with a name and a path under any of the usual keys, or bare path strings.
+
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use super::{entry_from_fields, has_extension, pick, push_entry, Importer, NAME_KEYS, UNGROUPED};
+use crate::scan::ScanGroup;
+
+const GROUP_LIST_KEYS: &[&str] = &["groups", "tabs", "categories", "pages", "folders"];
+const ITEM_LIST_KEYS: &[&str] = &[
+    "apps",
+    "items",
+    "shortcuts",
+    "children",
+    "entries",
+    "links",
+    "launchers",
+];
+
+pub(crate) struct JsonImporter;
+
+/// The scalar fields of an object, as strings.
+fn fields(object: &Map<String, Value>) -> Vec<(String, String)> {
+    object
+        .iter()
+        .filter_map(|(k, v)| {
+            let value = match v {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            Some((k.clone(), value))
+        })
+        .collect()
+}
+
+fn list<'a>(object: &'a Map<String, Value>, keys: &[&str]) -> Option<&'a Vec<Value>> {
+    keys.iter().find_map(|key| {
+        object
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .and_then(|(_, v)| v.as_array())
+    })
+}
+
+/// Adds the items of `items` to `group`; nested groups get a `parent / child` name.
+fn import_items(groups: &mut Vec<ScanGroup>, group: &str, items: &[Value]) {
+    for item in items {
+        match item {
+            Value::String(path) => {
+                let fields = [("path".to_string(), path.clone())];
+                if let Some(entry) = entry_from_fields(&fields) {
+                    push_entry(groups, group, entry);
+                }
+            }
+            Value::Object(object) => {
+                let fields = fields(object);
+                if let Some(entry) = entry_from_fields(&fields) {
+                    push_entry(groups, group, entry);
+                } else if let Some(children) = list(object, ITEM_LIST_KEYS) {
+                    let name = pick(&fields, NAME_KEYS).unwrap_or(UNGROUPED);
+                    import_items(groups, &format!("{} / {}", group, name), children);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn import_group(groups: &mut Vec<ScanGroup>, group: &Value) {
+    let Value::Object(object) = group else {
+        return;
+    };
+    let name = pick(&fields(object), NAME_KEYS)
+        .unwrap_or(UNGROUPED)
+        .to_string();
+    if let Some(items) = list(object, ITEM_LIST_KEYS) {
+        import_items(groups, &name, items);
+    }
+}
+
+impl Importer for JsonImporter {
+    fn id(&self) -> &'static str {
+        "json"
+    }
+
+    /// An INI file starts with `[` too, so without the extension the text has to parse.
+    fn detect(&self, path: &Path, text: &str) -> bool {
+        has_extension(path, &["json"])
+            || (text.trim_start().starts_with(['{', '['])
+                && serde_json::from_str::<Value>(text).is_ok())
+    }
+
+    fn import(&self, text: &str) -> Result<Vec<ScanGroup>, String> {
+        let root: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let mut groups = Vec::new();
+        match &root {
+            Value::Array(list) => {
+                for group in list {
+                    import_group(&mut groups, group);
+                }
+            }
+            Value::Object(object) => {
+                if let Some(list) = list(object, GROUP_LIST_KEYS) {
+                    for group in list {
+                        import_group(&mut groups, group);
+                    }
+                } else if list(object, ITEM_LIST_KEYS).is_some() {
+                    import_group(&mut groups, &root);
+                } else {
+                    for (name, items) in object {
+                        if let Value::Array(items) = items {
+                            import_items(&mut groups, name, items);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        if groups.is_empty() {
+            return Err("no items with a path found".to_string());
+        }
+        Ok(groups)
+    }
+}
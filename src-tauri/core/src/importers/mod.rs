@@ -0,0 +1,286 @@
+//! Turns other launchers' configuration files and browser bookmark exports into groups
+//! of apps. Each format is an [`Importer`]; adding one to [`importers`] is all a new
+//! format needs.
+
+use std::path::Path;
+
+use crate::model::{AppEntry, LauncherState};
+use crate::scan::{self, ScanGroup, ScanPreview};
+
+mod bookmarks;
+mod ini;
+mod json;
+mod markup;
+mod xml;
+
+/// One configuration format.
+pub trait Importer {
+    /// Short name to pick the importer by, e.g. `ini`.
+    fn id(&self) -> &'static str;
+    /// Whether `text`, read from `path`, looks like this format.
+    fn detect(&self, path: &Path, text: &str) -> bool;
+    /// The groups and apps `text` describes.
+    fn import(&self, text: &str) -> Result<Vec<ScanGroup>, String>;
+}
+
+/// Every importer, most specific first, since detection stops at the first match.
+pub fn importers() -> Vec<Box<dyn Importer>> {
+    vec![
+        Box::new(bookmarks::BookmarksImporter),
+        Box::new(xml::XmlImporter),
+        Box::new(json::JsonImporter),
+        Box::new(ini::IniImporter),
+    ]
+}
+
+/// Reads `path` with the importer named by `format`, or the first one that recognizes
+/// it, and previews adding the result to `state`.
+pub fn import_file(
+    path: &Path,
+    format: Option<&str>,
+    state: Option<&LauncherState>,
+) -> Result<ScanPreview, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let text = decode(&bytes);
+    let importer = match format.map(str::trim).filter(|f| !f.is_empty()) {
+        Some(format) => importers()
+            .into_iter()
+            .find(|i| i.id().eq_ignore_ascii_case(format))
+            .ok_or_else(|| format!("unknown import format: {}", format))?,
+        None => importers()
+            .into_iter()
+            .find(|i| i.detect(path, &text))
+            .ok_or_else(|| format!("unrecognized file: {}", path.display()))?,
+    };
+    let groups = importer.import(&text)?;
+    Ok(scan::preview(
+        path.to_string_lossy().to_string(),
+        groups,
+        state,
+    ))
+}
+
+/// Configuration files come as UTF-8, with or without a BOM, or as UTF-16 from Windows
+/// tools.
+fn decode(bytes: &[u8]) -> String {
+    let utf16 = |bytes: &[u8], le: bool| {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| {
+                if le {
+                    u16::from_le_bytes([c[0], c[1]])
+                } else {
+                    u16::from_be_bytes([c[0], c[1]])
+                }
+            })
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest).to_string(),
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, true),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, false),
+        _ => String::from_utf8_lossy(bytes).to_string(),
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| extensions.contains(&e.as_str()))
+}
+
+/// Field names other launchers use for the same thing.
+const NAME_KEYS: &[&str] = &["name", "title", "label", "caption", "text"];
+const PATH_KEYS: &[&str] = &[
+    "path", "target", "file", "filename", "command", "exe", "url", "href", "location",
+];
+const ARGS_KEYS: &[&str] = &["args", "arguments", "params", "parameters", "param"];
+
+fn pick<'a>(fields: &'a [(String, String)], keys: &[&str]) -> Option<&'a str> {
+    keys.iter().find_map(|key| {
+        fields
+            .iter()
+            .find(|(k, v)| k.eq_ignore_ascii_case(key) && !v.trim().is_empty())
+            .map(|(_, v)| v.trim())
+    })
+}
+
+/// An entry from an item's fields, named after its file when the item has no name.
+fn entry_from_fields(fields: &[(String, String)]) -> Option<AppEntry> {
+    let path = pick(fields, PATH_KEYS)?.trim_matches('"').to_string();
+    let name = pick(fields, NAME_KEYS)
+        .map(str::to_string)
+        .unwrap_or_else(|| fallback_name(&path));
+    let args = pick(fields, ARGS_KEYS).map(str::to_string);
+    Some(scan::new_entry(name, path, args))
+}
+
+/// The file name without extension, or the host of a URL.
+fn fallback_name(path: &str) -> String {
+    if let Some((_, rest)) = path.split_once("://") {
+        return rest.split('/').next().unwrap_or(rest).to_string();
+    }
+    let file = path.rsplit(['\\', '/']).next().unwrap_or(path);
+    match file.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem.to_string(),
+        _ => file.to_string(),
+    }
+}
+
+/// Adds `entry` to the group called `name`, creating it at the end.
+fn push_entry(groups: &mut Vec<ScanGroup>, name: &str, entry: AppEntry) {
+    match groups.iter_mut().find(|g| g.name == name) {
+        Some(group) => group.apps.push(entry),
+        None => groups.push(ScanGroup {
+            name: name.to_string(),
+            group_id: None,
+            apps: vec![entry],
+        }),
+    }
+}
+
+/// Group name for items a file doesn't put into any group.
+const UNGROUPED: &str = "Imported";
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::storage::tests::TempDir;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/import")
+            .join(name)
+    }
+
+    fn import_fixture(importer: &dyn Importer, name: &str) -> Vec<ScanGroup> {
+        let text = decode(&std::fs::read(fixture(name)).unwrap());
+        importer.import(&text).unwrap()
+    }
+
+    /// Name, path and arguments of an app.
+    type Item<'a> = (&'a str, &'a str, Option<&'a str>);
+
+    /// Group names with the items of each group.
+    fn summary(groups: &[ScanGroup]) -> Vec<(&str, Vec<Item<'_>>)> {
+        groups
+            .iter()
+            .map(|g| {
+                let apps = g
+                    .apps
+                    .iter()
+                    .map(|a| (a.name.as_str(), a.path.as_str(), a.args.as_deref()))
+                    .collect();
+                (g.name.as_str(), apps)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bookmarks_folders_become_groups() {
+        let groups = import_fixture(&bookmarks::BookmarksImporter, "bookmarks.html");
+        assert_eq!(
+            summary(&groups),
+            [
+                (
+                    "Bookmarks bar",
+                    vec![("Mail", "https://example.com/mail", None)]
+                ),
+                (
+                    "Bookmarks bar / Work",
+                    vec![("Tracker & Board", "https://tracker.example.com/", None)]
+                ),
+                (
+                    "Bookmarks",
+                    vec![("news.example.org", "https://news.example.org/", None)]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn xml_items_take_attributes_or_child_elements() {
+        let groups = import_fixture(&xml::XmlImporter, "launcher.xml");
+        assert_eq!(
+            summary(&groups),
+            [
+                (
+                    "Dev",
+                    vec![("Editor", "C:\\Tools\\editor.exe", Some("--new-window"))]
+                ),
+                (
+                    "Dev / Tools",
+                    vec![("Terminal", "C:\\Tools\\term.exe", None)]
+                ),
+                ("Imported", vec![("game", "C:\\Games\\game.exe", None)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_groups_hold_objects_strings_and_nested_lists() {
+        let groups = import_fixture(&json::JsonImporter, "launcher.json");
+        assert_eq!(
+            summary(&groups),
+            [
+                (
+                    "Office",
+                    vec![
+                        ("Writer", "C:\\Office\\writer.exe", Some("-n")),
+                        ("calc", "C:\\Office\\calc.exe", None),
+                    ]
+                ),
+                (
+                    "Office / Templates",
+                    vec![("Letter", "C:\\Docs\\letter.dotx", None)]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn utf16_ini_sections_skip_settings() {
+        let groups = import_fixture(&ini::IniImporter, "launcher.ini");
+        assert_eq!(
+            summary(&groups),
+            [(
+                "Tools",
+                vec![
+                    (
+                        "Editor",
+                        "C:\\Program Files\\Editor\\editor.exe",
+                        Some("--reuse")
+                    ),
+                    ("Paint", "C:\\Windows\\mspaint.exe", None),
+                    ("Shell", "C:\\Windows\\System32\\cmd.exe", Some("/k cd \\")),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn import_file_detects_each_format() {
+        let dir = TempDir::new("import-detect");
+        let cases = [
+            ("bookmarks.html", "Bookmarks bar"),
+            ("launcher.xml", "Dev"),
+            ("launcher.json", "Office"),
+            ("launcher.ini", "Tools"),
+        ];
+        for (name, first_group) in cases {
+            let preview = import_file(&fixture(name), None, None).unwrap();
+            assert_eq!(preview.groups[0].name, first_group, "{}", name);
+
+            // Without a telling extension, the content alone decides
+            let renamed = dir.0.join(format!("{}.txt", name));
+            std::fs::copy(fixture(name), &renamed).unwrap();
+            let preview = import_file(&renamed, None, None).unwrap();
+            assert_eq!(preview.groups[0].name, first_group, "{} as .txt", name);
+        }
+        let err = import_file(&fixture("launcher.json"), Some("csv"), None).unwrap_err();
+        assert!(err.contains("unknown import format"), "{}", err);
+    }
+}
@@ -0,0 +1,109 @@
+//! XML configurations that nest items in groups. An item is any element with a path,
+//! given as an attribute (`<item name="Editor" path="C:\ed.exe"/>`) or as child
+//! elements (`<item><name>Editor</name><path>C:\ed.exe</path></item>`). Enclosing
+//! elements with a name make up its group, e.g. `Dev / Tools`.
+
+use std::path::Path;
+
+use super::markup::{tokens, Token};
+use super::{entry_from_fields, has_extension, pick, push_entry, Importer, NAME_KEYS, UNGROUPED};
+use crate::scan::ScanGroup;
+
+pub(crate) struct XmlImporter;
+
+struct Element {
+    name: String,
+    /// Attributes, then the text of child elements that only hold text.
+    fields: Vec<(String, String)>,
+    text: String,
+    has_children: bool,
+}
+
+/// Closes the innermost open element: an item goes into its group, a leaf becomes a field
+/// of its parent.
+fn close(stack: &mut Vec<Element>, groups: &mut Vec<ScanGroup>) {
+    let Some(element) = stack.pop() else {
+        return;
+    };
+    if let Some(entry) = entry_from_fields(&element.fields) {
+        let names: Vec<&str> = stack
+            .iter()
+            .filter_map(|e| pick(&e.fields, NAME_KEYS))
+            .collect();
+        let group = match names.is_empty() {
+            true => UNGROUPED.to_string(),
+            false => names.join(" / "),
+        };
+        push_entry(groups, &group, entry);
+        return;
+    }
+    if !element.has_children {
+        if let Some(parent) = stack.last_mut() {
+            parent
+                .fields
+                .push((element.name, element.text.trim().to_string()));
+        }
+    }
+}
+
+impl Importer for XmlImporter {
+    fn id(&self) -> &'static str {
+        "xml"
+    }
+
+    fn detect(&self, path: &Path, text: &str) -> bool {
+        has_extension(path, &["xml"]) || text.trim_start().starts_with("<?xml")
+    }
+
+    fn import(&self, text: &str) -> Result<Vec<ScanGroup>, String> {
+        let mut groups = Vec::new();
+        let mut stack: Vec<Element> = Vec::new();
+
+        for token in tokens(text) {
+            match token {
+                Token::Start {
+                    name,
+                    attrs,
+                    self_closing,
+                } => {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.has_children = true;
+                    }
+                    stack.push(Element {
+                        name,
+                        fields: attrs,
+                        text: String::new(),
+                        has_children: false,
+                    });
+                    if self_closing {
+                        close(&mut stack, &mut groups);
+                    }
+                }
+                Token::Text(text) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&text);
+                    }
+                }
+                Token::End { name } => {
+                    // Unbalanced tags close everything opened since the match
+                    if stack.iter().any(|e| e.name == name) {
+                        while let Some(top) = stack.last() {
+                            let done = top.name == name;
+                            close(&mut stack, &mut groups);
+                            if done {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        while !stack.is_empty() {
+            close(&mut stack, &mut groups);
+        }
+        if groups.is_empty() {
+            return Err("no items with a path found".to_string());
+        }
+        Ok(groups)
+    }
+}
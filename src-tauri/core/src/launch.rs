@@ -0,0 +1,186 @@
+use crate::model::{AppEntry, EntryKind};
+use crate::paths;
+
+const UWP_PREFIX: &str = "uwp:";
+
+/// Splits an argument string the same way the frontend's `parseArgs` does:
+/// whitespace separates, double quotes group, and a backslash escapes the next char.
+pub fn parse_args(args: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut escaping = false;
+
+    let mut push = |current: &mut String| {
+        let t = current.trim();
+        if !t.is_empty() {
+            result.push(t.to_string());
+        }
+        current.clear();
+    };
+
+    for ch in args.chars() {
+        if escaping {
+            current.push(ch);
+            escaping = false;
+            continue;
+        }
+        if ch == '\\' {
+            escaping = true;
+            continue;
+        }
+        if ch == '"' {
+            in_quotes = !in_quotes;
+            continue;
+        }
+        if !in_quotes && ch.is_whitespace() {
+            push(&mut current);
+            continue;
+        }
+        current.push(ch);
+    }
+    push(&mut current);
+    result
+}
+
+pub fn spawn_path(path: &str, args: Vec<String>) -> Result<(), String> {
+    spawn_path_in(path, args, None)
+}
+
+/// [`spawn_path`], started in `working_dir` when one is given. A program that needs its
+/// own working folder is started directly rather than handed to Explorer.
+pub fn spawn_path_in(
+    path: &str,
+    args: Vec<String>,
+    working_dir: Option<&str>,
+) -> Result<(), String> {
+    let resolved_path = paths::resolve_launch_path(path);
+    let working_dir = working_dir
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(paths::resolve_launch_path);
+    if let Some(dir) = working_dir {
+        return std::process::Command::new(resolved_path)
+            .args(args)
+            .current_dir(dir)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| e.to_string());
+    }
+    if args.is_empty() {
+        #[cfg(target_os = "windows")]
+        {
+            std::process::Command::new("explorer")
+                .arg(resolved_path)
+                .spawn()
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            std::process::Command::new(resolved_path)
+                .spawn()
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+    } else {
+        std::process::Command::new(resolved_path)
+            .args(args)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Opens a folder, document or URL with whatever the system opens it with.
+pub fn open_with_default(target: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut command = std::process::Command::new("explorer");
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = std::process::Command::new("xdg-open");
+    command
+        .arg(target)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Opens `url` in `browser`, a program path or name, or in the default browser.
+pub fn open_url(url: &str, browser: Option<&str>) -> Result<(), String> {
+    match browser.map(str::trim).filter(|b| !b.is_empty()) {
+        Some(browser) => std::process::Command::new(paths::resolve_program(browser))
+            .arg(url.trim())
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        None => open_with_default(url.trim()),
+    }
+}
+
+/// Runs `line` through the system shell.
+pub fn run_command(line: &str) -> Result<(), String> {
+    if line.trim().is_empty() {
+        return Err("empty command".to_string());
+    }
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.arg("/C").arg(line);
+        command
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut command = {
+        let mut command = std::process::Command::new("sh");
+        command.arg("-c").arg(line);
+        command
+    };
+    command.spawn().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Launches a stored entry the way a click on its card does.
+pub fn launch_entry(entry: &AppEntry) -> Result<(), String> {
+    let path = entry.path.trim();
+    let args = entry.args.as_deref().unwrap_or("").trim();
+    match entry.resolved_kind() {
+        EntryKind::Uwp => {
+            let app_id = match path.get(..UWP_PREFIX.len()) {
+                Some(prefix) if prefix.eq_ignore_ascii_case(UWP_PREFIX) => {
+                    &path[UWP_PREFIX.len()..]
+                }
+                _ => path,
+            };
+            spawn_uwp_app(app_id)
+        }
+        EntryKind::Url => open_url(path, entry.browser.as_deref()),
+        EntryKind::Folder | EntryKind::Document if args.is_empty() => {
+            open_with_default(&paths::resolve_launch_path(path))
+        }
+        EntryKind::Command if args.is_empty() => run_command(path),
+        EntryKind::Command => run_command(&format!("{} {}", path, args)),
+        _ => spawn_path_in(path, parse_args(args), entry.working_dir.as_deref()),
+    }
+}
+
+/// Starts a Store app by its AppUserModelID through the shell's AppsFolder.
+pub fn spawn_uwp_app(app_id: &str) -> Result<(), String> {
+    let aumid = app_id.trim();
+    if aumid.is_empty() {
+        return Err("empty app id".to_string());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("UWP is only supported on Windows".to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("explorer.exe")
+            .arg(format!("shell:AppsFolder\\{}", aumid))
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,15 @@
+//! Launcher data and behavior without any UI: the saved model, the SQLite store, where the
+//! data lives, path resolution, starting apps and reading Windows shortcuts. The Tauri app
+//! and its command-line interface are both built on this crate.
+
+pub mod data_dir;
+pub mod history;
+pub mod importers;
+pub mod launch;
+pub mod lnk;
+pub mod model;
+pub mod paths;
+pub mod profiles;
+pub mod scan;
+pub mod storage;
+pub mod sync;
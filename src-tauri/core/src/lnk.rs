@@ -0,0 +1,447 @@
+//! Reads Windows shell links (`.lnk`, MS-SHLLINK) byte by byte, without COM, so
+//! shortcuts can be resolved on any platform.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::model::{create_id, now_millis, AppEntry, EntryKind};
+
+const HEADER_SIZE: u32 = 0x4C;
+const LINK_CLSID: [u8; 16] = [
+    0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x1;
+const HAS_LINK_INFO: u32 = 0x2;
+const HAS_NAME: u32 = 0x4;
+const HAS_RELATIVE_PATH: u32 = 0x8;
+const HAS_WORKING_DIR: u32 = 0x10;
+const HAS_ARGUMENTS: u32 = 0x20;
+const HAS_ICON_LOCATION: u32 = 0x40;
+const IS_UNICODE: u32 = 0x80;
+
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x1;
+const COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX: u32 = 0x2;
+
+const ENVIRONMENT_BLOCK: u32 = 0xA000_0001;
+const ICON_ENVIRONMENT_BLOCK: u32 = 0xA000_0007;
+/// Both environment blocks hold a 260-byte ANSI and a 520-byte UTF-16 path.
+const ENVIRONMENT_BLOCK_SIZE: usize = 0x314;
+
+/// What a shortcut points at. Paths are as stored, with `%VAR%` references left in.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Shortcut {
+    /// Absolute target from the link info: a local path or `\\server\share\...`.
+    pub target: Option<String>,
+    /// Target from the environment-variable block, e.g. `%windir%\notepad.exe`.
+    pub env_target: Option<String>,
+    /// Target relative to the shortcut's folder.
+    pub relative_path: Option<String>,
+    pub arguments: Option<String>,
+    pub working_dir: Option<String>,
+    pub description: Option<String>,
+    pub icon_location: Option<String>,
+    pub icon_index: i32,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("shortcut ends early at byte {}", self.pos))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// One StringData entry: a character count, then UTF-16 or ANSI characters.
+    fn string_data(&mut self, unicode: bool) -> Result<String, String> {
+        let count = self.u16()? as usize;
+        if unicode {
+            Ok(utf16(self.take(count * 2)?))
+        } else {
+            Ok(ansi(self.take(count)?))
+        }
+    }
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    let b = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// The code page isn't stored; UTF-8 is tried first, then bytes are taken as Latin-1.
+fn ansi(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => bytes.iter().map(|b| *b as char).collect(),
+    }
+}
+
+fn utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+fn ansi_z(bytes: &[u8], offset: usize) -> Option<String> {
+    let rest = bytes.get(offset..)?;
+    let end = rest.iter().position(|b| *b == 0).unwrap_or(rest.len());
+    Some(ansi(&rest[..end]))
+}
+
+fn utf16_z(bytes: &[u8], offset: usize) -> Option<String> {
+    let rest = bytes.get(offset..)?;
+    let end = rest
+        .chunks_exact(2)
+        .position(|c| c == [0, 0])
+        .unwrap_or(rest.len() / 2);
+    Some(utf16(&rest[..end * 2]))
+}
+
+fn non_empty(s: Option<String>) -> Option<String> {
+    s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn join_suffix(base: String, suffix: Option<String>) -> String {
+    match suffix.filter(|s| !s.is_empty()) {
+        Some(suffix) if base.ends_with('\\') => base + &suffix,
+        Some(suffix) => format!("{}\\{}", base, suffix),
+        None => base,
+    }
+}
+
+/// The target stored in a LinkInfo structure, local or on a network share.
+fn link_info_target(info: &[u8]) -> Option<String> {
+    let header_size = u32_at(info, 4)?;
+    let flags = u32_at(info, 8)?;
+    let has_unicode = header_size >= 0x24;
+    let suffix = if has_unicode {
+        u32_at(info, 0x20).and_then(|o| utf16_z(info, o as usize))
+    } else {
+        u32_at(info, 0x18).and_then(|o| ansi_z(info, o as usize))
+    };
+
+    if flags & VOLUME_ID_AND_LOCAL_BASE_PATH != 0 {
+        let base = if has_unicode {
+            u32_at(info, 0x1C).and_then(|o| utf16_z(info, o as usize))
+        } else {
+            None
+        };
+        let base = non_empty(base)
+            .or_else(|| non_empty(u32_at(info, 0x10).and_then(|o| ansi_z(info, o as usize))));
+        if let Some(base) = base {
+            return Some(join_suffix(base, suffix));
+        }
+    }
+
+    if flags & COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX != 0 {
+        let link = info.get(u32_at(info, 0x14)? as usize..)?;
+        let net_name_offset = u32_at(link, 8)?;
+        let net_name = if net_name_offset > 0x14 {
+            u32_at(link, 0x14).and_then(|o| utf16_z(link, o as usize))
+        } else {
+            None
+        };
+        let net_name =
+            non_empty(net_name).or_else(|| non_empty(ansi_z(link, net_name_offset as usize)))?;
+        return Some(join_suffix(net_name, suffix));
+    }
+    None
+}
+
+/// The path in an environment-variable or icon-environment block, preferring UTF-16.
+fn environment_path(block: &[u8]) -> Option<String> {
+    if block.len() < ENVIRONMENT_BLOCK_SIZE {
+        return None;
+    }
+    non_empty(utf16_z(&block[8 + 260..ENVIRONMENT_BLOCK_SIZE], 0))
+        .or_else(|| non_empty(ansi_z(&block[8..8 + 260], 0)))
+}
+
+/// Parses the bytes of a `.lnk` file.
+pub fn parse(bytes: &[u8]) -> Result<Shortcut, String> {
+    let mut r = Reader { bytes, pos: 0 };
+    if r.u32()? != HEADER_SIZE || r.take(16)? != LINK_CLSID {
+        return Err("not a Windows shortcut".to_string());
+    }
+    let flags = r.u32()?;
+    r.pos = 0x38;
+    let icon_index = r.u32()? as i32;
+    r.pos = HEADER_SIZE as usize;
+
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        let len = r.u16()? as usize;
+        r.take(len)?;
+    }
+
+    let mut shortcut = Shortcut {
+        icon_index,
+        ..Shortcut::default()
+    };
+    if flags & HAS_LINK_INFO != 0 {
+        let start = r.pos;
+        let len = r.u32()? as usize;
+        r.pos = start;
+        let info = r.take(len.max(4))?;
+        shortcut.target = link_info_target(info);
+    }
+
+    let unicode = flags & IS_UNICODE != 0;
+    if flags & HAS_NAME != 0 {
+        shortcut.description = non_empty(Some(r.string_data(unicode)?));
+    }
+    if flags & HAS_RELATIVE_PATH != 0 {
+        shortcut.relative_path = non_empty(Some(r.string_data(unicode)?));
+    }
+    if flags & HAS_WORKING_DIR != 0 {
+        shortcut.working_dir = non_empty(Some(r.string_data(unicode)?));
+    }
+    if flags & HAS_ARGUMENTS != 0 {
+        shortcut.arguments = non_empty(Some(r.string_data(unicode)?));
+    }
+    if flags & HAS_ICON_LOCATION != 0 {
+        shortcut.icon_location = non_empty(Some(r.string_data(unicode)?));
+    }
+
+    // Extra data blocks follow until one shorter than 4 bytes ends the list
+    while let Ok(size) = r.u32() {
+        let size = size as usize;
+        if size < 8 {
+            break;
+        }
+        r.pos -= 4;
+        let Ok(block) = r.take(size) else {
+            break;
+        };
+        match u32_at(block, 4) {
+            Some(ENVIRONMENT_BLOCK) => shortcut.env_target = environment_path(block),
+            Some(ICON_ENVIRONMENT_BLOCK) => {
+                if let Some(icon) = environment_path(block) {
+                    shortcut.icon_location = Some(icon);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(shortcut)
+}
+
+/// Reads and parses a `.lnk` file.
+pub fn read(path: &Path) -> Result<Shortcut, String> {
+    parse(&std::fs::read(path).map_err(|e| e.to_string())?)
+}
+
+/// Replaces `%NAME%` with the variable's value. Unknown variables are left as written.
+pub fn expand_env(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('%') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        match std::env::var(&after[..end]) {
+            Ok(v) if end > 0 => out.push_str(&v),
+            _ => out.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+impl Shortcut {
+    /// Where the shortcut leads, with variables expanded. The environment block wins
+    /// because it still holds when the link moved to another machine; a relative path
+    /// is resolved against `lnk_dir`.
+    pub fn resolved_target(&self, lnk_dir: Option<&Path>) -> Option<String> {
+        if let Some(env) = &self.env_target {
+            return Some(expand_env(env));
+        }
+        if let Some(target) = &self.target {
+            return Some(expand_env(target));
+        }
+        let relative = expand_env(self.relative_path.as_deref()?);
+        Some(match lnk_dir {
+            Some(dir) => dir
+                .join(relative.replace('\\', std::path::MAIN_SEPARATOR_STR))
+                .to_string_lossy()
+                .to_string(),
+            None => relative,
+        })
+    }
+
+    /// Where the icon comes from, as `file,index` when the index isn't 0. A location that
+    /// is just the target itself adds nothing, so it is left out.
+    pub fn resolved_icon_location(&self, target: Option<&str>) -> Option<String> {
+        let file = expand_env(self.icon_location.as_deref()?);
+        match self.icon_index {
+            0 if target.is_some_and(|t| t.eq_ignore_ascii_case(&file)) => None,
+            0 => Some(file),
+            index => Some(format!("{},{}", file, index)),
+        }
+    }
+
+    /// An entry that launches the shortcut's target directly, named after the `.lnk`
+    /// file, with the link's working folder, comment and icon. Links without a file
+    /// target, e.g. to Control Panel items, keep the `.lnk`, which starts in its own folder.
+    pub fn to_app_entry(&self, lnk_path: &Path) -> AppEntry {
+        let name = lnk_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .or_else(|| self.description.clone())
+            .unwrap_or_default();
+        let target = self.resolved_target(lnk_path.parent());
+        let icon_location = self.resolved_icon_location(target.as_deref());
+        let (path, args, working_dir) = match target {
+            Some(target) => (
+                target,
+                self.arguments.clone(),
+                self.working_dir.as_deref().map(expand_env),
+            ),
+            None => (lnk_path.to_string_lossy().to_string(), None, None),
+        };
+        let now = now_millis();
+        AppEntry {
+            id: create_id(),
+            name,
+            kind: Some(EntryKind::infer(&path)),
+            path,
+            args,
+            icon: None,
+            icon_hash: None,
+            pinned: false,
+            added_at: now,
+            updated_at: now,
+            browser: None,
+            working_dir,
+            description: self.description.clone(),
+            icon_location,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/lnk")
+            .join(name);
+        std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+    }
+
+    #[test]
+    fn local_target_with_unicode_strings() {
+        let link = parse(&fixture("local.lnk")).unwrap();
+        assert_eq!(
+            link.target.as_deref(),
+            Some("C:\\Program Files\\Ünïcode App\\app.exe")
+        );
+        assert_eq!(link.arguments.as_deref(), Some("--profile \"work\""));
+        assert_eq!(
+            link.working_dir.as_deref(),
+            Some("C:\\Program Files\\Ünïcode App")
+        );
+        assert_eq!(link.description.as_deref(), Some("Starts the app"));
+        assert_eq!(
+            link.icon_location.as_deref(),
+            Some("C:\\Program Files\\Ünïcode App\\app.ico")
+        );
+        assert_eq!(link.icon_index, 2);
+    }
+
+    #[test]
+    fn unc_target_joins_share_and_suffix() {
+        let link = parse(&fixture("unc.lnk")).unwrap();
+        assert_eq!(
+            link.target.as_deref(),
+            Some("\\\\server\\share\\tools\\tool.exe")
+        );
+        assert_eq!(link.arguments.as_deref(), Some("/quiet"));
+    }
+
+    #[test]
+    fn relative_target_resolves_against_the_link_folder() {
+        let link = parse(&fixture("relative.lnk")).unwrap();
+        assert_eq!(link.target, None);
+        assert_eq!(link.relative_path.as_deref(), Some("..\\bin\\app.exe"));
+        let dir = Path::new("links");
+        let expected = dir
+            .join(["..", "bin", "app.exe"].join(std::path::MAIN_SEPARATOR_STR))
+            .to_string_lossy()
+            .to_string();
+        assert_eq!(link.resolved_target(Some(dir)), Some(expected));
+    }
+
+    #[test]
+    fn environment_blocks_win_over_stored_paths() {
+        let link = parse(&fixture("env-block.lnk")).unwrap();
+        assert_eq!(link.target.as_deref(), Some("C:\\Windows\\notepad.exe"));
+        assert_eq!(link.env_target.as_deref(), Some("%windir%\\notepad.exe"));
+        assert_eq!(
+            link.icon_location.as_deref(),
+            Some("%SystemRoot%\\System32\\imageres.dll")
+        );
+        assert_eq!(link.icon_index, -101);
+    }
+
+    #[test]
+    fn truncated_link_is_an_error() {
+        let err = parse(&fixture("truncated.lnk")).unwrap_err();
+        assert!(err.contains("ends early"), "{}", err);
+        assert!(parse(b"not a link").is_err());
+    }
+
+    #[test]
+    fn app_entry_keeps_working_dir_description_and_icon() {
+        let link = parse(&fixture("local.lnk")).unwrap();
+        let entry = link.to_app_entry(Path::new("Start Menu/My App.lnk"));
+        assert_eq!(entry.name, "My App");
+        assert_eq!(entry.path, "C:\\Program Files\\Ünïcode App\\app.exe");
+        assert_eq!(entry.args.as_deref(), Some("--profile \"work\""));
+        assert_eq!(
+            entry.working_dir.as_deref(),
+            Some("C:\\Program Files\\Ünïcode App")
+        );
+        assert_eq!(entry.description.as_deref(), Some("Starts the app"));
+        assert_eq!(
+            entry.icon_location.as_deref(),
+            Some("C:\\Program Files\\Ünïcode App\\app.ico,2")
+        );
+
+        // An icon that is just the target at index 0 adds nothing
+        let link = Shortcut {
+            target: Some("C:\\Tools\\tool.exe".to_string()),
+            icon_location: Some("C:\\Tools\\TOOL.EXE".to_string()),
+            ..Shortcut::default()
+        };
+        let entry = link.to_app_entry(Path::new("tool.lnk"));
+        assert_eq!(entry.icon_location, None);
+    }
+}
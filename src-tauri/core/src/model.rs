@@ -0,0 +1,441 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LauncherState {
+    pub version: u8,
+    #[serde(rename = "activeGroupId")]
+    pub active_group_id: String,
+    pub groups: Vec<Group>,
+    #[serde(default)]
+    pub settings: UiSettings,
+    /// The revision this state was read at. A write is only accepted while the database
+    /// is still at that revision.
+    #[serde(default)]
+    pub revision: u64,
+}
+
+fn default_card_size() -> u32 {
+    120
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiSettings {
+    #[serde(rename = "language", default = "default_language")]
+    pub language: String,
+    #[serde(rename = "cardWidth", alias = "cardSize", default = "default_card_size")]
+    pub card_width: u32,
+    #[serde(rename = "cardHeight", default = "default_card_height")]
+    pub card_height: u32,
+    #[serde(rename = "toggleHotkey", default)]
+    pub toggle_hotkey: String,
+    #[serde(rename = "theme", default = "default_theme")]
+    pub theme: String,
+    #[serde(rename = "sidebarWidth", default = "default_sidebar_width")]
+    pub sidebar_width: u32,
+    #[serde(rename = "fontFamily", default = "default_font_family")]
+    pub font_family: String,
+    #[serde(rename = "fontSize", default = "default_font_size")]
+    pub font_size: u32,
+    #[serde(rename = "cardFontSize", default = "default_card_font_size")]
+    pub card_font_size: u32,
+    #[serde(rename = "cardIconScale", default = "default_card_icon_scale")]
+    pub card_icon_scale: u32,
+    #[serde(
+        rename = "dblClickBlankToHide",
+        default = "default_dbl_click_blank_to_hide"
+    )]
+    pub dbl_click_blank_to_hide: bool,
+    #[serde(rename = "alwaysOnTop", default = "default_always_on_top")]
+    pub always_on_top: bool,
+    #[serde(rename = "hideOnStartup", default = "default_hide_on_startup")]
+    pub hide_on_startup: bool,
+    #[serde(rename = "useRelativePath", default = "default_use_relative_path")]
+    pub use_relative_path: bool,
+    #[serde(
+        rename = "enableGroupDragSort",
+        default = "default_enable_group_drag_sort"
+    )]
+    pub enable_group_drag_sort: bool,
+    #[serde(rename = "autoStart", default)]
+    pub auto_start: bool,
+    #[serde(rename = "doubleTapMs", default = "default_double_tap_ms")]
+    pub double_tap_ms: u32,
+    #[serde(rename = "holdPeekMs", default = "default_hold_peek_ms")]
+    pub hold_peek_ms: u32,
+    /// Where the window appears when shown: `last`, `center`, `cursor` or `edge`.
+    #[serde(rename = "showPosition", default = "default_show_position")]
+    pub show_position: String,
+    #[serde(rename = "hideOnBlur", default)]
+    pub hide_on_blur: bool,
+    #[serde(rename = "hideAfterLaunch", default)]
+    pub hide_after_launch: bool,
+    /// Folder the library is mirrored to and imported from; empty when sync is off.
+    #[serde(rename = "syncDir", default)]
+    pub sync_dir: String,
+}
+
+fn default_language() -> String {
+    String::new()
+}
+
+fn default_card_height() -> u32 {
+    96
+}
+
+fn default_theme() -> String {
+    "dark".to_string()
+}
+
+fn default_sidebar_width() -> u32 {
+    140
+}
+
+fn default_font_family() -> String {
+    "maye".to_string()
+}
+
+fn default_font_size() -> u32 {
+    13
+}
+
+fn default_card_font_size() -> u32 {
+    11
+}
+
+fn default_card_icon_scale() -> u32 {
+    56
+}
+
+fn default_dbl_click_blank_to_hide() -> bool {
+    true
+}
+
+fn default_always_on_top() -> bool {
+    true
+}
+
+fn default_hide_on_startup() -> bool {
+    false
+}
+
+fn default_use_relative_path() -> bool {
+    false
+}
+
+fn default_enable_group_drag_sort() -> bool {
+    false
+}
+
+fn default_double_tap_ms() -> u32 {
+    300
+}
+
+fn default_hold_peek_ms() -> u32 {
+    350
+}
+
+fn default_show_position() -> String {
+    "last".to_string()
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            language: default_language(),
+            card_width: default_card_size(),
+            card_height: default_card_height(),
+            toggle_hotkey: String::new(),
+            theme: default_theme(),
+            sidebar_width: default_sidebar_width(),
+            font_family: default_font_family(),
+            font_size: default_font_size(),
+            card_font_size: default_card_font_size(),
+            card_icon_scale: default_card_icon_scale(),
+            dbl_click_blank_to_hide: default_dbl_click_blank_to_hide(),
+            always_on_top: default_always_on_top(),
+            hide_on_startup: default_hide_on_startup(),
+            use_relative_path: default_use_relative_path(),
+            enable_group_drag_sort: default_enable_group_drag_sort(),
+            auto_start: false,
+            double_tap_ms: default_double_tap_ms(),
+            hold_peek_ms: default_hold_peek_ms(),
+            show_position: default_show_position(),
+            hide_on_blur: false,
+            hide_after_launch: false,
+            sync_dir: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Group {
+    pub id: String,
+    pub name: String,
+    pub apps: Vec<AppEntry>,
+    #[serde(rename = "createdAt", default)]
+    pub created_at: i64,
+    /// Last time the name or position changed.
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppEntry {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(rename = "iconHash", default, skip_serializing_if = "Option::is_none")]
+    pub icon_hash: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(rename = "addedAt")]
+    pub added_at: i64,
+    /// Last time a saved field (group, name, path, args, pinned, kind, browser, working
+    /// folder, description, icon location) changed.
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: i64,
+    /// What `path` is; inferred from the path when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<EntryKind>,
+    /// Browser to open a URL entry with instead of the default one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser: Option<String>,
+    /// Folder a program is started in, e.g. a shortcut's "Start in".
+    #[serde(rename = "workingDir", default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Shown as the card's tooltip, e.g. a shortcut's comment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// File the icon is taken from instead of `path`, written `file,index` the way
+    /// Windows writes icon locations when the index isn't 0.
+    #[serde(rename = "iconLocation", default, skip_serializing_if = "Option::is_none")]
+    pub icon_location: Option<String>,
+}
+
+impl AppEntry {
+    /// The stored kind, or the one `path` suggests.
+    pub fn resolved_kind(&self) -> EntryKind {
+        self.kind.unwrap_or_else(|| EntryKind::infer(&self.path))
+    }
+}
+
+/// How an entry is launched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Executable,
+    Folder,
+    Document,
+    Url,
+    Uwp,
+    Shell,
+    /// A command line run through the system shell.
+    Command,
+}
+
+/// Extensions started as programs rather than opened with their associated app.
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe",
+    "com",
+    "bat",
+    "cmd",
+    "ps1",
+    "msc",
+    "lnk",
+    "appref-ms",
+    "sh",
+    "app",
+    "appimage",
+];
+
+impl EntryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EntryKind::Executable => "executable",
+            EntryKind::Folder => "folder",
+            EntryKind::Document => "document",
+            EntryKind::Url => "url",
+            EntryKind::Uwp => "uwp",
+            EntryKind::Shell => "shell",
+            EntryKind::Command => "command",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<EntryKind> {
+        [
+            EntryKind::Executable,
+            EntryKind::Folder,
+            EntryKind::Document,
+            EntryKind::Url,
+            EntryKind::Uwp,
+            EntryKind::Shell,
+            EntryKind::Command,
+        ]
+        .into_iter()
+        .find(|k| k.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// The kind `path` looks like. Never [`EntryKind::Command`], which only the user picks.
+    pub fn infer(path: &str) -> EntryKind {
+        let trimmed = path.trim();
+        let lower = trimmed.to_ascii_lowercase();
+        if lower.starts_with("uwp:") {
+            return EntryKind::Uwp;
+        }
+        if lower.starts_with("shell:") {
+            return EntryKind::Shell;
+        }
+        if crate::paths::is_url(trimmed) {
+            return EntryKind::Url;
+        }
+        let resolved = crate::paths::resolve_launch_path(trimmed);
+        if std::path::Path::new(&resolved).is_dir() || trimmed.ends_with(['/', '\\']) {
+            return EntryKind::Folder;
+        }
+        let file = lower.rsplit(['/', '\\']).next().unwrap_or(&lower);
+        match file.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() && !EXECUTABLE_EXTENSIONS.contains(&ext) => {
+                EntryKind::Document
+            }
+            _ => EntryKind::Executable,
+        }
+    }
+}
+
+/// One row of the append-only `change_log`: a field of a group, an app or the settings
+/// changing from `old_value` to `new_value`. The field `*` with only one of the two
+/// values is the whole entity being created or deleted, as a JSON object.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeLogEntry {
+    pub id: i64,
+    pub changed_at: i64,
+    pub revision: u64,
+    pub entity: String,
+    pub entity_id: String,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+/// What a global hotkey does when pressed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum HotkeyAction {
+    ToggleWindow,
+    ShowSearch,
+    LaunchApp {
+        #[serde(rename = "appId")]
+        app_id: String,
+    },
+    OpenGroup {
+        #[serde(rename = "groupId")]
+        group_id: String,
+    },
+    LaunchClipboard,
+}
+
+impl HotkeyAction {
+    pub(crate) fn to_row(&self) -> (&'static str, &str) {
+        match self {
+            HotkeyAction::ToggleWindow => ("toggleWindow", ""),
+            HotkeyAction::ShowSearch => ("showSearch", ""),
+            HotkeyAction::LaunchApp { app_id } => ("launchApp", app_id),
+            HotkeyAction::OpenGroup { group_id } => ("openGroup", group_id),
+            HotkeyAction::LaunchClipboard => ("launchClipboard", ""),
+        }
+    }
+
+    pub(crate) fn from_row(kind: &str, target: String) -> Option<Self> {
+        match kind {
+            "toggleWindow" => Some(HotkeyAction::ToggleWindow),
+            "showSearch" => Some(HotkeyAction::ShowSearch),
+            "launchApp" => Some(HotkeyAction::LaunchApp { app_id: target }),
+            "openGroup" => Some(HotkeyAction::OpenGroup { group_id: target }),
+            "launchClipboard" => Some(HotkeyAction::LaunchClipboard),
+            _ => None,
+        }
+    }
+}
+
+/// How a hotkey's key events turn into its action.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyMode {
+    /// Fires once when the shortcut goes down.
+    #[default]
+    Press,
+    /// Fires when the shortcut, or a bare `ctrl`/`alt`/`shift`, is tapped twice quickly.
+    DoubleTap,
+    /// Shows the window while held; releasing after the peek threshold hides it again.
+    Hold,
+}
+
+impl HotkeyMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            HotkeyMode::Press => "press",
+            HotkeyMode::DoubleTap => "doubleTap",
+            HotkeyMode::Hold => "hold",
+        }
+    }
+
+    pub(crate) fn from_str(raw: &str) -> Self {
+        match raw {
+            "doubleTap" => HotkeyMode::DoubleTap,
+            "hold" => HotkeyMode::Hold,
+            _ => HotkeyMode::Press,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    pub accelerator: String,
+    pub action: HotkeyAction,
+    #[serde(default)]
+    pub mode: HotkeyMode,
+}
+
+/// Main window placement in physical pixels, kept in `meta` under `window_geometry`.
+/// The bounds are the restored (non-maximized) ones even while `maximized` is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+    /// Name of the monitor the window was on, to notice when it is gone.
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
+/// Icon bytes stored in `icon_blobs`, shared by every app whose icon hashes the same.
+pub struct IconBlob {
+    pub hash: String,
+    pub mime: String,
+    pub data: Vec<u8>,
+}
+
+pub fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// New id in the same shape as the frontend's `createId` fallback.
+pub fn create_id() -> String {
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    format!("{:x}-{:x}", now_millis(), hasher.finish())
+}
@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+
+/// Paths that aren't files on disk: `shell:` folders, `uwp:` apps and URLs.
+pub fn is_special_path(path: &str) -> bool {
+    let lower = path.trim().to_ascii_lowercase();
+    lower.starts_with("shell:") || lower.starts_with("uwp:") || is_url(&lower)
+}
+
+/// Whether `path` starts with a URL scheme such as `https:` or `mailto:`. One-letter
+/// schemes are drive letters, and `shell:`/`uwp:` aren't URLs.
+pub fn is_url(path: &str) -> bool {
+    let Some((scheme, _)) = path.trim().split_once(':') else {
+        return false;
+    };
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !scheme.eq_ignore_ascii_case("shell")
+        && !scheme.eq_ignore_ascii_case("uwp")
+}
+
+pub fn app_base_dir() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+}
+
+fn make_relative_path_inner(path: &Path, base: &Path) -> Option<PathBuf> {
+    let path_components: Vec<_> = path.components().collect();
+    let base_components: Vec<_> = base.components().collect();
+    if path_components.is_empty() || base_components.is_empty() {
+        return None;
+    }
+    if let (Some(std::path::Component::Prefix(p1)), Some(std::path::Component::Prefix(p2))) =
+        (path_components.first(), base_components.first())
+    {
+        if p1.kind() != p2.kind() {
+            return None;
+        }
+    }
+
+    let mut idx = 0usize;
+    while idx < path_components.len()
+        && idx < base_components.len()
+        && path_components[idx] == base_components[idx]
+    {
+        idx += 1;
+    }
+
+    let mut rel = PathBuf::new();
+    for _ in idx..base_components.len() {
+        rel.push("..");
+    }
+    for comp in &path_components[idx..] {
+        rel.push(comp.as_os_str());
+    }
+    if rel.as_os_str().is_empty() {
+        rel.push(".");
+    }
+    Some(rel)
+}
+
+/// Makes a path typed in another directory absolute, e.g. one forwarded from a second
+/// instance started in `cwd`. Special paths and absolute paths are left alone.
+pub fn resolve_against(path: &str, cwd: &str) -> String {
+    let trimmed = path.trim().trim_matches('"');
+    if trimmed.is_empty() || is_special_path(trimmed) || cwd.trim().is_empty() {
+        return trimmed.to_string();
+    }
+    let p = Path::new(trimmed);
+    if p.is_absolute() {
+        return trimmed.to_string();
+    }
+    Path::new(cwd).join(p).to_string_lossy().to_string()
+}
+
+pub fn resolve_launch_path(path: &str) -> String {
+    if path.trim().is_empty() || is_special_path(path) {
+        return path.to_string();
+    }
+    let p = Path::new(path);
+    if p.is_absolute() {
+        return path.to_string();
+    }
+    if let Some(base) = app_base_dir() {
+        return base.join(p).to_string_lossy().to_string();
+    }
+    path.to_string()
+}
+
+/// [`resolve_launch_path`] for a program to run, such as a browser: a bare name like
+/// `firefox` is left for the system to look up on `PATH`, and only a value with a folder
+/// in it, e.g. `.\tools\browser.exe`, is taken relative to the launcher.
+pub fn resolve_program(program: &str) -> String {
+    let trimmed = program.trim();
+    if trimmed.contains(['/', '\\']) {
+        resolve_launch_path(trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Where the system would find a bare program name such as `firefox`: the first folder
+/// on `PATH` with a file of that name.
+pub fn find_on_path(program: &str) -> Option<PathBuf> {
+    let dirs = std::env::var_os("PATH")?;
+    std::env::split_paths(&dirs)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Whether a saved path still points at something. Special paths can't be checked and count as valid.
+pub fn launch_path_exists(path: &str) -> bool {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return false;
+    }
+    if is_special_path(trimmed) {
+        return true;
+    }
+    let resolved = resolve_launch_path(trimmed);
+    Path::new(&resolved).exists()
+}
+
+/// Default display name for a path: the file name without its extension.
+pub fn suggest_app_name(path: &str) -> String {
+    let base = path
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(path);
+    match base.rfind('.') {
+        Some(dot) if dot > 0 => base[..dot].to_string(),
+        _ => base.to_string(),
+    }
+}
+
+/// Rewrites an absolute path relative to the executable's directory, for portable
+/// setups. Special paths, relative paths and paths on another drive come back as given.
+pub fn make_relative_path(path: &str) -> Result<String, String> {
+    if path.trim().is_empty() || is_special_path(path) {
+        return Ok(path.to_string());
+    }
+    let base = app_base_dir().ok_or_else(|| "base dir not found".to_string())?;
+    let p = Path::new(path);
+    if !p.is_absolute() {
+        return Ok(path.to_string());
+    }
+    let rel = make_relative_path_inner(p, &base).unwrap_or_else(|| p.to_path_buf());
+    Ok(rel.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_program_names_are_left_for_path_lookup() {
+        assert_eq!(resolve_program(" firefox "), "firefox");
+        assert_eq!(resolve_program("chrome.exe"), "chrome.exe");
+        let relative = resolve_program("./tools/browser");
+        assert!(Path::new(&relative).is_absolute(), "{}", relative);
+        assert!(relative.ends_with("browser"), "{}", relative);
+        assert_eq!(resolve_program("/usr/bin/firefox"), "/usr/bin/firefox");
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data_dir;
+use crate::model::{create_id, now_millis};
+
+/// The profile whose database is `launcher.db` in the data directory itself, so
+/// data from before profiles existed keeps working unchanged.
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+pub(crate) const REGISTRY_FILE: &str = "profiles.json";
+const PROFILES_DIR: &str = "profiles";
+
+/// A separate set of groups, apps, settings and hotkeys, each in its own database.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+/// `profiles.json` in the data directory: every profile and the one in use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+/// Switching is remembered in `profiles.json`; this keeps the current process from
+/// rereading it for every database open.
+static ACTIVE: Mutex<Option<String>> = Mutex::new(None);
+
+fn registry_path() -> PathBuf {
+    data_dir::current().dir.join(REGISTRY_FILE)
+}
+
+/// Reads the registry, always with the default profile and a valid active id.
+pub fn list() -> ProfileList {
+    let mut list: ProfileList = std::fs::read_to_string(registry_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or(ProfileList {
+            active: DEFAULT_PROFILE_ID.to_string(),
+            profiles: Vec::new(),
+        });
+    if !list.profiles.iter().any(|p| p.id == DEFAULT_PROFILE_ID) {
+        list.profiles.insert(
+            0,
+            Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: "Default".to_string(),
+                created_at: 0,
+            },
+        );
+    }
+    if !list.profiles.iter().any(|p| p.id == list.active) {
+        list.active = DEFAULT_PROFILE_ID.to_string();
+    }
+    list
+}
+
+fn save(list: &ProfileList) -> Result<(), String> {
+    let path = registry_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(list).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+pub fn db_path_for(id: &str) -> PathBuf {
+    let location = data_dir::current();
+    if id == DEFAULT_PROFILE_ID {
+        return location.db_path();
+    }
+    location.dir.join(PROFILES_DIR).join(id).join("launcher.db")
+}
+
+pub fn active_id() -> String {
+    if let Ok(mut active) = ACTIVE.lock() {
+        return active.get_or_insert_with(|| list().active).clone();
+    }
+    list().active
+}
+
+pub fn active_db_path() -> PathBuf {
+    db_path_for(&active_id())
+}
+
+fn check_new_name(list: &ProfileList, name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("profile name is empty".to_string());
+    }
+    let wanted = name.to_lowercase();
+    if list
+        .profiles
+        .iter()
+        .any(|p| p.name.to_lowercase() == wanted)
+    {
+        return Err(format!("a profile named {} already exists", name));
+    }
+    Ok(name.to_string())
+}
+
+/// Finds a profile by id, or else by its name ignoring case.
+pub fn find(id_or_name: &str) -> Option<Profile> {
+    let wanted = id_or_name.trim().to_lowercase();
+    let profiles = list().profiles;
+    let by_id = profiles.iter().find(|p| p.id == id_or_name).cloned();
+    by_id.or_else(|| {
+        profiles
+            .into_iter()
+            .find(|p| p.name.to_lowercase() == wanted)
+    })
+}
+
+fn add(name: &str, copy_from: Option<&str>) -> Result<Profile, String> {
+    let mut list = list();
+    let name = check_new_name(&list, name)?;
+    let profile = Profile {
+        id: create_id(),
+        name,
+        created_at: now_millis(),
+    };
+    let db = db_path_for(&profile.id);
+    match copy_from {
+        Some(source) => data_dir::copy_db(&db_path_for(source), &db)?,
+        None => {
+            crate::storage::open(&db)?;
+        }
+    }
+    list.profiles.push(profile.clone());
+    save(&list)?;
+    Ok(profile)
+}
+
+/// Creates an empty profile. It starts with default settings and no hotkeys.
+pub fn create(name: &str) -> Result<Profile, String> {
+    add(name, None)
+}
+
+/// Creates a profile holding a copy of everything in `source`.
+pub fn clone_profile(source: &str, name: &str) -> Result<Profile, String> {
+    let source = find(source).ok_or_else(|| format!("profile not found: {}", source))?;
+    add(name, Some(&source.id))
+}
+
+/// Makes `id_or_name` the active profile, for this process and the next start.
+pub fn switch(id_or_name: &str) -> Result<Profile, String> {
+    let profile = find(id_or_name).ok_or_else(|| format!("profile not found: {}", id_or_name))?;
+    let mut list = list();
+    list.active = profile.id.clone();
+    save(&list)?;
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active = Some(profile.id.clone());
+    }
+    Ok(profile)
+}
+
+/// Database files of every non-default profile, for moving the data directory.
+pub(crate) fn profile_db_paths() -> Vec<PathBuf> {
+    list()
+        .profiles
+        .iter()
+        .filter(|p| p.id != DEFAULT_PROFILE_ID)
+        .map(|p| db_path_for(&p.id))
+        .filter(|path| path.exists())
+        .collect()
+}
@@ -0,0 +1,675 @@
+//! Finds launchable files in a folder tree (Start Menu, Desktop, a tools folder) and
+//! turns them into groups of apps to add in one go.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::lnk;
+use crate::model::{create_id, now_millis, AppEntry, EntryKind, Group, LauncherState};
+use crate::paths;
+
+fn default_max_depth() -> u32 {
+    6
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanOptions {
+    /// How many folder levels below the root are walked.
+    #[serde(default = "default_max_depth")]
+    pub max_depth: u32,
+    /// Puts everything into one group named after the root instead of a group per folder.
+    #[serde(default)]
+    pub single_group: bool,
+    /// Leaves out uninstallers, which Start Menu folders are full of.
+    #[serde(default = "default_true")]
+    pub skip_uninstallers: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            max_depth: default_max_depth(),
+            single_group: false,
+            skip_uninstallers: true,
+        }
+    }
+}
+
+/// Apps found in one folder. `group_id` is set when a group of that name already exists,
+/// so accepting adds to it instead of creating another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanGroup {
+    pub name: String,
+    #[serde(default)]
+    pub group_id: Option<String>,
+    pub apps: Vec<AppEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanPreview {
+    /// The folder or file the apps were found in.
+    pub root: String,
+    pub groups: Vec<ScanGroup>,
+    /// Paths left out because the library, or an earlier find, already has them.
+    #[serde(default)]
+    pub duplicates: Vec<String>,
+}
+
+/// A well-known folder worth scanning, e.g. `startMenu` or `desktop`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanRoot {
+    pub kind: String,
+    pub path: String,
+}
+
+/// Compares paths the way the file system does: case-insensitively on Windows.
+fn path_key(path: &str) -> String {
+    let trimmed = path.trim();
+    if cfg!(target_os = "windows") {
+        trimmed.replace('/', "\\").to_lowercase()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(unix)]
+fn has_exec_bit(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn has_exec_bit(_path: &Path) -> bool {
+    false
+}
+
+fn is_executable(path: &Path, ext: &str) -> bool {
+    matches!(ext, "exe" | "bat" | "cmd" | "com" | "msc" | "appimage")
+        || (ext.is_empty() && has_exec_bit(path))
+}
+
+/// The `key=value` pairs of one `[section]` of an INI-style file.
+fn ini_section(text: &str, section: &str) -> Vec<(String, String)> {
+    let mut in_section = false;
+    let mut pairs = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line.trim_matches(|c| c == '[' || c == ']') == section;
+            continue;
+        }
+        if !in_section || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            pairs.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    pairs
+}
+
+fn ini_value<'a>(pairs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v.as_str())
+        .filter(|v| !v.is_empty())
+}
+
+/// Splits a `.desktop` `Exec` line into the program and its arguments, dropping field
+/// codes such as `%U` that only make sense when a file is handed over.
+fn split_exec(exec: &str) -> Option<(String, Option<String>)> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = exec.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => quoted = !quoted,
+            '\\' if quoted => current.extend(chars.next()),
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    let parts: Vec<String> = parts
+        .into_iter()
+        .filter(|p| !(p.len() == 2 && p.starts_with('%')))
+        .collect();
+    let (program, args) = parts.split_first()?;
+    let args = args
+        .iter()
+        .map(|a| {
+            if a.contains(char::is_whitespace) {
+                format!("\"{}\"", a)
+            } else {
+                a.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some((program.clone(), (!args.is_empty()).then_some(args)))
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+pub(crate) fn new_entry(name: String, path: String, args: Option<String>) -> AppEntry {
+    let now = now_millis();
+    AppEntry {
+        id: create_id(),
+        name,
+        kind: Some(EntryKind::infer(&path)),
+        path,
+        args,
+        icon: None,
+        icon_hash: None,
+        pinned: false,
+        added_at: now,
+        updated_at: now,
+        browser: None,
+        working_dir: None,
+        description: None,
+        icon_location: None,
+    }
+}
+
+/// The entry a found file stands for, or `None` when it isn't launchable.
+fn entry_for(path: &Path) -> Option<AppEntry> {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let path_str = path.to_string_lossy().to_string();
+    match ext.as_str() {
+        "lnk" => {
+            let shortcut = lnk::read(path).ok()?;
+            Some(shortcut.to_app_entry(path))
+        }
+        "desktop" => {
+            let text = std::fs::read_to_string(path).ok()?;
+            let entry = ini_section(&text, "Desktop Entry");
+            let hidden = |key| ini_value(&entry, key) == Some("true");
+            if hidden("NoDisplay") || hidden("Hidden") {
+                return None;
+            }
+            let name = ini_value(&entry, "Name")
+                .map(str::to_string)
+                .unwrap_or_else(|| file_stem(path));
+            match ini_value(&entry, "Type").unwrap_or("Application") {
+                "Application" => {
+                    let (program, args) = split_exec(ini_value(&entry, "Exec")?)?;
+                    // A bare command is looked up on PATH, as the desktop does; one that
+                    // isn't installed would only make a broken entry
+                    let program = if program.contains('/') {
+                        program
+                    } else {
+                        paths::find_on_path(&program)?.to_string_lossy().to_string()
+                    };
+                    Some(new_entry(name, program, args))
+                }
+                // Opened through the .desktop file itself
+                "Link" => Some(new_entry(name, path_str, None)),
+                _ => None,
+            }
+        }
+        "url" => {
+            let text = std::fs::read_to_string(path).ok()?;
+            ini_value(&ini_section(&text, "InternetShortcut"), "URL")?;
+            Some(new_entry(file_stem(path), path_str, None))
+        }
+        _ if is_executable(path, &ext) => Some(new_entry(file_stem(path), path_str, None)),
+        _ => None,
+    }
+}
+
+fn is_uninstaller(entry: &AppEntry) -> bool {
+    let name = entry.name.to_lowercase();
+    let file = Path::new(&entry.path)
+        .file_name()
+        .map(|f| f.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name.contains("uninstall") || file.starts_with("unins") || file.starts_with("uninstall")
+}
+
+fn group_name(root: &Path, dir: &Path, options: &ScanOptions) -> String {
+    let root_name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| root.to_string_lossy().to_string());
+    if options.single_group {
+        return root_name;
+    }
+    match dir.strip_prefix(root) {
+        Ok(rel) if !rel.as_os_str().is_empty() => rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join(" / "),
+        _ => root_name,
+    }
+}
+
+struct Scan<'a> {
+    root: &'a Path,
+    options: &'a ScanOptions,
+    groups: Vec<ScanGroup>,
+}
+
+impl Scan<'_> {
+    fn add(&mut self, dir: &Path, entry: AppEntry) {
+        if self.options.skip_uninstallers && is_uninstaller(&entry) {
+            return;
+        }
+        let name = group_name(self.root, dir, self.options);
+        match self.groups.iter_mut().find(|g| g.name == name) {
+            Some(group) => group.apps.push(entry),
+            None => self.groups.push(ScanGroup {
+                name,
+                group_id: None,
+                apps: vec![entry],
+            }),
+        }
+    }
+
+    fn walk(&mut self, dir: &Path, depth: u32) {
+        let Ok(read) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut items: Vec<PathBuf> = read.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        items.sort();
+        let mut subdirs = Vec::new();
+        for path in items {
+            let hidden = path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+            if hidden {
+                continue;
+            }
+            // Symlinked folders are skipped so a loop can't keep the walk going
+            let Ok(meta) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if meta.is_dir() {
+                subdirs.push(path);
+            } else if let Some(entry) = entry_for(&path) {
+                self.add(dir, entry);
+            }
+        }
+        if depth < self.options.max_depth {
+            for sub in subdirs {
+                self.walk(&sub, depth + 1);
+            }
+        }
+    }
+}
+
+/// Walks `root` and collects what it finds, grouped by folder. Anything `state` already
+/// has, by path, is left out.
+pub fn scan_folder(
+    root: &Path,
+    options: &ScanOptions,
+    state: Option<&LauncherState>,
+) -> Result<ScanPreview, String> {
+    if !root.is_dir() {
+        return Err(format!("not a folder: {}", root.display()));
+    }
+    let mut scan = Scan {
+        root,
+        options,
+        groups: Vec::new(),
+    };
+    scan.walk(root, 0);
+    Ok(preview(
+        root.to_string_lossy().to_string(),
+        scan.groups,
+        state,
+    ))
+}
+
+/// A preview of adding `found` to `state`: apps whose path `state` or an earlier find
+/// already has are left out, groups of the same name are merged, and groups `state`
+/// already has a group's name for are marked with its id.
+pub fn preview(
+    source: String,
+    found: Vec<ScanGroup>,
+    state: Option<&LauncherState>,
+) -> ScanPreview {
+    let mut seen: HashSet<String> = state
+        .map(|s| s.groups.iter().flat_map(|g| &g.apps))
+        .into_iter()
+        .flatten()
+        .map(|a| path_key(&a.path))
+        .collect();
+    let mut groups: Vec<ScanGroup> = Vec::new();
+    let mut duplicates = Vec::new();
+    for group in found {
+        let name = group.name.trim().to_string();
+        let apps: Vec<AppEntry> = group
+            .apps
+            .into_iter()
+            .filter(|a| {
+                let new = seen.insert(path_key(&a.path));
+                if !new {
+                    duplicates.push(a.path.clone());
+                }
+                new
+            })
+            .collect();
+        if apps.is_empty() {
+            continue;
+        }
+        match groups
+            .iter_mut()
+            .find(|g| g.name.eq_ignore_ascii_case(&name))
+        {
+            Some(existing) => existing.apps.extend(apps),
+            None => groups.push(ScanGroup {
+                group_id: state.and_then(|s| {
+                    s.groups
+                        .iter()
+                        .find(|g| g.name.trim().eq_ignore_ascii_case(&name))
+                        .map(|g| g.id.clone())
+                }),
+                name,
+                apps,
+            }),
+        }
+    }
+    ScanPreview {
+        root: source,
+        groups,
+        duplicates,
+    }
+}
+
+/// `state` with the apps of `preview` added: to the group named by `group_id` when it
+/// still exists, otherwise to a new group. Paths `state` has gained since the scan are
+/// skipped again. Returns the new state and how many apps were added.
+pub fn apply_preview(state: &LauncherState, preview: &ScanPreview) -> (LauncherState, usize) {
+    let mut next = state.clone();
+    let mut seen: HashSet<String> = next
+        .groups
+        .iter()
+        .flat_map(|g| &g.apps)
+        .map(|a| path_key(&a.path))
+        .collect();
+    let now = now_millis();
+    let mut added = 0;
+    for scanned in &preview.groups {
+        let apps: Vec<AppEntry> = scanned
+            .apps
+            .iter()
+            .filter(|a| seen.insert(path_key(&a.path)))
+            .cloned()
+            .collect();
+        if apps.is_empty() {
+            continue;
+        }
+        added += apps.len();
+        let existing = scanned
+            .group_id
+            .as_ref()
+            .and_then(|id| next.groups.iter_mut().find(|g| &g.id == id));
+        match existing {
+            Some(group) => group.apps.extend(apps),
+            None => next.groups.push(Group {
+                id: create_id(),
+                name: scanned.name.clone(),
+                apps,
+                created_at: now,
+                updated_at: now,
+            }),
+        }
+    }
+    if next.active_group_id.is_empty() {
+        if let Some(first) = next.groups.first() {
+            next.active_group_id = first.id.clone();
+        }
+    }
+    (next, added)
+}
+
+fn env_dir(var: &str, rest: &[&str]) -> Option<PathBuf> {
+    let base = std::env::var_os(var).filter(|v| !v.is_empty())?;
+    Some(
+        rest.iter()
+            .fold(PathBuf::from(base), |p, part| p.join(part)),
+    )
+}
+
+/// The Start Menu and Desktop folders of this machine that exist.
+pub fn suggested_roots() -> Vec<ScanRoot> {
+    let candidates: Vec<(&str, Option<PathBuf>)> = if cfg!(target_os = "windows") {
+        let programs = ["Microsoft", "Windows", "Start Menu", "Programs"];
+        vec![
+            ("startMenu", env_dir("APPDATA", &programs)),
+            ("startMenu", env_dir("ProgramData", &programs)),
+            ("desktop", env_dir("USERPROFILE", &["Desktop"])),
+            ("desktop", env_dir("PUBLIC", &["Desktop"])),
+        ]
+    } else {
+        vec![
+            (
+                "startMenu",
+                env_dir("XDG_DATA_HOME", &["applications"])
+                    .or_else(|| env_dir("HOME", &[".local", "share", "applications"])),
+            ),
+            ("startMenu", Some(PathBuf::from("/usr/share/applications"))),
+            ("desktop", env_dir("HOME", &["Desktop"])),
+        ]
+    };
+    candidates
+        .into_iter()
+        .filter_map(|(kind, path)| {
+            let path = path.filter(|p| p.is_dir())?;
+            Some(ScanRoot {
+                kind: kind.to_string(),
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::{app, state, TempDir};
+
+    fn touch(root: &Path, rel: &str, text: &str) -> String {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, text).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn found(preview: &ScanPreview) -> Vec<(String, Vec<String>)> {
+        preview
+            .groups
+            .iter()
+            .map(|g| {
+                (
+                    g.name.clone(),
+                    g.apps.iter().map(|a| a.name.clone()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn sub_folders_become_groups_down_to_max_depth() {
+        let dir = TempDir::new("scan-groups");
+        let root_name = dir.0.file_name().unwrap().to_string_lossy().to_string();
+        touch(&dir.0, "top.exe", "");
+        touch(&dir.0, "Tools/tool.exe", "");
+        touch(&dir.0, "Tools/Deep/deep.bat", "");
+        touch(&dir.0, "Tools/readme.txt", "");
+
+        let scanned = scan_folder(&dir.0, &ScanOptions::default(), None).unwrap();
+        assert_eq!(
+            found(&scanned),
+            vec![
+                (root_name.clone(), names(&["top"])),
+                ("Tools".to_string(), names(&["tool"])),
+                ("Tools / Deep".to_string(), names(&["deep"])),
+            ]
+        );
+
+        let shallow = ScanOptions {
+            max_depth: 1,
+            ..ScanOptions::default()
+        };
+        let scanned = scan_folder(&dir.0, &shallow, None).unwrap();
+        assert_eq!(
+            found(&scanned),
+            vec![
+                (root_name.clone(), names(&["top"])),
+                ("Tools".to_string(), names(&["tool"])),
+            ]
+        );
+
+        let single = ScanOptions {
+            single_group: true,
+            ..ScanOptions::default()
+        };
+        let scanned = scan_folder(&dir.0, &single, None).unwrap();
+        assert_eq!(
+            found(&scanned),
+            vec![(root_name, names(&["top", "tool", "deep"]))]
+        );
+    }
+
+    #[test]
+    fn uninstallers_and_hidden_files_are_skipped() {
+        let dir = TempDir::new("scan-skip");
+        touch(&dir.0, "App/app.exe", "");
+        touch(&dir.0, "App/unins000.exe", "");
+        touch(&dir.0, "App/Uninstall App.exe", "");
+        touch(&dir.0, "App/.hidden.exe", "");
+        touch(&dir.0, ".cache/cached.exe", "");
+
+        let scanned = scan_folder(&dir.0, &ScanOptions::default(), None).unwrap();
+        assert_eq!(found(&scanned), vec![("App".to_string(), names(&["app"]))]);
+
+        let everything = ScanOptions {
+            skip_uninstallers: false,
+            ..ScanOptions::default()
+        };
+        let scanned = scan_folder(&dir.0, &everything, None).unwrap();
+        assert_eq!(
+            found(&scanned),
+            vec![(
+                "App".to_string(),
+                names(&["Uninstall App", "app", "unins000"])
+            )]
+        );
+    }
+
+    #[test]
+    fn paths_the_library_or_the_scan_already_has_are_left_out() {
+        let dir = TempDir::new("scan-dedupe");
+        let known = touch(&dir.0, "Work/known.exe", "");
+        touch(&dir.0, "Work/new.exe", "");
+        let library = state(vec![app("a1", &known)]);
+
+        let scanned = scan_folder(&dir.0, &ScanOptions::default(), Some(&library)).unwrap();
+        assert_eq!(found(&scanned), vec![("Work".to_string(), names(&["new"]))]);
+        assert_eq!(scanned.duplicates, vec![known.clone()]);
+        // The library already has a "Work" group, so accepting adds to it
+        assert_eq!(scanned.groups[0].group_id.as_deref(), Some("g1"));
+
+        // Two finds of the same target: the second is dropped and same-named groups merge
+        let first = ScanGroup {
+            name: "Tools".to_string(),
+            group_id: None,
+            apps: vec![app("x", "/opt/x"), app("y", "/opt/y")],
+        };
+        let second = ScanGroup {
+            name: " tools ".to_string(),
+            group_id: None,
+            apps: vec![app("x2", "/opt/x"), app("z", "/opt/z")],
+        };
+        let merged = preview("import".to_string(), vec![first, second], None);
+        assert_eq!(
+            found(&merged),
+            vec![("Tools".to_string(), names(&["X", "Y", "Z"]))]
+        );
+        assert_eq!(merged.duplicates, vec!["/opt/x".to_string()]);
+    }
+
+    #[test]
+    fn applying_skips_paths_added_since_the_preview() {
+        let dir = TempDir::new("scan-apply");
+        let a = touch(&dir.0, "Work/a.exe", "");
+        let b = touch(&dir.0, "Tools/b.exe", "");
+        let library = state(vec![]);
+        let scanned = scan_folder(&dir.0, &ScanOptions::default(), Some(&library)).unwrap();
+        assert_eq!(scanned.groups.len(), 2);
+
+        // `a` was added by hand while the preview was open
+        let library = state(vec![app("a1", &a)]);
+        let (next, added) = apply_preview(&library, &scanned);
+        assert_eq!(added, 1);
+        let work = next.groups.iter().find(|g| g.id == "g1").unwrap();
+        assert_eq!(work.apps.len(), 1);
+        let tools = next.groups.iter().find(|g| g.name == "Tools").unwrap();
+        assert_eq!(tools.apps.len(), 1);
+        assert_eq!(tools.apps[0].path, b);
+
+        // Applying the same preview again adds nothing
+        let (again, added) = apply_preview(&next, &scanned);
+        assert_eq!(added, 0);
+        assert_eq!(again.groups.len(), next.groups.len());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn desktop_entries_resolve_bare_programs_on_path() {
+        let dir = TempDir::new("scan-desktop");
+        let entry =
+            |exec: &str| format!("[Desktop Entry]\nType=Application\nName=N\nExec={exec}\n");
+        let bare = touch(&dir.0, "bare.desktop", &entry("sh -c true %u"));
+        let absolute = touch(
+            &dir.0,
+            "absolute.desktop",
+            &entry("/opt/tool/bin/tool --new"),
+        );
+        let missing = touch(&dir.0, "missing.desktop", &entry("no-such-program-here %U"));
+
+        let bare = entry_for(Path::new(&bare)).unwrap();
+        assert!(Path::new(&bare.path).is_absolute());
+        assert_eq!(Path::new(&bare.path).file_name().unwrap(), "sh");
+        assert_eq!(bare.args.as_deref(), Some("-c true"));
+
+        let absolute = entry_for(Path::new(&absolute)).unwrap();
+        assert_eq!(absolute.path, "/opt/tool/bin/tool");
+        assert_eq!(absolute.args.as_deref(), Some("--new"));
+
+        assert!(entry_for(Path::new(&missing)).is_none());
+    }
+}
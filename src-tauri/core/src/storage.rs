@@ -0,0 +1,1666 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rusqlite::{params, Connection, TransactionBehavior};
+
+use crate::model::{
+    now_millis, AppEntry, ChangeLogEntry, EntryKind, Group, HotkeyAction, HotkeyBinding,
+    HotkeyMode, IconBlob, LauncherState, UiSettings, WindowGeometry,
+};
+
+fn icon_content_hash(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Splits a `data:<mime>;base64,<payload>` URL into its mime type and decoded bytes.
+fn decode_icon_data_url(data: &str) -> Option<(String, Vec<u8>)> {
+    use base64::Engine;
+    let rest = data.trim().strip_prefix("data:")?;
+    let (header, payload) = rest.split_once(',')?;
+    let mime = header.strip_suffix(";base64")?;
+    let mime = if mime.is_empty() { "image/png" } else { mime };
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .ok()?;
+    if bytes.is_empty() {
+        return None;
+    }
+    Some((mime.to_string(), bytes))
+}
+
+fn put_icon_blob(conn: &Connection, mime: &str, bytes: &[u8], now: i64) -> rusqlite::Result<String> {
+    let hash = icon_content_hash(bytes);
+    conn.execute(
+        "INSERT OR IGNORE INTO icon_blobs(hash, data, mime, created_at) VALUES(?1, ?2, ?3, ?4)",
+        params![hash, bytes, mime, now],
+    )?;
+    Ok(hash)
+}
+
+fn link_app_icon(conn: &Connection, app_id: &str, hash: &str, now: i64) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO app_icons(app_id, icon_hash, updated_at)
+         SELECT ?1, ?2, ?3 WHERE EXISTS (SELECT 1 FROM icon_blobs WHERE hash = ?2)
+         ON CONFLICT(app_id) DO UPDATE SET
+           icon_hash = excluded.icon_hash,
+           updated_at = excluded.updated_at
+         WHERE app_icons.icon_hash != excluded.icon_hash",
+        params![app_id, hash, now],
+    )?;
+    Ok(())
+}
+
+/// A data URL in `icon` stores a new blob, and `iconHash` points the app at an existing
+/// one. Anything else (such as a `qlicon:` URL) keeps whatever icon the app already has.
+fn sync_app_icon(conn: &Connection, entry: &AppEntry, now: i64) -> rusqlite::Result<()> {
+    if let Some((mime, bytes)) = entry.icon.as_deref().and_then(decode_icon_data_url) {
+        let hash = put_icon_blob(conn, &mime, &bytes, now)?;
+        return link_app_icon(conn, &entry.id, &hash, now);
+    }
+    match entry.icon_hash.as_deref().map(str::trim).filter(|h| !h.is_empty()) {
+        Some(hash) => link_app_icon(conn, &entry.id, hash, now),
+        None => Ok(()),
+    }
+}
+
+fn table_has_column(conn: &Connection, table: &str, column: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(1) FROM pragma_table_info(?1) WHERE name = ?2",
+        params![table, column],
+        |r| r.get::<_, i64>(0),
+    )
+    .map(|n| n > 0)
+    .unwrap_or(false)
+}
+
+/// Moves base64 icons from the legacy `app_icons.icon` / `apps.icon` text columns
+/// into deduplicated `icon_blobs`, then recreates `app_icons` as a hash reference table.
+fn migrate_inline_icons(conn: &mut Connection) -> Result<(), String> {
+    let legacy_table = table_has_column(conn, "app_icons", "icon");
+    let inline_apps: i64 = conn
+        .query_row("SELECT COUNT(1) FROM apps WHERE icon != ''", [], |r| r.get(0))
+        .unwrap_or(0);
+    if !legacy_table && inline_apps == 0 {
+        return conn
+            .execute_batch(APP_ICONS_SCHEMA)
+            .map_err(|e| e.to_string());
+    }
+
+    let mut inline: Vec<(String, String, i64)> = Vec::new();
+    if legacy_table {
+        let mut stmt = conn
+            .prepare("SELECT app_id, icon, updated_at FROM app_icons")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .map_err(|e| e.to_string())?;
+        for row in rows {
+            inline.push(row.map_err(|e| e.to_string())?);
+        }
+    }
+    {
+        let mut stmt = conn
+            .prepare("SELECT id, icon, added_at FROM apps WHERE icon != ''")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .map_err(|e| e.to_string())?;
+        for row in rows {
+            let row: (String, String, i64) = row.map_err(|e| e.to_string())?;
+            // app_icons took precedence over apps.icon when both were set
+            if !inline.iter().any(|(id, _, _)| *id == row.0) {
+                inline.push(row);
+            }
+        }
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    if legacy_table {
+        tx.execute("DROP TABLE app_icons", [])
+            .map_err(|e| e.to_string())?;
+    }
+    tx.execute_batch(APP_ICONS_SCHEMA)
+        .map_err(|e| e.to_string())?;
+    for (app_id, data, updated_at) in &inline {
+        let Some((mime, bytes)) = decode_icon_data_url(data) else {
+            continue;
+        };
+        let exists: i64 = tx
+            .query_row("SELECT COUNT(1) FROM apps WHERE id = ?1", params![app_id], |r| r.get(0))
+            .map_err(|e| e.to_string())?;
+        if exists == 0 {
+            continue;
+        }
+        let hash = put_icon_blob(&tx, &mime, &bytes, *updated_at).map_err(|e| e.to_string())?;
+        link_app_icon(&tx, app_id, &hash, *updated_at).map_err(|e| e.to_string())?;
+    }
+    tx.execute("UPDATE apps SET icon = '' WHERE icon != ''", [])
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+const APP_ICONS_SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS app_icons (
+  app_id TEXT PRIMARY KEY,
+  icon_hash TEXT NOT NULL,
+  updated_at INTEGER NOT NULL,
+  FOREIGN KEY(app_id) REFERENCES apps(id) ON DELETE CASCADE,
+  FOREIGN KEY(icon_hash) REFERENCES icon_blobs(hash)
+);
+CREATE INDEX IF NOT EXISTS idx_app_icons_hash ON app_icons(icon_hash);
+"#;
+
+/// Stores the kind each existing app's path suggests, for rows from before `kind` was
+/// a column.
+fn backfill_app_kinds(conn: &Connection) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare("SELECT id, path FROM apps WHERE kind = ''")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    for (id, path) in rows {
+        conn.execute(
+            "UPDATE apps SET kind = ?1 WHERE id = ?2",
+            params![EntryKind::infer(&path).as_str(), id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Seeds the `hotkeys` table from the single toggle hotkey older versions kept in
+/// `ui_settings`. Runs once; the `hotkeys_seeded` meta key marks it done.
+fn migrate_toggle_hotkey(conn: &Connection) -> Result<(), String> {
+    let seeded: i64 = conn
+        .query_row("SELECT COUNT(1) FROM meta WHERE key = 'hotkeys_seeded'", [], |r| r.get(0))
+        .unwrap_or(0);
+    if seeded > 0 {
+        return Ok(());
+    }
+    let toggle = read_ui_settings(conn).toggle_hotkey.trim().to_lowercase();
+    if !toggle.is_empty() {
+        conn.execute(
+            "INSERT OR IGNORE INTO hotkeys(accelerator, action, target, position)
+             VALUES(?1, 'toggleWindow', '', 0)",
+            params![toggle],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    conn.execute(
+        "INSERT INTO meta(key, value) VALUES('hotkeys_seeded', '1')
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Prefix of the error a write based on an outdated revision fails with.
+pub const STALE_STATE_ERROR: &str = "stale-state";
+
+/// The active profile's database in the resolved data directory; see
+/// [`crate::data_dir`] and [`crate::profiles`].
+pub fn default_db_path() -> Result<PathBuf, String> {
+    Ok(crate::profiles::active_db_path())
+}
+
+/// Bumped by every [`write_launcher_state`]; 0 until the first one.
+pub fn read_revision(conn: &Connection) -> u64 {
+    conn.query_row("SELECT value FROM meta WHERE key = 'revision'", [], |r| {
+        r.get::<_, String>(0)
+    })
+    .ok()
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(0)
+}
+
+/// The oldest revision whose changes undo can still take back, as set by
+/// [`write_undo_floor`]; 0, keeping every revision, until it is set.
+fn read_undo_floor(conn: &Connection) -> u64 {
+    conn.query_row("SELECT value FROM meta WHERE key = 'undo_floor'", [], |r| {
+        r.get::<_, String>(0)
+    })
+    .ok()
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(0)
+}
+
+/// Tells writes that undo can't reach revisions before `revision` anymore, so the icons
+/// of apps deleted before it can go.
+pub fn write_undo_floor(conn: &Connection, revision: u64) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO meta(key, value) VALUES('undo_floor', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![revision.to_string()],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// SQLite's `PRAGMA data_version`: changes whenever another connection commits.
+pub fn read_data_version(conn: &Connection) -> Result<i64, String> {
+    conn.query_row("PRAGMA data_version", [], |r| r.get(0))
+        .map_err(|e| e.to_string())
+}
+
+/// The schema [`migrate`] brings a database to, kept in `PRAGMA user_version`. Bump it
+/// whenever `migrate` changes, so existing databases run it again.
+const SCHEMA_VERSION: i64 = 1;
+
+/// Opens the database at `path`, creating it and bringing the schema up to date. The
+/// migrations only run while the database is behind [`SCHEMA_VERSION`], so later opens
+/// cost no more than [`open_existing`].
+pub fn open(path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut conn = Connection::open(path).map_err(|e| e.to_string())?;
+    set_pragmas(&conn)?;
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if version < SCHEMA_VERSION {
+        migrate(&mut conn)?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(conn)
+}
+
+/// Creates the tables and brings columns and data from older versions up to date.
+fn migrate(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS meta (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS groups (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  position INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS apps (
+  id TEXT PRIMARY KEY,
+  group_id TEXT NOT NULL,
+  name TEXT NOT NULL,
+  path TEXT NOT NULL,
+  args TEXT NOT NULL,
+  icon TEXT NOT NULL DEFAULT '',
+  position INTEGER NOT NULL,
+  added_at INTEGER NOT NULL,
+  FOREIGN KEY(group_id) REFERENCES groups(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_apps_group_position ON apps(group_id, position);
+CREATE TABLE IF NOT EXISTS icon_blobs (
+  hash TEXT PRIMARY KEY,
+  data BLOB NOT NULL,
+  mime TEXT NOT NULL DEFAULT 'image/png',
+  created_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS hotkeys (
+  accelerator TEXT PRIMARY KEY,
+  action TEXT NOT NULL,
+  target TEXT NOT NULL DEFAULT '',
+  mode TEXT NOT NULL DEFAULT 'press',
+  position INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS launch_history (
+  app_id TEXT NOT NULL,
+  launched_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_launch_history_time ON launch_history(launched_at);
+CREATE TABLE IF NOT EXISTS change_log (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  changed_at INTEGER NOT NULL,
+  revision INTEGER NOT NULL,
+  entity TEXT NOT NULL,
+  entity_id TEXT NOT NULL,
+  field TEXT NOT NULL,
+  old_value TEXT,
+  new_value TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_change_log_entity ON change_log(entity, entity_id);
+CREATE INDEX IF NOT EXISTS idx_change_log_revision ON change_log(revision);
+"#,
+    )
+    .map_err(|e| e.to_string())?;
+
+    if !table_has_column(conn, "apps", "icon") {
+        let _ = conn.execute("ALTER TABLE apps ADD COLUMN icon TEXT NOT NULL DEFAULT ''", []);
+    }
+    if !table_has_column(conn, "apps", "pinned") {
+        let _ = conn.execute("ALTER TABLE apps ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0", []);
+    }
+    if !table_has_column(conn, "apps", "updated_at") {
+        let _ = conn.execute(
+            "ALTER TABLE apps ADD COLUMN updated_at INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute("UPDATE apps SET updated_at = added_at", []);
+    }
+    if !table_has_column(conn, "apps", "kind") {
+        let _ = conn.execute("ALTER TABLE apps ADD COLUMN kind TEXT NOT NULL DEFAULT ''", []);
+        backfill_app_kinds(conn)?;
+    }
+    if !table_has_column(conn, "apps", "browser") {
+        let _ = conn.execute("ALTER TABLE apps ADD COLUMN browser TEXT NOT NULL DEFAULT ''", []);
+    }
+    for column in ["working_dir", "description", "icon_location"] {
+        if !table_has_column(conn, "apps", column) {
+            let sql = format!("ALTER TABLE apps ADD COLUMN {} TEXT NOT NULL DEFAULT ''", column);
+            let _ = conn.execute(&sql, []);
+        }
+    }
+    if !table_has_column(conn, "groups", "updated_at") {
+        let _ = conn.execute(
+            "ALTER TABLE groups ADD COLUMN created_at INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE groups ADD COLUMN updated_at INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+    }
+    // Groups from before `created_at` date from their oldest app, or from now when empty
+    conn.execute(
+        "UPDATE groups SET created_at = COALESCE(
+           (SELECT MIN(added_at) FROM apps WHERE apps.group_id = groups.id AND added_at > 0),
+           ?1)
+         WHERE created_at = 0",
+        params![now_millis()],
+    )
+    .map_err(|e| e.to_string())?;
+    if !table_has_column(conn, "hotkeys", "mode") {
+        let _ = conn.execute(
+            "ALTER TABLE hotkeys ADD COLUMN mode TEXT NOT NULL DEFAULT 'press'",
+            [],
+        );
+    }
+
+    // Migration: inline base64 icons -> icon_blobs
+    migrate_inline_icons(conn)?;
+    // Migration: ui_settings.toggleHotkey -> hotkeys
+    migrate_toggle_hotkey(conn)?;
+    Ok(())
+}
+
+/// Opens an existing database without the schema and migration checks of [`open`],
+/// for hot paths such as icon requests and reads once the app has opened it at
+/// startup. Fails when the file isn't there yet.
+pub fn open_existing(path: &Path) -> Result<Connection, String> {
+    use rusqlite::OpenFlags;
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| e.to_string())?;
+    set_pragmas(&conn)?;
+    Ok(conn)
+}
+
+fn set_pragmas(conn: &Connection) -> Result<(), String> {
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "synchronous", "NORMAL")
+        .map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "busy_timeout", "5000")
+        .map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "foreign_keys", "ON")
+        .map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "cache_size", "-8000")
+        .map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "temp_store", "2")
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn read_launcher_state(conn: &Connection) -> Result<Option<LauncherState>, String> {
+    let has_any: i64 = conn
+        .query_row("SELECT COUNT(1) FROM groups", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if has_any == 0 {
+        return Ok(None);
+    }
+
+    let active_group_id: String = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'active_group_id' LIMIT 1",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap_or_else(|_| String::new());
+
+    let mut groups_stmt = conn
+        .prepare("SELECT id, name, created_at, updated_at FROM groups ORDER BY position ASC")
+        .map_err(|e| e.to_string())?;
+    let group_rows = groups_stmt
+        .query_map([], |row| {
+            Ok(Group {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                apps: Vec::new(),
+                created_at: row.get(2)?,
+                updated_at: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut groups: Vec<Group> = Vec::new();
+    for row in group_rows {
+        groups.push(row.map_err(|e| e.to_string())?);
+    }
+
+    let mut apps_stmt = conn
+        .prepare(
+            "SELECT a.id, a.group_id, a.name, a.path, a.args, i.icon_hash, a.pinned, a.added_at,
+                    a.updated_at, a.kind, a.browser, a.working_dir, a.description, a.icon_location
+             FROM apps a
+             LEFT JOIN app_icons i ON a.id = i.app_id
+             ORDER BY a.position ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let app_rows = apps_stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, bool>(6)?,
+                row.get::<_, i64>(7)?,
+                row.get::<_, i64>(8)?,
+                row.get::<_, String>(9)?,
+                row.get::<_, String>(10)?,
+                row.get::<_, String>(11)?,
+                row.get::<_, String>(12)?,
+                row.get::<_, String>(13)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut apps_by_group: HashMap<String, Vec<AppEntry>> = HashMap::new();
+    for row in app_rows {
+        let (
+            id,
+            group_id,
+            name,
+            path,
+            args,
+            icon_hash,
+            pinned,
+            added_at,
+            updated_at,
+            kind,
+            browser,
+            working_dir,
+            description,
+            icon_location,
+        ) = row.map_err(|e| e.to_string())?;
+        let args_opt = if args.trim().is_empty() { None } else { Some(args) };
+        let kind = Some(EntryKind::parse(&kind).unwrap_or_else(|| EntryKind::infer(&path)));
+        apps_by_group.entry(group_id).or_default().push(AppEntry {
+            id,
+            name,
+            path,
+            args: args_opt,
+            icon: None,
+            icon_hash,
+            pinned,
+            added_at,
+            updated_at,
+            kind,
+            browser: non_empty(browser),
+            working_dir: non_empty(working_dir),
+            description: non_empty(description),
+            icon_location: non_empty(icon_location),
+        });
+    }
+
+    for g in &mut groups {
+        if let Some(apps) = apps_by_group.remove(&g.id) {
+            g.apps = apps;
+        }
+    }
+
+    let active = if !active_group_id.is_empty() && groups.iter().any(|g| g.id == active_group_id) {
+        active_group_id
+    } else {
+        groups
+            .first()
+            .map(|g| g.id.clone())
+            .unwrap_or_default()
+    };
+
+    let settings = read_ui_settings(conn);
+
+    Ok(Some(LauncherState {
+        version: 1,
+        active_group_id: active,
+        groups,
+        settings,
+        revision: read_revision(conn),
+    }))
+}
+
+/// Field values of a stored group or app, in the form they are compared and logged.
+type Fields = Vec<(&'static str, String)>;
+
+fn group_fields(name: &str, position: i64) -> Fields {
+    vec![("name", name.to_string()), ("position", position.to_string())]
+}
+
+#[allow(clippy::too_many_arguments)]
+fn app_fields(
+    group_id: &str,
+    name: &str,
+    path: &str,
+    args: &str,
+    pinned: bool,
+    position: i64,
+    kind: &str,
+    browser: &str,
+    working_dir: &str,
+    description: &str,
+    icon_location: &str,
+) -> Fields {
+    vec![
+        ("groupId", group_id.to_string()),
+        ("name", name.to_string()),
+        ("path", path.to_string()),
+        ("args", args.to_string()),
+        ("pinned", pinned.to_string()),
+        ("position", position.to_string()),
+        ("kind", kind.to_string()),
+        ("browser", browser.to_string()),
+        ("workingDir", working_dir.to_string()),
+        ("description", description.to_string()),
+        ("iconLocation", icon_location.to_string()),
+    ]
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.trim().is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn fields_json(fields: &Fields) -> String {
+    let map: serde_json::Map<String, serde_json::Value> = fields
+        .iter()
+        .map(|(k, v)| (k.to_string(), serde_json::Value::String(v.clone())))
+        .collect();
+    serde_json::Value::Object(map).to_string()
+}
+
+/// Where `write_launcher_state` appends to the change log.
+struct ChangeLogger {
+    revision: u64,
+    now: i64,
+}
+
+impl ChangeLogger {
+    fn log(
+        &self,
+        tx: &Connection,
+        entity: &str,
+        id: &str,
+        field: &str,
+        old: Option<&str>,
+        new: Option<&str>,
+    ) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT INTO change_log(changed_at, revision, entity, entity_id, field, old_value, new_value)
+             VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![self.now, self.revision as i64, entity, id, field, old, new],
+        )?;
+        Ok(())
+    }
+
+    /// Logs how an entity went from `old` to `new`, field by field or as a whole when
+    /// it was created or deleted, and tells whether anything changed.
+    ///
+    /// `position` only shifts when a sibling is added or removed, so it is logged only
+    /// when the entity `moved` among its siblings, and never counts as a change.
+    fn log_fields(
+        &self,
+        tx: &Connection,
+        entity: &str,
+        id: &str,
+        old: Option<&Fields>,
+        new: Option<&Fields>,
+        moved: bool,
+    ) -> rusqlite::Result<bool> {
+        match (old, new) {
+            (None, Some(new)) => {
+                self.log(tx, entity, id, "*", None, Some(&fields_json(new)))?;
+                Ok(true)
+            }
+            (Some(old), None) => {
+                self.log(tx, entity, id, "*", Some(&fields_json(old)), None)?;
+                Ok(true)
+            }
+            (Some(old), Some(new)) => {
+                let mut changed = false;
+                for ((field, a), (_, b)) in old.iter().zip(new) {
+                    if a == b {
+                        continue;
+                    }
+                    if *field == "position" {
+                        if moved {
+                            self.log(tx, entity, id, field, Some(a), Some(b))?;
+                        }
+                        continue;
+                    }
+                    self.log(tx, entity, id, field, Some(a), Some(b))?;
+                    changed = true;
+                }
+                Ok(changed)
+            }
+            (None, None) => Ok(false),
+        }
+    }
+}
+
+fn field<'a>(fields: &'a Fields, name: &str) -> &'a str {
+    fields
+        .iter()
+        .find(|(k, _)| *k == name)
+        .map_or("", |(_, v)| v.as_str())
+}
+
+fn stored_position(fields: &Fields) -> i64 {
+    field(fields, "position").parse().unwrap_or(0)
+}
+
+/// The ids in `order` whose rank differs from their rank by stored position. Entries
+/// added or removed around them don't count, so only real moves show up.
+fn reordered<'a>(order: &[&'a str], position: impl Fn(&str) -> i64) -> HashSet<&'a str> {
+    let mut before = order.to_vec();
+    before.sort_by_key(|id| position(id));
+    order
+        .iter()
+        .zip(&before)
+        .filter(|(now, then)| now != then)
+        .map(|(now, _)| *now)
+        .collect()
+}
+
+/// A changed row gets `now` as its modification time, unless the incoming one is newer
+/// than what is stored, as it is for an edit imported by sync.
+fn modified_at(changed: bool, stored: i64, incoming: i64, now: i64) -> i64 {
+    match changed {
+        false => stored,
+        true if incoming > stored => incoming,
+        true => now,
+    }
+}
+
+fn read_stored_groups(tx: &Connection) -> rusqlite::Result<HashMap<String, (Fields, i64)>> {
+    let mut stmt = tx.prepare("SELECT id, name, position, updated_at FROM groups")?;
+    let rows = stmt.query_map([], |r| {
+        Ok((
+            r.get::<_, String>(0)?,
+            (
+                group_fields(&r.get::<_, String>(1)?, r.get(2)?),
+                r.get::<_, i64>(3)?,
+            ),
+        ))
+    })?;
+    rows.collect()
+}
+
+fn read_stored_apps(tx: &Connection) -> rusqlite::Result<HashMap<String, (Fields, i64)>> {
+    let mut stmt = tx.prepare(
+        "SELECT id, group_id, name, path, args, pinned, position, updated_at, kind, browser,
+                working_dir, description, icon_location
+         FROM apps",
+    )?;
+    let rows = stmt.query_map([], |r| {
+        Ok((
+            r.get::<_, String>(0)?,
+            (
+                app_fields(
+                    &r.get::<_, String>(1)?,
+                    &r.get::<_, String>(2)?,
+                    &r.get::<_, String>(3)?,
+                    &r.get::<_, String>(4)?,
+                    r.get(5)?,
+                    r.get(6)?,
+                    &r.get::<_, String>(8)?,
+                    &r.get::<_, String>(9)?,
+                    &r.get::<_, String>(10)?,
+                    &r.get::<_, String>(11)?,
+                    &r.get::<_, String>(12)?,
+                ),
+                r.get::<_, i64>(7)?,
+            ),
+        ))
+    })?;
+    rows.collect()
+}
+
+/// `fields` of an app about to be deleted, plus what undo needs to bring it back as it
+/// was: when it was added and the icon it had.
+fn deleted_app_fields(tx: &Connection, id: &str, fields: &Fields) -> rusqlite::Result<Fields> {
+    let (added_at, icon_hash): (i64, Option<String>) = tx.query_row(
+        "SELECT a.added_at, i.icon_hash FROM apps a
+         LEFT JOIN app_icons i ON a.id = i.app_id
+         WHERE a.id = ?1",
+        params![id],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )?;
+    let mut fields = fields.clone();
+    fields.push(("addedAt", added_at.to_string()));
+    if let Some(hash) = icon_hash {
+        fields.push(("iconHash", hash));
+    }
+    Ok(fields)
+}
+
+/// Logs each top-level setting that differs between the stored and the new settings JSON.
+/// The first settings ever written are not a change.
+fn log_settings(
+    tx: &Connection,
+    logger: &ChangeLogger,
+    old_json: &str,
+    new_json: &str,
+) -> rusqlite::Result<()> {
+    use serde_json::Value;
+    if old_json.trim().is_empty() {
+        return Ok(());
+    }
+    let as_map = |json: &str| match serde_json::from_str::<Value>(json) {
+        Ok(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let (old, new) = (as_map(old_json), as_map(new_json));
+    let keys: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        let (a, b) = (old.get(key), new.get(key));
+        if a != b {
+            let a = a.map(Value::to_string);
+            let b = b.map(Value::to_string);
+            logger.log(tx, "settings", "", key, a.as_deref(), b.as_deref())?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the saved groups, apps and settings with `state` in one transaction and
+/// returns the new revision. Fails with [`STALE_STATE_ERROR`] when someone else wrote
+/// since `state.revision` was read.
+///
+/// Rows only get a new `updated_at` when one of their fields actually changes, and
+/// every change is appended to `change_log`.
+pub fn write_launcher_state(conn: &mut Connection, state: &LauncherState) -> Result<u64, String> {
+    // Immediate, so no other writer can get in between the check and the write
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let now = now_millis();
+
+    let current = read_revision(&tx);
+    if state.revision != current {
+        return Err(format!(
+            "{}: the data was changed elsewhere (revision {}, expected {})",
+            STALE_STATE_ERROR, current, state.revision
+        ));
+    }
+    let revision = current + 1;
+    let logger = ChangeLogger { revision, now };
+    tx.execute(
+        "INSERT INTO meta(key, value) VALUES('revision', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![revision.to_string()],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // UPSERT meta
+    tx.execute(
+        "INSERT INTO meta(key, value) VALUES('active_group_id', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![state.active_group_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let settings_json = serde_json::to_string(&state.settings).map_err(|e| e.to_string())?;
+    let old_settings_json: String = tx
+        .query_row("SELECT value FROM meta WHERE key = 'ui_settings'", [], |r| {
+            r.get(0)
+        })
+        .unwrap_or_default();
+    log_settings(&tx, &logger, &old_settings_json, &settings_json).map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO meta(key, value) VALUES('ui_settings', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![settings_json],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let stored_groups = read_stored_groups(&tx).map_err(|e| e.to_string())?;
+    let stored_apps = read_stored_apps(&tx).map_err(|e| e.to_string())?;
+
+    // Collect current IDs for diff delete
+    let mut new_group_ids: HashSet<String> = HashSet::new();
+    let mut new_app_ids: HashSet<String> = HashSet::new();
+
+    let kept_groups: Vec<&str> = state
+        .groups
+        .iter()
+        .map(|g| g.id.as_str())
+        .filter(|id| stored_groups.contains_key(*id))
+        .collect();
+    let moved_groups = reordered(&kept_groups, |id| stored_position(&stored_groups[id].0));
+
+    // UPSERT groups
+    for (group_pos, group) in state.groups.iter().enumerate() {
+        new_group_ids.insert(group.id.clone());
+        let fields = group_fields(&group.name, group_pos as i64);
+        let stored = stored_groups.get(&group.id);
+        let moved = moved_groups.contains(group.id.as_str());
+        let changed = logger
+            .log_fields(
+                &tx,
+                "group",
+                &group.id,
+                stored.map(|s| &s.0),
+                Some(&fields),
+                moved,
+            )
+            .map_err(|e| e.to_string())?;
+        let updated_at = modified_at(changed, stored.map_or(0, |s| s.1), group.updated_at, now);
+        let created_at = if group.created_at > 0 { group.created_at } else { now };
+        tx.execute(
+            "INSERT INTO groups(id, name, position, created_at, updated_at) VALUES(?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(id) DO UPDATE SET
+               name = excluded.name,
+               position = excluded.position,
+               updated_at = excluded.updated_at",
+            params![group.id, group.name, group_pos as i64, created_at, updated_at],
+        )
+        .map_err(|e| e.to_string())?;
+
+        let kept_apps: Vec<&str> = group
+            .apps
+            .iter()
+            .map(|a| a.id.as_str())
+            .filter(|id| {
+                stored_apps
+                    .get(*id)
+                    .is_some_and(|s| field(&s.0, "groupId") == group.id)
+            })
+            .collect();
+        let moved_apps = reordered(&kept_apps, |id| stored_position(&stored_apps[id].0));
+
+        // UPSERT apps
+        for (app_pos, app_entry) in group.apps.iter().enumerate() {
+            new_app_ids.insert(app_entry.id.clone());
+            let args = app_entry.args.as_deref().unwrap_or("");
+            let kind = app_entry.resolved_kind().as_str();
+            let browser = app_entry.browser.as_deref().unwrap_or("").trim();
+            let working_dir = app_entry.working_dir.as_deref().unwrap_or("").trim();
+            let description = app_entry.description.as_deref().unwrap_or("").trim();
+            let icon_location = app_entry.icon_location.as_deref().unwrap_or("").trim();
+            let mut fields = app_fields(
+                &group.id,
+                &app_entry.name,
+                &app_entry.path,
+                args,
+                app_entry.pinned,
+                app_pos as i64,
+                kind,
+                browser,
+                working_dir,
+                description,
+                icon_location,
+            );
+            let stored = stored_apps.get(&app_entry.id);
+            if stored.is_none() {
+                // Redo brings a created app back with the time it was added
+                fields.push(("addedAt", app_entry.added_at.to_string()));
+            }
+            // Moving to another group always moves it among its siblings
+            let moved = moved_apps.contains(app_entry.id.as_str())
+                || stored.is_some_and(|s| field(&s.0, "groupId") != group.id);
+            let changed = logger
+                .log_fields(
+                    &tx,
+                    "app",
+                    &app_entry.id,
+                    stored.map(|s| &s.0),
+                    Some(&fields),
+                    moved,
+                )
+                .map_err(|e| e.to_string())?;
+            let updated_at =
+                modified_at(changed, stored.map_or(0, |s| s.1), app_entry.updated_at, now);
+            // A new path or icon location means a new icon; drop the old link before the row
+            // is updated
+            tx.execute(
+                "DELETE FROM app_icons WHERE app_id = ?1
+                 AND EXISTS (SELECT 1 FROM apps
+                             WHERE id = ?1 AND (path != ?2 OR icon_location != ?3))",
+                params![app_entry.id, app_entry.path, icon_location],
+            )
+            .map_err(|e| e.to_string())?;
+            tx.execute(
+                "INSERT INTO apps(id, group_id, name, path, args, position, added_at, pinned, updated_at,
+                                  kind, browser, working_dir, description, icon_location)
+                 VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                 ON CONFLICT(id) DO UPDATE SET
+                   group_id = excluded.group_id,
+                   name = excluded.name,
+                   path = excluded.path,
+                   args = excluded.args,
+                   position = excluded.position,
+                   pinned = excluded.pinned,
+                   updated_at = excluded.updated_at,
+                   kind = excluded.kind,
+                   browser = excluded.browser,
+                   working_dir = excluded.working_dir,
+                   description = excluded.description,
+                   icon_location = excluded.icon_location",
+                params![
+                    app_entry.id,
+                    group.id,
+                    app_entry.name,
+                    app_entry.path,
+                    args,
+                    app_pos as i64,
+                    app_entry.added_at,
+                    app_entry.pinned,
+                    updated_at,
+                    kind,
+                    browser,
+                    working_dir,
+                    description,
+                    icon_location
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+            sync_app_icon(&tx, app_entry, now).map_err(|e| e.to_string())?;
+        }
+    }
+
+    for (id, (fields, _)) in &stored_apps {
+        if !new_app_ids.contains(id) {
+            let snapshot = deleted_app_fields(&tx, id, fields).map_err(|e| e.to_string())?;
+            logger
+                .log_fields(&tx, "app", id, Some(&snapshot), None, false)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    for (id, (fields, _)) in &stored_groups {
+        if !new_group_ids.contains(id) {
+            logger
+                .log_fields(&tx, "group", id, Some(fields), None, false)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    // Delete removed apps (diff delete)
+    if !new_app_ids.is_empty() {
+        let placeholders: String = new_app_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!("DELETE FROM apps WHERE id NOT IN ({})", placeholders);
+        let params: Vec<&dyn rusqlite::ToSql> = new_app_ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
+        tx.execute(&sql, params.as_slice()).map_err(|e| e.to_string())?;
+    } else {
+        tx.execute("DELETE FROM apps", []).map_err(|e| e.to_string())?;
+    }
+
+    // Delete removed groups (diff delete)
+    if !new_group_ids.is_empty() {
+        let placeholders: String = new_group_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!("DELETE FROM groups WHERE id NOT IN ({})", placeholders);
+        let params: Vec<&dyn rusqlite::ToSql> = new_group_ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
+        tx.execute(&sql, params.as_slice()).map_err(|e| e.to_string())?;
+    } else {
+        tx.execute("DELETE FROM groups", []).map_err(|e| e.to_string())?;
+    }
+
+    // Drop icon blobs no app refers to anymore, unless undo can still bring one back
+    tx.execute(
+        "DELETE FROM icon_blobs WHERE hash NOT IN (SELECT icon_hash FROM app_icons)
+         AND hash NOT IN (
+           SELECT json_extract(old_value, '$.iconHash') FROM change_log
+           WHERE revision >= ?1 AND entity = 'app' AND field = '*' AND old_value IS NOT NULL
+             AND json_extract(old_value, '$.iconHash') IS NOT NULL)",
+        params![read_undo_floor(&tx) as i64],
+    )
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(revision)
+}
+
+fn change_log_row(r: &rusqlite::Row) -> rusqlite::Result<ChangeLogEntry> {
+    Ok(ChangeLogEntry {
+        id: r.get(0)?,
+        changed_at: r.get(1)?,
+        revision: r.get::<_, i64>(2)? as u64,
+        entity: r.get(3)?,
+        entity_id: r.get(4)?,
+        field: r.get(5)?,
+        old_value: r.get(6)?,
+        new_value: r.get(7)?,
+    })
+}
+
+/// The latest `limit` change log rows, newest first.
+pub fn read_change_log(conn: &Connection, limit: usize) -> Result<Vec<ChangeLogEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, changed_at, revision, entity, entity_id, field, old_value, new_value
+             FROM change_log ORDER BY id DESC LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![limit as i64], change_log_row)
+        .map_err(|e| e.to_string())?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Everything the write that produced `revision` changed, in the order it was logged.
+pub fn read_revision_changes(
+    conn: &Connection,
+    revision: u64,
+) -> Result<Vec<ChangeLogEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, changed_at, revision, entity, entity_id, field, old_value, new_value
+             FROM change_log WHERE revision = ?1 ORDER BY id ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![revision as i64], change_log_row)
+        .map_err(|e| e.to_string())?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())
+}
+
+pub fn read_app_icon(conn: &Connection, app_id: &str) -> Result<Option<IconBlob>, String> {
+    let row = conn.query_row(
+        "SELECT b.hash, b.mime, b.data
+         FROM app_icons i
+         JOIN icon_blobs b ON b.hash = i.icon_hash
+         WHERE i.app_id = ?1",
+        params![app_id],
+        |r| {
+            Ok(IconBlob {
+                hash: r.get(0)?,
+                mime: r.get(1)?,
+                data: r.get(2)?,
+            })
+        },
+    );
+    match row {
+        Ok(blob) => Ok(Some(blob)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// The stored icons of `app_ids` in one query, by app id. Apps without one are left out.
+pub fn read_app_icons(
+    conn: &Connection,
+    app_ids: &[&str],
+) -> Result<HashMap<String, IconBlob>, String> {
+    if app_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let placeholders: String = app_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let sql = format!(
+        "SELECT i.app_id, b.hash, b.mime, b.data
+         FROM app_icons i
+         JOIN icon_blobs b ON b.hash = i.icon_hash
+         WHERE i.app_id IN ({})",
+        placeholders
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(app_ids), |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                IconBlob {
+                    hash: r.get(1)?,
+                    mime: r.get(2)?,
+                    data: r.get(3)?,
+                },
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<rusqlite::Result<_>>()
+        .map_err(|e| e.to_string())
+}
+
+/// The path of `app_id` and the icon location set for it, if any.
+pub fn read_app_icon_source(
+    conn: &Connection,
+    app_id: &str,
+) -> Result<Option<(String, Option<String>)>, String> {
+    let row = conn.query_row(
+        "SELECT path, icon_location FROM apps WHERE id = ?1",
+        params![app_id],
+        |r| Ok((r.get(0)?, non_empty(r.get(1)?))),
+    );
+    match row {
+        Ok(source) => Ok(Some(source)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// The apps without a stored icon, as `(id, path, icon location)`.
+pub fn read_apps_without_icon(
+    conn: &Connection,
+) -> Result<Vec<(String, String, Option<String>)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.id, a.path, a.icon_location
+             FROM apps a
+             LEFT JOIN app_icons i ON i.app_id = a.id
+             WHERE i.app_id IS NULL",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, non_empty(r.get(2)?))))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<rusqlite::Result<_>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Persists an icon extracted for `app_id`, as long as the app is still saved with `path`.
+/// Returns the blob hash, or `None` when the app is unknown or has moved on.
+pub fn write_app_icon(
+    conn: &mut Connection,
+    app_id: &str,
+    path: &str,
+    mime: &str,
+    bytes: &[u8],
+) -> Result<Option<String>, String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let matches: i64 = tx
+        .query_row(
+            "SELECT COUNT(1) FROM apps WHERE id = ?1 AND path = ?2",
+            params![app_id, path],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if matches == 0 {
+        return Ok(None);
+    }
+    let now = now_millis();
+    let hash = put_icon_blob(&tx, mime, bytes, now).map_err(|e| e.to_string())?;
+    link_app_icon(&tx, app_id, &hash, now).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(Some(hash))
+}
+
+pub fn read_ui_settings(conn: &Connection) -> UiSettings {
+    let settings_raw: String = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'ui_settings' LIMIT 1",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap_or_else(|_| String::new());
+    if settings_raw.trim().is_empty() {
+        return UiSettings::default();
+    }
+    serde_json::from_str::<UiSettings>(&settings_raw).unwrap_or_else(|_| UiSettings::default())
+}
+
+pub fn read_hotkey_bindings(conn: &Connection) -> Result<Vec<HotkeyBinding>, String> {
+    let mut stmt = conn
+        .prepare("SELECT accelerator, action, target, mode FROM hotkeys ORDER BY position ASC")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    let mut bindings = Vec::new();
+    for row in rows {
+        let (accelerator, kind, target, mode) = row.map_err(|e| e.to_string())?;
+        if let Some(action) = HotkeyAction::from_row(&kind, target) {
+            bindings.push(HotkeyBinding {
+                accelerator,
+                action,
+                mode: HotkeyMode::from_str(&mode),
+            });
+        }
+    }
+    Ok(bindings)
+}
+
+pub fn write_hotkey_bindings(conn: &mut Connection, bindings: &[HotkeyBinding]) -> Result<(), String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM hotkeys", []).map_err(|e| e.to_string())?;
+    for (pos, binding) in bindings.iter().enumerate() {
+        let (kind, target) = binding.action.to_row();
+        tx.execute(
+            "INSERT INTO hotkeys(accelerator, action, target, mode, position)
+             VALUES(?1, ?2, ?3, ?4, ?5)",
+            params![binding.accelerator, kind, target, binding.mode.as_str(), pos as i64],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+pub fn read_app_entry(conn: &Connection, app_id: &str) -> Result<Option<AppEntry>, String> {
+    let row = conn.query_row(
+        "SELECT id, name, path, args, added_at, pinned, updated_at, kind, browser, working_dir,
+                description, icon_location
+         FROM apps WHERE id = ?1",
+        params![app_id],
+        |r| {
+            let args: String = r.get(3)?;
+            let path: String = r.get(2)?;
+            let kind = EntryKind::parse(&r.get::<_, String>(7)?)
+                .unwrap_or_else(|| EntryKind::infer(&path));
+            Ok(AppEntry {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                path,
+                args: if args.trim().is_empty() { None } else { Some(args) },
+                icon: None,
+                icon_hash: None,
+                pinned: r.get(5)?,
+                added_at: r.get(4)?,
+                updated_at: r.get(6)?,
+                kind: Some(kind),
+                browser: non_empty(r.get(8)?),
+                working_dir: non_empty(r.get(9)?),
+                description: non_empty(r.get(10)?),
+                icon_location: non_empty(r.get(11)?),
+            })
+        },
+    );
+    match row {
+        Ok(entry) => Ok(Some(entry)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// How many launches `launch_history` keeps; older rows are trimmed on insert.
+const LAUNCH_HISTORY_LIMIT: i64 = 200;
+
+pub fn insert_app_launch(conn: &Connection, app_id: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO launch_history(app_id, launched_at) VALUES(?1, ?2)",
+        params![app_id, now_millis()],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM launch_history WHERE rowid NOT IN
+           (SELECT rowid FROM launch_history ORDER BY launched_at DESC LIMIT ?1)",
+        params![LAUNCH_HISTORY_LIMIT],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Most recently launched app ids, newest first, skipping apps that no longer exist.
+pub fn read_recent_app_ids(conn: &Connection, limit: usize) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT h.app_id FROM launch_history h
+             JOIN apps a ON a.id = h.app_id
+             GROUP BY h.app_id
+             ORDER BY MAX(h.launched_at) DESC
+             LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![limit as i64], |r| r.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+pub fn read_window_geometry(conn: &Connection) -> Option<WindowGeometry> {
+    let raw: String = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'window_geometry' LIMIT 1",
+            [],
+            |r| r.get(0),
+        )
+        .ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+pub fn write_window_geometry(conn: &Connection, geometry: &WindowGeometry) -> Result<(), String> {
+    let json = serde_json::to_string(geometry).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO meta(key, value) VALUES('window_geometry', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![json],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A scratch directory under the system temp dir, removed when dropped.
+    pub(crate) struct TempDir(pub(crate) PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "quick-launcher-{}-{}-{}",
+                name,
+                std::process::id(),
+                now_millis()
+            ));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+
+        pub(crate) fn db(&self) -> PathBuf {
+            self.0.join("launcher.db")
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    pub(crate) fn app(id: &str, path: &str) -> AppEntry {
+        AppEntry {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            path: path.to_string(),
+            args: None,
+            icon: None,
+            icon_hash: None,
+            pinned: false,
+            added_at: 1,
+            updated_at: 0,
+            kind: None,
+            browser: None,
+            working_dir: None,
+            description: None,
+            icon_location: None,
+        }
+    }
+
+    pub(crate) fn state(apps: Vec<AppEntry>) -> LauncherState {
+        LauncherState {
+            version: 1,
+            active_group_id: "g1".to_string(),
+            groups: vec![Group {
+                id: "g1".to_string(),
+                name: "Work".to_string(),
+                apps,
+                created_at: 0,
+                updated_at: 0,
+            }],
+            settings: UiSettings::default(),
+            revision: 0,
+        }
+    }
+
+    #[test]
+    fn empty_database_reads_as_nothing_saved() {
+        let dir = TempDir::new("empty");
+        let conn = open(&dir.db()).unwrap();
+        assert!(read_launcher_state(&conn).unwrap().is_none());
+        assert_eq!(read_revision(&conn), 0);
+    }
+
+    #[test]
+    fn write_then_read_round_trips() {
+        let dir = TempDir::new("roundtrip");
+        let mut conn = open(&dir.db()).unwrap();
+        let mut a = app("a", "/usr/bin/env");
+        a.args = Some("--flag \"x y\"".to_string());
+        a.pinned = true;
+        a.working_dir = Some("/tmp".to_string());
+        a.description = Some("Runs things".to_string());
+        a.icon_location = Some("/usr/share/icons/env.png".to_string());
+        let mut next = state(vec![a, app("b", "https://example.com")]);
+        next.settings.card_width = 150;
+        assert_eq!(write_launcher_state(&mut conn, &next).unwrap(), 1);
+
+        let read = read_launcher_state(&conn).unwrap().unwrap();
+        assert_eq!(read.revision, 1);
+        assert_eq!(read.active_group_id, "g1");
+        assert_eq!(read.settings.card_width, 150);
+        let apps = &read.groups[0].apps;
+        assert_eq!(
+            apps.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert_eq!(apps[0].args.as_deref(), Some("--flag \"x y\""));
+        assert!(apps[0].pinned);
+        assert_eq!(apps[0].working_dir.as_deref(), Some("/tmp"));
+        assert_eq!(apps[0].description.as_deref(), Some("Runs things"));
+        assert_eq!(apps[0].icon_location.as_deref(), Some("/usr/share/icons/env.png"));
+        assert_eq!(apps[1].working_dir, None);
+        assert_eq!(apps[1].kind, Some(EntryKind::Url));
+        assert!(read.groups[0].created_at > 0);
+
+        // Reopening sees the same data
+        drop(conn);
+        let conn = open(&dir.db()).unwrap();
+        let again = read_launcher_state(&conn).unwrap().unwrap();
+        assert_eq!(again.groups[0].apps.len(), 2);
+    }
+
+    #[test]
+    fn removed_apps_and_groups_are_deleted() {
+        let dir = TempDir::new("delete");
+        let mut conn = open(&dir.db()).unwrap();
+        let mut next = state(vec![app("a", "/a"), app("b", "/b")]);
+        next.revision = write_launcher_state(&mut conn, &next).unwrap();
+        next.groups[0].apps.remove(0);
+        next.revision = write_launcher_state(&mut conn, &next).unwrap();
+        let read = read_launcher_state(&conn).unwrap().unwrap();
+        assert_eq!(read.groups[0].apps.len(), 1);
+        assert_eq!(read.groups[0].apps[0].id, "b");
+        assert!(read_app_entry(&conn, "a").unwrap().is_none());
+    }
+
+    #[test]
+    fn icons_of_several_apps_are_read_at_once() {
+        let dir = TempDir::new("icons");
+        let mut conn = open(&dir.db()).unwrap();
+        let next = state(vec![app("a", "/a"), app("b", "/b"), app("c", "/c")]);
+        write_launcher_state(&mut conn, &next).unwrap();
+        let shared = write_app_icon(&mut conn, "a", "/a", "image/png", b"one").unwrap();
+        write_app_icon(&mut conn, "b", "/b", "image/png", b"one").unwrap();
+
+        let icons = read_app_icons(&conn, &["a", "b", "c", "gone"]).unwrap();
+        assert_eq!(icons.len(), 2);
+        assert_eq!(
+            read_apps_without_icon(&conn).unwrap(),
+            [("c".to_string(), "/c".to_string(), None)]
+        );
+        assert_eq!(Some(&icons["a"].hash), shared.as_ref());
+        assert_eq!(icons["b"].data, b"one");
+        assert!(read_app_icons(&conn, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn stale_revision_is_rejected() {
+        let dir = TempDir::new("stale");
+        let mut conn = open(&dir.db()).unwrap();
+        let first = state(vec![app("a", "/a")]);
+        assert_eq!(write_launcher_state(&mut conn, &first).unwrap(), 1);
+        // Still at revision 0, as if read before the write above
+        let err = write_launcher_state(&mut conn, &first).unwrap_err();
+        assert!(err.starts_with(STALE_STATE_ERROR), "{}", err);
+
+        let mut current = read_launcher_state(&conn).unwrap().unwrap();
+        current.groups[0].name = "Home".to_string();
+        assert_eq!(write_launcher_state(&mut conn, &current).unwrap(), 2);
+        assert_eq!(read_revision(&conn), 2);
+    }
+
+    fn logged(conn: &Connection, revision: u64) -> Vec<(String, String)> {
+        read_revision_changes(conn, revision)
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.entity_id, c.field))
+            .collect()
+    }
+
+    #[test]
+    fn removing_an_app_leaves_its_siblings_alone() {
+        let dir = TempDir::new("siblings");
+        let mut conn = open(&dir.db()).unwrap();
+        let mut next = state(vec![app("a", "/a"), app("b", "/b"), app("c", "/c")]);
+        next.revision = write_launcher_state(&mut conn, &next).unwrap();
+        let before = read_launcher_state(&conn).unwrap().unwrap();
+
+        next.groups[0].apps.remove(0);
+        next.revision = write_launcher_state(&mut conn, &next).unwrap();
+        assert_eq!(
+            logged(&conn, next.revision),
+            [("a".to_string(), "*".to_string())]
+        );
+        let after = read_launcher_state(&conn).unwrap().unwrap();
+        for (old, new) in before.groups[0].apps[1..].iter().zip(&after.groups[0].apps) {
+            assert_eq!(old.updated_at, new.updated_at, "{}", old.id);
+        }
+    }
+
+    #[test]
+    fn a_move_logs_only_the_apps_whose_order_changed() {
+        let dir = TempDir::new("move");
+        let mut conn = open(&dir.db()).unwrap();
+        let mut next = state(vec![
+            app("a", "/a"),
+            app("b", "/b"),
+            app("c", "/c"),
+            app("d", "/d"),
+        ]);
+        next.revision = write_launcher_state(&mut conn, &next).unwrap();
+
+        // b and c swap; a and d stay put
+        next.groups[0].apps.swap(1, 2);
+        next.revision = write_launcher_state(&mut conn, &next).unwrap();
+        let mut changes = logged(&conn, next.revision);
+        changes.sort();
+        assert_eq!(
+            changes,
+            [
+                ("b".to_string(), "position".to_string()),
+                ("c".to_string(), "position".to_string())
+            ]
+        );
+        // Order alone isn't an edit
+        let read = read_launcher_state(&conn).unwrap().unwrap();
+        assert!(read.groups[0]
+            .apps
+            .iter()
+            .all(|a| a.updated_at == read.groups[0].apps[0].updated_at));
+    }
+
+    #[test]
+    fn migrations_run_only_while_the_schema_is_behind() {
+        let dir = TempDir::new("schema-version");
+        let conn = open(&dir.db()).unwrap();
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        conn.execute("INSERT INTO groups VALUES('g1', 'Work', 0, 0, 0)", [])
+            .unwrap();
+        drop(conn);
+
+        // A current database is opened as it is; the created_at backfill doesn't run
+        let conn = open(&dir.db()).unwrap();
+        let created_at = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT created_at FROM groups", [], |r| r.get(0))
+                .unwrap()
+        };
+        assert_eq!(created_at(&conn), 0);
+        conn.pragma_update(None, "user_version", 0).unwrap();
+        drop(conn);
+        assert!(created_at(&open(&dir.db()).unwrap()) > 0);
+    }
+
+    #[test]
+    fn old_schema_is_migrated() {
+        let dir = TempDir::new("migrate");
+        {
+            let conn = Connection::open(dir.db()).unwrap();
+            conn.execute_batch(
+                r#"
+CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE TABLE groups (id TEXT PRIMARY KEY, name TEXT NOT NULL, position INTEGER NOT NULL);
+CREATE TABLE apps (
+  id TEXT PRIMARY KEY, group_id TEXT NOT NULL, name TEXT NOT NULL, path TEXT NOT NULL,
+  args TEXT NOT NULL, icon TEXT NOT NULL DEFAULT '', position INTEGER NOT NULL,
+  added_at INTEGER NOT NULL
+);
+INSERT INTO meta VALUES('active_group_id', 'g1');
+INSERT INTO meta VALUES('ui_settings', '{"toggleHotkey":"Alt+Space","cardSize":140}');
+INSERT INTO groups VALUES('g1', 'Old', 0);
+INSERT INTO apps VALUES('a', 'g1', 'Site', 'https://example.com', '',
+  'data:image/png;base64,iVBORw0KGgo=', 0, 42);
+INSERT INTO apps VALUES('b', 'g1', 'Tmp', '/tmp', '', '', 1, 43);
+"#,
+            )
+            .unwrap();
+        }
+        let conn = open(&dir.db()).unwrap();
+        for column in ["pinned", "updated_at", "kind", "browser"] {
+            assert!(table_has_column(&conn, "apps", column), "apps.{}", column);
+        }
+        assert!(table_has_column(&conn, "groups", "created_at"));
+        let created_at: i64 = conn
+            .query_row("SELECT created_at FROM groups WHERE id = 'g1'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(created_at, 42);
+
+        let read = read_launcher_state(&conn).unwrap().unwrap();
+        let apps = &read.groups[0].apps;
+        assert_eq!(apps[0].updated_at, 42);
+        assert_eq!(apps[0].kind, Some(EntryKind::Url));
+        assert_eq!(apps[1].kind, Some(EntryKind::Folder));
+        // The inline icon moved into icon_blobs
+        assert!(apps[0].icon_hash.is_some());
+        assert_eq!(
+            read_app_icon(&conn, "a").unwrap().unwrap().mime,
+            "image/png"
+        );
+        // The alias and the single toggle hotkey carried over
+        assert_eq!(read.settings.card_width, 140);
+        let hotkeys = read_hotkey_bindings(&conn).unwrap();
+        assert_eq!(hotkeys.len(), 1);
+        assert_eq!(hotkeys[0].accelerator, "alt+space");
+
+        // Opening again is a no-op
+        drop(conn);
+        let conn = open(&dir.db()).unwrap();
+        assert_eq!(read_hotkey_bindings(&conn).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,683 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::{AppEntry, EntryKind, Group, LauncherState};
+use crate::storage;
+
+/// The library file kept in the sync folder.
+pub const LIBRARY_FILE: &str = "quick-launcher-library.json";
+const LIBRARY_FORMAT: u32 = 1;
+
+/// One group as it is mirrored to the sync folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryGroup {
+    pub id: String,
+    pub name: String,
+    pub position: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// One app as it is mirrored to the sync folder. Icons stay local; they are
+/// extracted again from the path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryApp {
+    pub id: String,
+    pub group_id: String,
+    pub name: String,
+    pub path: String,
+    #[serde(default)]
+    pub args: String,
+    #[serde(default)]
+    pub pinned: bool,
+    pub position: i64,
+    #[serde(default)]
+    pub added_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+    /// An [`EntryKind`] name; empty in files from before kinds were stored.
+    #[serde(default)]
+    pub kind: String,
+    #[serde(default)]
+    pub browser: String,
+    #[serde(default)]
+    pub working_dir: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub icon_location: String,
+    /// The stored icon an undone deletion links again. Never written to the sync folder.
+    #[serde(skip)]
+    pub icon_hash: Option<String>,
+}
+
+/// The groups and apps of a profile, sorted by id so the same library always
+/// serializes to the same file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Library {
+    pub format: u32,
+    pub groups: Vec<LibraryGroup>,
+    pub apps: Vec<LibraryApp>,
+}
+
+/// The library as of the last sync, kept next to the database. It tells an edit on
+/// one side from a deletion on the other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncBase {
+    folder: PathBuf,
+    library: Library,
+}
+
+/// What a sync did.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOutcome {
+    /// Remote edits were written to the database.
+    pub imported: bool,
+    /// The library file in the sync folder was rewritten.
+    pub exported: bool,
+}
+
+trait Entity: Clone + PartialEq + Serialize + DeserializeOwned {
+    fn id(&self) -> &str;
+    fn updated_at(&self) -> i64;
+    fn set_updated_at(&mut self, at: i64);
+
+    /// Equal apart from when it was last changed.
+    fn same_as(&self, other: &Self) -> bool {
+        let mut a = self.clone();
+        a.set_updated_at(0);
+        let mut b = other.clone();
+        b.set_updated_at(0);
+        a == b
+    }
+}
+
+impl Entity for LibraryGroup {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn updated_at(&self) -> i64 {
+        self.updated_at
+    }
+    fn set_updated_at(&mut self, at: i64) {
+        self.updated_at = at;
+    }
+}
+
+impl Entity for LibraryApp {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn updated_at(&self) -> i64 {
+        self.updated_at
+    }
+    fn set_updated_at(&mut self, at: i64) {
+        self.updated_at = at;
+    }
+}
+
+impl Library {
+    pub(crate) fn from_state(state: &LauncherState) -> Self {
+        let mut groups = Vec::new();
+        let mut apps = Vec::new();
+        for (group_pos, group) in state.groups.iter().enumerate() {
+            groups.push(LibraryGroup {
+                id: group.id.clone(),
+                name: group.name.clone(),
+                position: group_pos as i64,
+                updated_at: group.updated_at,
+            });
+            for (app_pos, app) in group.apps.iter().enumerate() {
+                apps.push(LibraryApp {
+                    id: app.id.clone(),
+                    group_id: group.id.clone(),
+                    name: app.name.clone(),
+                    path: app.path.clone(),
+                    args: app.args.clone().unwrap_or_default(),
+                    pinned: app.pinned,
+                    position: app_pos as i64,
+                    added_at: app.added_at,
+                    updated_at: app.updated_at,
+                    kind: app.resolved_kind().as_str().to_string(),
+                    browser: app.browser.clone().unwrap_or_default(),
+                    working_dir: app.working_dir.clone().unwrap_or_default(),
+                    description: app.description.clone().unwrap_or_default(),
+                    icon_location: app.icon_location.clone().unwrap_or_default(),
+                    icon_hash: None,
+                });
+            }
+        }
+        let mut library = Library {
+            format: LIBRARY_FORMAT,
+            groups,
+            apps,
+        };
+        library.sort();
+        library
+    }
+
+    fn sort(&mut self) {
+        self.groups.sort_by(|a, b| a.id.cmp(&b.id));
+        self.apps.sort_by(|a, b| a.id.cmp(&b.id));
+    }
+
+    /// Numbers groups, and the apps in each group, from 0 in their current order and
+    /// moves apps whose group is gone into the first group, the way the database
+    /// would store them.
+    pub(crate) fn normalize(&mut self) {
+        self.groups
+            .sort_by(|a, b| a.position.cmp(&b.position).then_with(|| a.id.cmp(&b.id)));
+        let group_pos: HashMap<String, i64> = self
+            .groups
+            .iter_mut()
+            .enumerate()
+            .map(|(pos, g)| {
+                g.position = pos as i64;
+                (g.id.clone(), g.position)
+            })
+            .collect();
+        let Some(first) = self.groups.first().map(|g| g.id.clone()) else {
+            self.apps.clear();
+            return;
+        };
+        for app in &mut self.apps {
+            if !group_pos.contains_key(&app.group_id) {
+                app.group_id = first.clone();
+                app.position = i64::MAX;
+            }
+        }
+        self.apps.sort_by(|a, b| {
+            (group_pos[&a.group_id], a.position, &a.id).cmp(&(
+                group_pos[&b.group_id],
+                b.position,
+                &b.id,
+            ))
+        });
+        let mut next: HashMap<String, i64> = HashMap::new();
+        for app in &mut self.apps {
+            let pos = next.entry(app.group_id.clone()).or_insert(0);
+            app.position = *pos;
+            *pos += 1;
+        }
+        self.sort();
+    }
+
+    /// Equal apart from when anything was last changed.
+    fn same_as(&self, other: &Self) -> bool {
+        fn same<T: Entity>(a: &[T], b: &[T]) -> bool {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.same_as(y))
+        }
+        same(&self.groups, &other.groups) && same(&self.apps, &other.apps)
+    }
+
+    /// Groups with their apps in position order, as the launcher keeps them.
+    pub(crate) fn into_groups(self) -> Vec<Group> {
+        let mut groups = self.groups;
+        groups.sort_by(|a, b| a.position.cmp(&b.position).then_with(|| a.id.cmp(&b.id)));
+        let mut apps = self.apps;
+        apps.sort_by(|a, b| a.position.cmp(&b.position).then_with(|| a.id.cmp(&b.id)));
+        let mut out: Vec<Group> = groups
+            .into_iter()
+            .map(|g| Group {
+                id: g.id,
+                name: g.name,
+                apps: Vec::new(),
+                created_at: 0,
+                updated_at: g.updated_at,
+            })
+            .collect();
+        for app in apps {
+            let Some(group) = out.iter_mut().find(|g| g.id == app.group_id) else {
+                continue;
+            };
+            group.apps.push(AppEntry {
+                id: app.id,
+                name: app.name,
+                path: app.path,
+                args: Some(app.args).filter(|a| !a.trim().is_empty()),
+                icon: None,
+                icon_hash: app.icon_hash,
+                pinned: app.pinned,
+                added_at: app.added_at,
+                updated_at: app.updated_at,
+                kind: EntryKind::parse(&app.kind),
+                browser: Some(app.browser).filter(|b| !b.trim().is_empty()),
+                working_dir: Some(app.working_dir).filter(|d| !d.trim().is_empty()),
+                description: Some(app.description).filter(|d| !d.trim().is_empty()),
+                icon_location: Some(app.icon_location).filter(|i| !i.trim().is_empty()),
+            });
+        }
+        out
+    }
+}
+
+pub fn library_file(folder: &Path) -> PathBuf {
+    folder.join(LIBRARY_FILE)
+}
+
+fn base_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("sync-base.json")
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str(&raw)
+            .map(Some)
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// Writes next to `path` first and renames, so a sync tool never picks up half a file.
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.tmp", name));
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+/// The later edit wins. A tie goes to the larger serialization, so every machine
+/// picks the same one.
+fn newer<T: Entity>(a: &T, b: &T) -> T {
+    let key = |e: &T| (e.updated_at(), serde_json::to_string(e).unwrap_or_default());
+    if key(a) >= key(b) {
+        a.clone()
+    } else {
+        b.clone()
+    }
+}
+
+/// Both sides changed `base`: a field only one side changed takes that side's value, and
+/// a field both changed takes the [`newer`] side's, so a move on one machine and a
+/// rename on another both survive.
+fn merge_fields<T: Entity>(base: &T, local: &T, remote: &T) -> T {
+    let winner = newer(local, remote);
+    let fields = |e: &T| match serde_json::to_value(e) {
+        Ok(Value::Object(map)) => Some(map),
+        _ => None,
+    };
+    let (Some(b), Some(l), Some(r), Some(mut out)) =
+        (fields(base), fields(local), fields(remote), fields(&winner))
+    else {
+        return winner;
+    };
+    for (mine, theirs) in [(&l, &r), (&r, &l)] {
+        for (key, value) in mine {
+            if theirs.get(key) == b.get(key) {
+                out.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    let Ok(mut merged) = serde_json::from_value::<T>(Value::Object(out)) else {
+        return winner;
+    };
+    merged.set_updated_at(local.updated_at().max(remote.updated_at()));
+    merged
+}
+
+/// Three-way merge by id. A side that left an entity as it was in `base` takes the
+/// other side's edit or deletion; edits on both sides are merged field by field by
+/// [`merge_fields`], and an edit beats a deletion.
+fn merge<T: Entity>(base: &[T], local: &[T], remote: &[T]) -> Vec<T> {
+    let by_id = |list: &[T]| -> HashMap<String, T> {
+        list.iter()
+            .map(|e| (e.id().to_string(), e.clone()))
+            .collect()
+    };
+    let (base, local, remote) = (by_id(base), by_id(local), by_id(remote));
+    let ids: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    let mut out = Vec::new();
+    for id in ids {
+        let b = base.get(id);
+        let unchanged = |e: &T| b.is_some_and(|b| b.same_as(e));
+        let merged = match (local.get(id), remote.get(id)) {
+            (Some(l), Some(r)) if l.same_as(r) => Some(newer(l, r)),
+            (Some(l), Some(r)) if unchanged(l) => Some(r.clone()),
+            (Some(l), Some(r)) if unchanged(r) => Some(l.clone()),
+            (Some(l), Some(r)) => Some(match b {
+                Some(b) => merge_fields(b, l, r),
+                None => newer(l, r),
+            }),
+            (Some(l), None) if unchanged(l) => None,
+            (None, Some(r)) if unchanged(r) => None,
+            (Some(e), None) | (None, Some(e)) => Some(e.clone()),
+            (None, None) => None,
+        };
+        out.extend(merged);
+    }
+    out
+}
+
+/// Merges the database at `db_path` with the library file in `folder` and brings both
+/// up to date: remote edits are written to the database, and the merged library to
+/// the folder. The first sync into a folder merges without deleting anything.
+pub fn sync_folder(
+    conn: &mut Connection,
+    db_path: &Path,
+    folder: &Path,
+) -> Result<SyncOutcome, String> {
+    let file = library_file(folder);
+    let base_file = base_path(db_path);
+    let base = read_json::<SyncBase>(&base_file)?
+        .filter(|b| b.folder == folder)
+        .map(|b| b.library);
+    let remote: Option<Library> = read_json(&file)?;
+
+    let local_state = storage::read_launcher_state(conn)?;
+    let local = local_state
+        .as_ref()
+        .map(Library::from_state)
+        .unwrap_or_else(|| Library {
+            format: LIBRARY_FORMAT,
+            groups: Vec::new(),
+            apps: Vec::new(),
+        });
+    let (base_groups, base_apps) = base
+        .as_ref()
+        .map(|b| (b.groups.as_slice(), b.apps.as_slice()))
+        .unwrap_or((&[], &[]));
+
+    let mut merged = match &remote {
+        Some(remote) => {
+            let mut merged = Library {
+                format: LIBRARY_FORMAT,
+                groups: merge(base_groups, &local.groups, &remote.groups),
+                apps: merge(base_apps, &local.apps, &remote.apps),
+            };
+            merged.normalize();
+            merged
+        }
+        None => local.clone(),
+    };
+
+    let mut outcome = SyncOutcome::default();
+    if !merged.same_as(&local) {
+        let settings = storage::read_ui_settings(conn);
+        let groups = merged.clone().into_groups();
+        let active_group_id = local_state
+            .as_ref()
+            .map(|s| s.active_group_id.clone())
+            .filter(|id| groups.iter().any(|g| g.id == *id))
+            .or_else(|| groups.first().map(|g| g.id.clone()))
+            .unwrap_or_default();
+        let state = LauncherState {
+            version: 1,
+            active_group_id,
+            groups,
+            settings,
+            revision: storage::read_revision(conn),
+        };
+        storage::write_launcher_state(conn, &state)?;
+        outcome.imported = true;
+        // The database stamps what changed; the folder gets the same times, so the next
+        // sync has nothing to do
+        if let Some(saved) = storage::read_launcher_state(conn)? {
+            merged = Library::from_state(&saved);
+        }
+    }
+    if remote.as_ref() != Some(&merged) {
+        std::fs::create_dir_all(folder).map_err(|e| e.to_string())?;
+        write_json(&file, &merged)?;
+        outcome.exported = true;
+    }
+    if outcome.imported || outcome.exported || base.as_ref() != Some(&merged) {
+        write_json(
+            &base_file,
+            &SyncBase {
+                folder: folder.to_path_buf(),
+                library: merged,
+            },
+        )?;
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::{app, state, TempDir};
+
+    fn group(id: &str, position: i64) -> LibraryGroup {
+        LibraryGroup {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            position,
+            updated_at: 1,
+        }
+    }
+
+    fn lib_app(id: &str, group_id: &str, position: i64) -> LibraryApp {
+        LibraryApp {
+            id: id.to_string(),
+            group_id: group_id.to_string(),
+            name: id.to_uppercase(),
+            path: format!("/bin/{}", id),
+            args: String::new(),
+            pinned: false,
+            position,
+            added_at: 1,
+            updated_at: 1,
+            kind: "app".to_string(),
+            browser: String::new(),
+            working_dir: String::new(),
+            description: String::new(),
+            icon_location: String::new(),
+            icon_hash: None,
+        }
+    }
+
+    fn renamed(mut app: LibraryApp, name: &str, at: i64) -> LibraryApp {
+        app.name = name.to_string();
+        app.updated_at = at;
+        app
+    }
+
+    fn library(groups: Vec<LibraryGroup>, apps: Vec<LibraryApp>) -> Library {
+        let mut library = Library {
+            format: LIBRARY_FORMAT,
+            groups,
+            apps,
+        };
+        library.sort();
+        library
+    }
+
+    fn merged(base: &Library, local: &Library, remote: &Library) -> Library {
+        let mut out = library(
+            merge(&base.groups, &local.groups, &remote.groups),
+            merge(&base.apps, &local.apps, &remote.apps),
+        );
+        out.normalize();
+        out
+    }
+
+    /// `(id, group, position, name)` of every app, in id order.
+    fn apps(library: &Library) -> Vec<(&str, &str, i64, &str)> {
+        library
+            .apps
+            .iter()
+            .map(|a| {
+                (
+                    a.id.as_str(),
+                    a.group_id.as_str(),
+                    a.position,
+                    a.name.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn an_edit_beats_a_deletion_on_the_other_side() {
+        let base = library(vec![group("g", 0)], vec![lib_app("a", "g", 0)]);
+        let deleted = library(vec![group("g", 0)], vec![]);
+        let edited = library(
+            vec![group("g", 0)],
+            vec![renamed(lib_app("a", "g", 0), "Edited", 5)],
+        );
+
+        assert_eq!(
+            apps(&merged(&base, &deleted, &edited)),
+            [("a", "g", 0, "Edited")]
+        );
+        assert_eq!(
+            apps(&merged(&base, &edited, &deleted)),
+            [("a", "g", 0, "Edited")]
+        );
+        // Without an edit the deletion goes through
+        assert!(merged(&base, &deleted, &base).apps.is_empty());
+        assert!(merged(&base, &base, &deleted).apps.is_empty());
+    }
+
+    #[test]
+    fn the_later_of_two_edits_to_one_field_wins_on_both_machines() {
+        let base = library(vec![group("g", 0)], vec![lib_app("a", "g", 0)]);
+        let local = library(
+            vec![group("g", 0)],
+            vec![renamed(lib_app("a", "g", 0), "Local", 5)],
+        );
+        let remote = library(
+            vec![group("g", 0)],
+            vec![renamed(lib_app("a", "g", 0), "Remote", 9)],
+        );
+        assert_eq!(
+            apps(&merged(&base, &local, &remote)),
+            [("a", "g", 0, "Remote")]
+        );
+        assert_eq!(
+            apps(&merged(&base, &remote, &local)),
+            [("a", "g", 0, "Remote")]
+        );
+
+        // A tie is settled the same way whichever side is local
+        let remote = library(
+            vec![group("g", 0)],
+            vec![renamed(lib_app("a", "g", 0), "Remote", 5)],
+        );
+        assert_eq!(
+            merged(&base, &local, &remote),
+            merged(&base, &remote, &local)
+        );
+    }
+
+    #[test]
+    fn a_first_merge_without_a_base_keeps_both_sides() {
+        let none = library(vec![], vec![]);
+        let local = library(
+            vec![group("g", 0)],
+            vec![
+                lib_app("a", "g", 0),
+                renamed(lib_app("c", "g", 1), "Old", 2),
+            ],
+        );
+        let remote = library(
+            vec![group("g", 0), group("h", 1)],
+            vec![
+                lib_app("b", "h", 0),
+                renamed(lib_app("c", "g", 1), "New", 3),
+            ],
+        );
+        assert_eq!(
+            apps(&merged(&none, &local, &remote)),
+            [("a", "g", 0, "A"), ("b", "h", 0, "B"), ("c", "g", 1, "New")]
+        );
+    }
+
+    #[test]
+    fn a_reorder_on_one_side_keeps_a_rename_on_the_other() {
+        let base = library(
+            vec![group("g", 0)],
+            vec![lib_app("a", "g", 0), lib_app("b", "g", 1)],
+        );
+        // Moving `a` behind `b` stamps both, later than the rename
+        let mut moved_a = lib_app("a", "g", 1);
+        moved_a.updated_at = 9;
+        let mut moved_b = lib_app("b", "g", 0);
+        moved_b.updated_at = 9;
+        let reordered = library(vec![group("g", 0)], vec![moved_a, moved_b]);
+        let edited = library(
+            vec![group("g", 0)],
+            vec![
+                renamed(lib_app("a", "g", 0), "Renamed", 5),
+                lib_app("b", "g", 1),
+            ],
+        );
+
+        let expected = [("a", "g", 1, "Renamed"), ("b", "g", 0, "B")];
+        let out = merged(&base, &reordered, &edited);
+        assert_eq!(apps(&out), expected);
+        assert_eq!(out.apps[0].updated_at, 9);
+        assert_eq!(apps(&merged(&base, &edited, &reordered)), expected);
+    }
+
+    #[test]
+    fn an_app_edited_in_a_group_deleted_elsewhere_moves_to_the_first_group() {
+        let base = library(
+            vec![group("g", 0), group("h", 1)],
+            vec![lib_app("a", "g", 0), lib_app("x", "h", 0)],
+        );
+        let deleted = library(vec![group("g", 0)], vec![lib_app("a", "g", 0)]);
+        let edited = library(
+            vec![group("g", 0), group("h", 1)],
+            vec![
+                lib_app("a", "g", 0),
+                renamed(lib_app("x", "h", 0), "Edited", 5),
+            ],
+        );
+
+        let out = merged(&base, &deleted, &edited);
+        assert_eq!(out.groups, vec![group("g", 0)]);
+        assert_eq!(apps(&out), [("a", "g", 0, "A"), ("x", "g", 1, "Edited")]);
+    }
+
+    #[test]
+    fn syncing_a_folder_merges_first_and_then_carries_deletions() {
+        let dir = TempDir::new("sync");
+        let folder = dir.0.join("shared");
+        let db = dir.db();
+        let mut conn = storage::open(&db).unwrap();
+        storage::write_launcher_state(&mut conn, &state(vec![app("a", "/bin/a")])).unwrap();
+
+        // Another machine already put a library there
+        let mut theirs = Library::from_state(&state(vec![app("b", "/bin/b")]));
+        theirs.normalize();
+        std::fs::create_dir_all(&folder).unwrap();
+        write_json(&library_file(&folder), &theirs).unwrap();
+
+        let outcome = sync_folder(&mut conn, &db, &folder).unwrap();
+        assert!(outcome.imported && outcome.exported);
+        let ids = |conn: &Connection| -> Vec<String> {
+            let state = storage::read_launcher_state(conn).unwrap().unwrap();
+            state.groups[0].apps.iter().map(|a| a.id.clone()).collect()
+        };
+        assert_eq!(ids(&conn), ["a", "b"]);
+        let file: Library = read_json(&library_file(&folder)).unwrap().unwrap();
+        assert_eq!(file.apps.len(), 2);
+
+        // Nothing changed since, so a second sync does nothing
+        let outcome = sync_folder(&mut conn, &db, &folder).unwrap();
+        assert!(!outcome.imported && !outcome.exported);
+
+        // With a base, a deletion on the other machine now goes through
+        let mut file = file;
+        file.apps.retain(|a| a.id != "b");
+        write_json(&library_file(&folder), &file).unwrap();
+        let outcome = sync_folder(&mut conn, &db, &folder).unwrap();
+        assert!(outcome.imported && !outcome.exported);
+        assert_eq!(ids(&conn), ["a"]);
+    }
+}
@@ -0,0 +1,567 @@
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use quick_launcher_core::model::{
+    create_id, now_millis, AppEntry, EntryKind, Group, LauncherState,
+};
+use quick_launcher_core::{data_dir, launch, paths, storage, sync};
+
+/// Exit codes of the command-line interface.
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_NOT_FOUND: i32 = 3;
+pub const EXIT_INVALID_ENTRIES: i32 = 4;
+
+const SUBCOMMANDS: &[&str] = &[
+    "list", "launch", "add", "remove", "export", "import", "validate", "backup", "sync", "help",
+];
+
+const USAGE: &str = "\
+Usage: quick-launcher <command> [options] [--json] [--data-dir <dir>]
+
+Commands:
+  list [--group <name>]                  List groups and apps
+  launch <id or name>                    Launch a saved app
+  add <path>... [--group <name>] [--name <name>] [--args <args>] [--kind <kind>]
+                                         Add apps (the group is created if missing);
+                                         kind is executable, folder, document, url,
+                                         uwp, shell or command (default: from the path)
+  remove <id or name>...                 Remove apps
+  export [<file>]                        Write all groups, apps and settings as JSON
+  import <file>                          Replace all groups, apps and settings from JSON
+  validate                               List apps whose path no longer exists
+  backup [<file>]                        Copy the database (default: <data dir>/backups/)
+  sync [<folder>]                        Merge with the library file in the sync folder
+                                         (default: the folder set in the settings)
+
+The data directory is --data-dir, else $QUICK_LAUNCHER_DATA_DIR, else data/ next to
+the executable when portable.flag is there or the folder is writable, else the
+user's app data folder.
+
+Exit codes: 0 ok, 1 failed, 2 bad usage, 3 app or group not found,
+4 validate found broken apps.
+
+A running launcher picks up changes made here within a second.";
+
+struct CliError {
+    code: i32,
+    message: String,
+}
+
+impl CliError {
+    fn usage(message: impl Into<String>) -> Self {
+        Self {
+            code: EXIT_USAGE,
+            message: message.into(),
+        }
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            code: EXIT_NOT_FOUND,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        Self {
+            code: EXIT_FAILED,
+            message,
+        }
+    }
+}
+
+type CliResult = Result<i32, CliError>;
+
+/// Parsed options: positional values, `--flag value` pairs, and `--json`.
+struct Options {
+    json: bool,
+    values: Vec<String>,
+    flags: Vec<(String, String)>,
+}
+
+impl Options {
+    fn parse(args: &[String], known_flags: &[&str]) -> Result<Self, CliError> {
+        let mut options = Options {
+            json: false,
+            values: Vec::new(),
+            flags: Vec::new(),
+        };
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--json" {
+                options.json = true;
+            } else if let Some(name) = arg.strip_prefix("--") {
+                if !known_flags.contains(&name) {
+                    return Err(CliError::usage(format!("unknown option: {}", arg)));
+                }
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CliError::usage(format!("{} needs a value", arg)))?;
+                options.flags.push((name.to_string(), value.clone()));
+            } else {
+                options.values.push(arg.clone());
+            }
+        }
+        Ok(options)
+    }
+
+    fn flag(&self, name: &str) -> Option<&str> {
+        self.flags
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn max_values(&self, max: usize) -> Result<(), CliError> {
+        match self.values.get(max) {
+            Some(extra) => Err(CliError::usage(format!("unexpected argument: {}", extra))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Runs a subcommand when the first argument names one, without starting the webview.
+/// Returns the exit code, or `None` to start the launcher normally.
+pub fn run_from_env() -> Option<i32> {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    data_dir::init(data_dir::flag_from_args(&raw));
+    let args = data_dir::strip_flag(&raw);
+    let command = args.first()?;
+    if !SUBCOMMANDS.contains(&command.as_str()) {
+        return None;
+    }
+    attach_console();
+    let result = run(command, &args[1..]);
+    Some(match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e.message);
+            if e.code == EXIT_USAGE {
+                eprintln!("\n{}", USAGE);
+            }
+            e.code
+        }
+    })
+}
+
+/// Release builds use the GUI subsystem and start without a console; print to the
+/// one of the shell that started us instead.
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_console() {}
+
+fn run(command: &str, args: &[String]) -> CliResult {
+    match command {
+        "list" => list(Options::parse(args, &["group"])?),
+        "launch" => launch(Options::parse(args, &[])?),
+        "add" => add(Options::parse(args, &["group", "name", "args", "kind"])?),
+        "remove" => remove(Options::parse(args, &[])?),
+        "export" => export(Options::parse(args, &[])?),
+        "import" => import(Options::parse(args, &[])?),
+        "validate" => validate(Options::parse(args, &[])?),
+        "backup" => backup(Options::parse(args, &[])?),
+        "sync" => sync_library(Options::parse(args, &[])?),
+        _ => {
+            println!("{}", USAGE);
+            Ok(EXIT_OK)
+        }
+    }
+}
+
+fn open_db() -> Result<Connection, CliError> {
+    Ok(storage::open(&storage::default_db_path()?)?)
+}
+
+fn load_state(conn: &Connection) -> Result<LauncherState, CliError> {
+    Ok(
+        storage::read_launcher_state(conn)?.unwrap_or_else(|| LauncherState {
+            version: 1,
+            active_group_id: String::new(),
+            groups: Vec::new(),
+            settings: storage::read_ui_settings(conn),
+            revision: storage::read_revision(conn),
+        }),
+    )
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), CliError> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", text);
+    Ok(())
+}
+
+fn find_group<'a>(groups: &'a [Group], name: &str) -> Option<&'a Group> {
+    let wanted = name.trim().to_lowercase();
+    groups
+        .iter()
+        .find(|g| g.id == name || g.name.trim().to_lowercase() == wanted)
+}
+
+/// Finds an app by id, or else by its name ignoring case.
+fn find_app<'a>(groups: &'a [Group], target: &str) -> Option<&'a AppEntry> {
+    let apps = || groups.iter().flat_map(|g| g.apps.iter());
+    let wanted = target.trim().to_lowercase();
+    apps()
+        .find(|a| a.id == target)
+        .or_else(|| apps().find(|a| a.name.trim().to_lowercase() == wanted))
+}
+
+#[derive(Serialize)]
+struct AppRow<'a> {
+    group: &'a str,
+    #[serde(flatten)]
+    app: &'a AppEntry,
+}
+
+fn list(opts: Options) -> CliResult {
+    opts.max_values(0)?;
+    let state = load_state(&open_db()?)?;
+    let groups: Vec<&Group> = match opts.flag("group") {
+        Some(name) => vec![find_group(&state.groups, name)
+            .ok_or_else(|| CliError::not_found(format!("group not found: {}", name)))?],
+        None => state.groups.iter().collect(),
+    };
+    if opts.json {
+        let rows: Vec<AppRow> = groups
+            .iter()
+            .flat_map(|g| {
+                g.apps.iter().map(|app| AppRow {
+                    group: &g.name,
+                    app,
+                })
+            })
+            .collect();
+        print_json(&rows)?;
+        return Ok(EXIT_OK);
+    }
+    for group in groups {
+        println!("{}", group.name);
+        for app in &group.apps {
+            println!("  {}\t{}\t{}", app.id, app.name, app.path);
+        }
+    }
+    Ok(EXIT_OK)
+}
+
+fn launch(opts: Options) -> CliResult {
+    opts.max_values(1)?;
+    let target = opts
+        .values
+        .first()
+        .ok_or_else(|| CliError::usage("launch needs an app id or name"))?;
+    let conn = open_db()?;
+    let state = load_state(&conn)?;
+    let entry = find_app(&state.groups, target)
+        .ok_or_else(|| CliError::not_found(format!("app not found: {}", target)))?;
+    launch::launch_entry(entry)?;
+    let _ = storage::insert_app_launch(&conn, &entry.id);
+    if opts.json {
+        print_json(entry)?;
+    }
+    Ok(EXIT_OK)
+}
+
+fn add(opts: Options) -> CliResult {
+    if opts.values.is_empty() {
+        return Err(CliError::usage("add needs at least one path"));
+    }
+    if opts.flag("name").is_some() && opts.values.len() > 1 {
+        return Err(CliError::usage(
+            "--name only works when adding a single path",
+        ));
+    }
+    let kind = match opts.flag("kind") {
+        Some(kind) => Some(
+            EntryKind::parse(kind)
+                .ok_or_else(|| CliError::usage(format!("unknown kind: {}", kind)))?,
+        ),
+        None => None,
+    };
+    let mut conn = open_db()?;
+    let mut state = load_state(&conn)?;
+    let cwd = std::env::current_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let group_index = match opts.flag("group") {
+        Some(name) => find_group(&state.groups, name).map(|g| g.id.clone()),
+        None => Some(state.active_group_id.clone()).filter(|id| !id.is_empty()),
+    }
+    .and_then(|id| state.groups.iter().position(|g| g.id == id));
+    let group_index = match group_index {
+        Some(i) => i,
+        None => {
+            let name = opts.flag("group").map(str::trim).unwrap_or("Group-1");
+            state.groups.push(Group {
+                id: create_id(),
+                name: name.to_string(),
+                apps: Vec::new(),
+                created_at: 0,
+                updated_at: 0,
+            });
+            state.groups.len() - 1
+        }
+    };
+    if state.active_group_id.is_empty() {
+        state.active_group_id = state.groups[group_index].id.clone();
+    }
+
+    let group = &mut state.groups[group_index];
+    let now = now_millis();
+    let mut added: Vec<AppEntry> = Vec::new();
+    for raw in &opts.values {
+        // A command line is run as typed, not looked up as a file
+        let path = if kind == Some(EntryKind::Command) {
+            raw.trim().to_string()
+        } else {
+            let path = paths::resolve_against(raw, &cwd);
+            if state.settings.use_relative_path {
+                paths::make_relative_path(&path).unwrap_or(path)
+            } else {
+                path
+            }
+        };
+        // Same rule as adding from the window: a group holds each path once.
+        if group
+            .apps
+            .iter()
+            .chain(added.iter())
+            .any(|a| a.path == path)
+        {
+            continue;
+        }
+        let name = opts
+            .flag("name")
+            .map(str::to_string)
+            .unwrap_or_else(|| paths::suggest_app_name(&path));
+        added.push(AppEntry {
+            id: create_id(),
+            name,
+            path,
+            args: opts
+                .flag("args")
+                .map(str::to_string)
+                .filter(|a| !a.trim().is_empty()),
+            icon: None,
+            icon_hash: None,
+            pinned: false,
+            added_at: now,
+            updated_at: 0,
+            kind,
+            browser: None,
+            working_dir: None,
+            description: None,
+            icon_location: None,
+        });
+    }
+    group.apps.splice(0..0, added.iter().cloned());
+    storage::write_launcher_state(&mut conn, &state)?;
+
+    if opts.json {
+        print_json(&added)?;
+    } else {
+        for app in &added {
+            println!("added {}\t{}\t{}", app.id, app.name, app.path);
+        }
+    }
+    Ok(EXIT_OK)
+}
+
+fn remove(opts: Options) -> CliResult {
+    if opts.values.is_empty() {
+        return Err(CliError::usage("remove needs an app id or name"));
+    }
+    let mut conn = open_db()?;
+    let mut state = load_state(&conn)?;
+    let mut removed: Vec<AppEntry> = Vec::new();
+    let mut missing: Vec<&str> = Vec::new();
+    for target in &opts.values {
+        let Some(id) = find_app(&state.groups, target).map(|a| a.id.clone()) else {
+            missing.push(target);
+            continue;
+        };
+        for group in &mut state.groups {
+            if let Some(pos) = group.apps.iter().position(|a| a.id == id) {
+                removed.push(group.apps.remove(pos));
+            }
+        }
+    }
+    if !removed.is_empty() {
+        storage::write_launcher_state(&mut conn, &state)?;
+    }
+
+    if opts.json {
+        print_json(&removed)?;
+    } else {
+        for app in &removed {
+            println!("removed {}\t{}", app.id, app.name);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(CliError::not_found(format!(
+            "app not found: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(EXIT_OK)
+}
+
+/// Icons go into the export as data URLs, so the file restores them on another machine.
+fn export(opts: Options) -> CliResult {
+    opts.max_values(1)?;
+    let conn = open_db()?;
+    let mut state = load_state(&conn)?;
+    for app in state.groups.iter_mut().flat_map(|g| g.apps.iter_mut()) {
+        if let Some(blob) = storage::read_app_icon(&conn, &app.id)? {
+            use base64::Engine;
+            app.icon = Some(format!(
+                "data:{};base64,{}",
+                blob.mime,
+                base64::engine::general_purpose::STANDARD.encode(&blob.data)
+            ));
+        }
+        app.icon_hash = None;
+    }
+    let text = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
+    match opts.values.first() {
+        Some(file) => {
+            std::fs::write(file, text).map_err(|e| e.to_string())?;
+            if !opts.json {
+                println!("exported to {}", file);
+            }
+        }
+        None => println!("{}", text),
+    }
+    Ok(EXIT_OK)
+}
+
+fn import(opts: Options) -> CliResult {
+    opts.max_values(1)?;
+    let file = opts
+        .values
+        .first()
+        .ok_or_else(|| CliError::usage("import needs a file"))?;
+    let text = std::fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+    let mut state: LauncherState =
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", file, e))?;
+    let mut conn = open_db()?;
+    // An import replaces whatever is there, whichever revision it was exported at
+    state.revision = storage::read_revision(&conn);
+    storage::write_launcher_state(&mut conn, &state)?;
+    let apps = state.groups.iter().map(|g| g.apps.len()).sum::<usize>();
+    if opts.json {
+        print_json(&serde_json::json!({ "groups": state.groups.len(), "apps": apps }))?;
+    } else {
+        println!("imported {} groups, {} apps", state.groups.len(), apps);
+    }
+    Ok(EXIT_OK)
+}
+
+fn validate(opts: Options) -> CliResult {
+    opts.max_values(0)?;
+    let state = load_state(&open_db()?)?;
+    let invalid: Vec<AppRow> = state
+        .groups
+        .iter()
+        .flat_map(|g| {
+            g.apps.iter().map(|app| AppRow {
+                group: &g.name,
+                app,
+            })
+        })
+        .filter(|row| !paths::launch_path_exists(&row.app.path))
+        .collect();
+    if opts.json {
+        print_json(&invalid)?;
+    } else {
+        for row in &invalid {
+            println!(
+                "{}\t{}\t{}\t{}",
+                row.group, row.app.id, row.app.name, row.app.path
+            );
+        }
+    }
+    Ok(if invalid.is_empty() {
+        EXIT_OK
+    } else {
+        EXIT_INVALID_ENTRIES
+    })
+}
+
+fn backup(opts: Options) -> CliResult {
+    opts.max_values(1)?;
+    let db = storage::default_db_path()?;
+    let target = match opts.values.first() {
+        Some(file) => PathBuf::from(file),
+        None => default_backup_path(&db),
+    };
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()).into());
+    }
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    // Unlike copying the file, this includes whatever is still in the WAL.
+    open_db()?
+        .execute("VACUUM INTO ?1", params![target.to_string_lossy()])
+        .map_err(|e| e.to_string())?;
+    if opts.json {
+        print_json(&serde_json::json!({ "path": target }))?;
+    } else {
+        println!("{}", target.display());
+    }
+    Ok(EXIT_OK)
+}
+
+fn sync_library(opts: Options) -> CliResult {
+    opts.max_values(1)?;
+    let db = storage::default_db_path()?;
+    let mut conn = open_db()?;
+    let folder = match opts.values.first() {
+        Some(folder) => PathBuf::from(folder),
+        None => {
+            let folder = storage::read_ui_settings(&conn).sync_dir;
+            if folder.trim().is_empty() {
+                return Err(CliError::usage("no sync folder is set"));
+            }
+            PathBuf::from(folder.trim())
+        }
+    };
+    let outcome = sync::sync_folder(&mut conn, &db, &folder)?;
+    if opts.json {
+        print_json(&outcome)?;
+    } else {
+        println!(
+            "{}: {}, {}",
+            sync::library_file(&folder).display(),
+            if outcome.imported {
+                "imported changes"
+            } else {
+                "nothing to import"
+            },
+            if outcome.exported {
+                "written"
+            } else {
+                "up to date"
+            }
+        );
+    }
+    Ok(EXIT_OK)
+}
+
+fn default_backup_path(db: &Path) -> PathBuf {
+    let dir = db.parent().unwrap_or(Path::new(".")).join("backups");
+    dir.join(format!("launcher-{}.db", now_millis()))
+}
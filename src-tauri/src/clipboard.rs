@@ -0,0 +1,80 @@
+/// Reads a launchable path from the clipboard: the first file of a file copy made in
+/// Explorer, or otherwise the first line of the clipboard text with surrounding quotes removed.
+pub(crate) fn read_clipboard_path() -> Result<String, String> {
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("clipboard is only supported on Windows".to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let raw = read_clipboard_windows()?;
+        let line = raw
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .unwrap_or("");
+        let path = line.trim_matches(|c| c == '"' || c == '\'').trim();
+        if path.is_empty() {
+            return Err("clipboard has no path".to_string());
+        }
+        Ok(path.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+struct ClipboardGuard;
+
+#[cfg(target_os = "windows")]
+impl Drop for ClipboardGuard {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = windows::Win32::System::DataExchange::CloseClipboard();
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn read_clipboard_windows() -> Result<String, String> {
+    use windows::Win32::Foundation::HGLOBAL;
+    use windows::Win32::System::DataExchange::{
+        GetClipboardData, IsClipboardFormatAvailable, OpenClipboard,
+    };
+    use windows::Win32::System::Memory::{GlobalLock, GlobalUnlock};
+    use windows::Win32::UI::Shell::{DragQueryFileW, HDROP};
+
+    const CF_UNICODETEXT: u32 = 13;
+    const CF_HDROP: u32 = 15;
+
+    unsafe { OpenClipboard(None) }.map_err(|e| e.to_string())?;
+    let _guard = ClipboardGuard;
+
+    if unsafe { IsClipboardFormatAvailable(CF_HDROP) }.is_ok() {
+        let handle = unsafe { GetClipboardData(CF_HDROP) }.map_err(|e| e.to_string())?;
+        let hdrop = HDROP(handle.0);
+        let len = unsafe { DragQueryFileW(hdrop, 0, None) };
+        if len > 0 {
+            let mut buf = vec![0u16; len as usize + 1];
+            let copied = unsafe { DragQueryFileW(hdrop, 0, Some(&mut buf)) };
+            return Ok(String::from_utf16_lossy(&buf[..copied as usize]));
+        }
+    }
+
+    unsafe { IsClipboardFormatAvailable(CF_UNICODETEXT) }
+        .map_err(|_| "clipboard has no text".to_string())?;
+    let handle = unsafe { GetClipboardData(CF_UNICODETEXT) }.map_err(|e| e.to_string())?;
+    let hglobal = HGLOBAL(handle.0);
+    let ptr = unsafe { GlobalLock(hglobal) } as *const u16;
+    if ptr.is_null() {
+        return Err("clipboard is locked".to_string());
+    }
+    let mut len = 0usize;
+    while unsafe { *ptr.add(len) } != 0 {
+        len += 1;
+    }
+    let text = String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(ptr, len) });
+    unsafe {
+        let _ = GlobalUnlock(hglobal);
+    }
+    Ok(text)
+}
@@ -0,0 +1,158 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use quick_launcher_core::history::{self, Direction};
+use quick_launcher_core::model::ChangeLogEntry;
+use quick_launcher_core::storage as core;
+
+use crate::storage::LauncherState;
+
+/// How many edits can be undone.
+const MAX_HISTORY: usize = 50;
+
+/// One save of the library from this session, with everything it changed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Edit {
+    pub revision: u64,
+    pub changed_at: i64,
+    pub changes: Vec<ChangeLogEntry>,
+}
+
+/// Edits that can be undone and redone, newest last. Kept in memory for the session.
+#[derive(Default)]
+pub struct HistoryState {
+    stacks: Mutex<Stacks>,
+}
+
+#[derive(Default)]
+struct Stacks {
+    undo: VecDeque<Edit>,
+    redo: Vec<Edit>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct History {
+    pub undo: Vec<Edit>,
+    pub redo: Vec<Edit>,
+}
+
+/// Records the save that produced `revision` as a new edit, unless it changed nothing
+/// in the library. A new edit can't be followed by a redo.
+pub(crate) fn record(app: &AppHandle, conn: &rusqlite::Connection, revision: u64) {
+    let Ok(changes) = core::read_revision_changes(conn, revision) else {
+        return;
+    };
+    let Some(first) = changes.first() else {
+        return;
+    };
+    let edit = Edit {
+        revision,
+        changed_at: first.changed_at,
+        changes,
+    };
+    if let Some(state) = app.try_state::<HistoryState>() {
+        if let Ok(mut stacks) = state.stacks.lock() {
+            stacks.redo.clear();
+            stacks.undo.push_back(edit);
+            while stacks.undo.len() > MAX_HISTORY {
+                stacks.undo.pop_front();
+            }
+            let _ = core::write_undo_floor(conn, stacks.floor(revision));
+        }
+    }
+}
+
+impl Stacks {
+    /// The oldest revision these edits reach back to, or the one after `current` when
+    /// there are none.
+    fn floor(&self, current: u64) -> u64 {
+        self.undo
+            .iter()
+            .chain(&self.redo)
+            .map(|e| e.revision)
+            .min()
+            .unwrap_or(current + 1)
+    }
+}
+
+/// Forgets every edit, e.g. at startup or once another profile's database is in use, so
+/// the icons of apps deleted earlier can go.
+pub(crate) fn clear(app: &AppHandle) {
+    if let Some(state) = app.try_state::<HistoryState>() {
+        if let Ok(mut stacks) = state.stacks.lock() {
+            *stacks = Stacks::default();
+        }
+    }
+    if let Ok(conn) = core::default_db_path().and_then(|path| core::open(&path)) {
+        let _ = core::write_undo_floor(&conn, core::read_revision(&conn) + 1);
+    }
+}
+
+/// Applies `edit` backwards or forwards to the saved state and saves the result, which
+/// the caller hands to the UI.
+fn apply(app: &AppHandle, edit: &Edit, direction: Direction) -> Result<LauncherState, String> {
+    let mut conn = core::open(&core::default_db_path()?)?;
+    let current = core::read_launcher_state(&conn)?.ok_or("nothing to change")?;
+    let mut next = history::apply_changes(&current, &edit.changes, direction)?;
+    next.revision =
+        crate::live_reload::write_state(app, || core::write_launcher_state(&mut conn, &next))?;
+    crate::settings::update(app, &next.settings);
+    crate::tray::refresh_tray_menu(app);
+    crate::sync::request(app);
+    Ok(next)
+}
+
+/// Takes back the latest edit and returns the state after it.
+#[tauri::command]
+pub fn undo(
+    app: AppHandle,
+    state: tauri::State<'_, HistoryState>,
+) -> Result<LauncherState, String> {
+    let mut stacks = state.stacks.lock().map_err(|e| e.to_string())?;
+    let edit = stacks.undo.pop_back().ok_or("nothing to undo")?;
+    match apply(&app, &edit, Direction::Undo) {
+        Ok(next) => {
+            stacks.redo.push(edit);
+            Ok(next)
+        }
+        Err(e) => {
+            stacks.undo.push_back(edit);
+            Err(e)
+        }
+    }
+}
+
+/// Makes the latest undone edit again and returns the state after it.
+#[tauri::command]
+pub fn redo(
+    app: AppHandle,
+    state: tauri::State<'_, HistoryState>,
+) -> Result<LauncherState, String> {
+    let mut stacks = state.stacks.lock().map_err(|e| e.to_string())?;
+    let edit = stacks.redo.pop().ok_or("nothing to redo")?;
+    match apply(&app, &edit, Direction::Redo) {
+        Ok(next) => {
+            stacks.undo.push_back(edit);
+            Ok(next)
+        }
+        Err(e) => {
+            stacks.redo.push(edit);
+            Err(e)
+        }
+    }
+}
+
+/// The edits that can be undone and redone, oldest first.
+#[tauri::command]
+pub fn history(state: tauri::State<'_, HistoryState>) -> Result<History, String> {
+    let stacks = state.stacks.lock().map_err(|e| e.to_string())?;
+    Ok(History {
+        undo: stacks.undo.iter().cloned().collect(),
+        redo: stacks.redo.clone(),
+    })
+}
@@ -1,53 +1,597 @@
+use std::collections::HashMap;
 use std::sync::Mutex;
+use std::time::Instant;
 
-use tauri::AppHandle;
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
-use crate::window_utils::toggle_main_window;
+use crate::hotkey_gesture::{
+    DoubleTapDetector, GestureTiming, HoldDetector, HoldRelease, TapModifier,
+};
+use crate::storage::{HotkeyAction, HotkeyBinding, HotkeyMode, UiSettings};
+use crate::window_utils::{show_main_window, toggle_main_window};
 
-pub struct HotkeyState(pub Mutex<Option<String>>);
+#[derive(Default)]
+pub struct HotkeyState {
+    /// Registered shortcuts and the binding each one triggers.
+    pub registry: Mutex<HashMap<Shortcut, HotkeyBinding>>,
+    /// Double-tap bindings on a bare modifier, fed by the keyboard hook instead of the plugin.
+    pub modifier_taps: Mutex<HashMap<TapModifier, HotkeyBinding>>,
+    /// Per-shortcut tap/hold tracking for bindings that aren't plain presses.
+    pub gestures: Mutex<HashMap<Shortcut, ShortcutGesture>>,
+    pub timing: Mutex<GestureTiming>,
+    /// Saved bindings that could not be registered at startup, until the UI collects them.
+    pub startup_failures: Mutex<Vec<HotkeyFailure>>,
+    /// Held for a whole rebind so two never interleave. The plugin's register and
+    /// unregister wait on the main thread, where the shortcut handler locks `registry`,
+    /// so `registry` itself is never held across them.
+    rebinding: Mutex<()>,
+}
+
+#[derive(Default)]
+pub struct ShortcutGesture {
+    double_tap: DoubleTapDetector,
+    hold: HoldDetector,
+    /// Whether the window was already showing when the current hold began.
+    was_visible: bool,
+}
+
+/// What a binding listens for: a regular shortcut, or a bare modifier tapped twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trigger {
+    Shortcut(Shortcut),
+    Modifier(TapModifier),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HotkeyFailure {
+    pub accelerator: String,
+    pub action: HotkeyAction,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HotkeyValidation {
+    pub input: String,
+    /// Canonical form of the accelerator, e.g. `ctrl+alt+space`.
+    pub normalized: Option<String>,
+    pub valid: bool,
+    pub registrable: bool,
+    /// The action already bound to this accelerator in the launcher, if any.
+    #[serde(rename = "boundTo")]
+    pub bound_to: Option<HotkeyAction>,
+    pub error: Option<String>,
+}
+
+pub const HOTKEY_FAILED_EVENT: &str = "hotkey-registration-failed";
 
-pub fn handle_shortcut_event(app: &AppHandle, event_state: ShortcutState) {
-    if event_state != ShortcutState::Pressed {
+pub fn handle_shortcut_event(
+    app: &AppHandle,
+    hotkey_state: &HotkeyState,
+    shortcut: &Shortcut,
+    event_state: ShortcutState,
+) {
+    let binding = match hotkey_state.registry.lock() {
+        Ok(registry) => registry.get(shortcut).cloned(),
+        Err(_) => None,
+    };
+    let Some(binding) = binding else {
+        return;
+    };
+    if binding.mode == HotkeyMode::Press {
+        if event_state == ShortcutState::Pressed {
+            run_action(app, &binding.action);
+        }
         return;
     }
-    toggle_main_window(app);
+
+    let timing = current_timing(hotkey_state);
+    let now = Instant::now();
+    let Ok(mut gestures) = hotkey_state.gestures.lock() else {
+        return;
+    };
+    let gesture = gestures.entry(*shortcut).or_default();
+    match (binding.mode, event_state) {
+        (HotkeyMode::DoubleTap, ShortcutState::Pressed)
+            if gesture.double_tap.press(now, &timing) =>
+        {
+            drop(gestures);
+            run_action(app, &binding.action);
+        }
+        (HotkeyMode::Hold, ShortcutState::Pressed) if gesture.hold.press(now) => {
+            gesture.was_visible = main_window_visible(app);
+            drop(gestures);
+            peek_action(app, &binding.action);
+        }
+        (HotkeyMode::Hold, ShortcutState::Released) => {
+            let release = gesture.hold.release(now, &timing);
+            let was_visible = gesture.was_visible;
+            drop(gestures);
+            match release {
+                HoldRelease::Peek if !was_visible => hide_main_window(app),
+                // A quick tap on a toggle hotkey still toggles.
+                HoldRelease::Tap if was_visible && binding.action == HotkeyAction::ToggleWindow => {
+                    hide_main_window(app)
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn main_window_visible(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .map(|w| w.is_visible().unwrap_or(false) && !w.is_minimized().unwrap_or(false))
+        .unwrap_or(false)
+}
+
+fn hide_main_window(app: &AppHandle) {
+    if let Some(w) = app.get_webview_window("main") {
+        let _ = w.hide();
+    }
+}
+
+/// The "show" half of a hold-mode action; hiding happens on release.
+fn peek_action(app: &AppHandle, action: &HotkeyAction) {
+    match action {
+        HotkeyAction::ToggleWindow => show_main_window(app),
+        other => run_action(app, other),
+    }
+}
+
+fn current_timing(hotkey_state: &HotkeyState) -> GestureTiming {
+    hotkey_state.timing.lock().map(|t| *t).unwrap_or_default()
+}
+
+/// Picks up the double-tap and hold thresholds from freshly saved settings.
+pub fn apply_settings_timing(app: &AppHandle, settings: &UiSettings) {
+    let timing = GestureTiming::from_millis(settings.double_tap_ms, settings.hold_peek_ms);
+    if let Some(state) = app.try_state::<HotkeyState>() {
+        if let Ok(mut t) = state.timing.lock() {
+            *t = timing;
+        }
+    }
+    #[cfg(target_os = "windows")]
+    crate::hotkey_gesture::modifier_hook::set_timing(timing);
+}
+
+#[cfg(target_os = "windows")]
+fn handle_modifier_double_tap(app: &AppHandle, modifier: TapModifier) {
+    let Some(state) = app.try_state::<HotkeyState>() else {
+        return;
+    };
+    let action = match state.modifier_taps.lock() {
+        Ok(taps) => taps.get(&modifier).map(|b| b.action.clone()),
+        Err(_) => None,
+    };
+    if let Some(action) = action {
+        run_action(app, &action);
+    }
+}
+
+/// Starts the keyboard hook that double-tap modifier bindings rely on.
+fn ensure_modifier_hook(app: &AppHandle, hotkey_state: &HotkeyState) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let handle = app.clone();
+        crate::hotkey_gesture::modifier_hook::install(current_timing(hotkey_state), move |m| {
+            // Keep the hook callback short; Windows drops hooks that stall input.
+            let handle = handle.clone();
+            std::thread::spawn(move || handle_modifier_double_tap(&handle, m));
+        })
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, hotkey_state);
+        Err("double-tap modifier hotkeys are only supported on Windows".to_string())
+    }
+}
+
+fn run_action(app: &AppHandle, action: &HotkeyAction) {
+    let result = match action {
+        HotkeyAction::ToggleWindow => {
+            toggle_main_window(app);
+            Ok(())
+        }
+        HotkeyAction::ShowSearch => {
+            show_main_window(app);
+            app.emit("hotkey-focus-search", ())
+                .map_err(|e| e.to_string())
+        }
+        HotkeyAction::OpenGroup { group_id } => {
+            show_main_window(app);
+            app.emit("hotkey-open-group", group_id)
+                .map_err(|e| e.to_string())
+        }
+        HotkeyAction::LaunchApp { app_id } => crate::launch::launch_app_by_id(app, app_id),
+        HotkeyAction::LaunchClipboard => crate::clipboard::read_clipboard_path()
+            .and_then(|path| crate::launch::spawn_path(&path, Vec::new())),
+    };
+    if let Err(e) = result {
+        let _ = app.emit("hotkey-error", e);
+    }
+}
+
+/// Writes a parsed shortcut back as `ctrl+alt+shift+super+<key>`, so equivalent
+/// spellings such as `Alt+Control+KeyK` and `ctrl+alt+k` are stored the same way.
+fn canonical_accelerator(shortcut: &Shortcut) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for (modifier, name) in [
+        (Modifiers::CONTROL, "ctrl"),
+        (Modifiers::ALT, "alt"),
+        (Modifiers::SHIFT, "shift"),
+        (Modifiers::SUPER, "super"),
+    ] {
+        if shortcut.mods.contains(modifier) {
+            parts.push(name.to_string());
+        }
+    }
+    let code = shortcut.key.to_string();
+    let key = ["Key", "Digit"]
+        .iter()
+        .find_map(|prefix| code.strip_prefix(prefix).filter(|rest| rest.len() == 1))
+        .unwrap_or(&code);
+    parts.push(key.to_lowercase());
+    parts.join("+")
+}
+
+fn parse_accelerator(accelerator: &str) -> Result<Shortcut, String> {
+    let trimmed = accelerator.trim().to_lowercase();
+    if trimmed.is_empty() {
+        return Err("hotkey is empty".to_string());
+    }
+    trimmed
+        .parse::<Shortcut>()
+        .map_err(|e| format!("{}: {}", trimmed, e))
+}
+
+/// A bare `ctrl`, `alt` or `shift` is only a valid trigger in double-tap mode.
+fn parse_trigger(accelerator: &str, mode: HotkeyMode) -> Result<Trigger, String> {
+    if mode == HotkeyMode::DoubleTap {
+        if let Some(m) = TapModifier::parse(accelerator) {
+            return Ok(Trigger::Modifier(m));
+        }
+    }
+    parse_accelerator(accelerator).map(Trigger::Shortcut)
+}
+
+fn canonical_trigger(trigger: &Trigger) -> String {
+    match trigger {
+        Trigger::Shortcut(s) => canonical_accelerator(s),
+        Trigger::Modifier(m) => m.as_str().to_string(),
+    }
+}
+
+fn check_mode(binding: &HotkeyBinding) -> Result<(), String> {
+    let peekable = matches!(
+        binding.action,
+        HotkeyAction::ToggleWindow | HotkeyAction::ShowSearch | HotkeyAction::OpenGroup { .. }
+    );
+    if binding.mode == HotkeyMode::Hold && !peekable {
+        return Err(format!(
+            "{}: hold mode only works with actions that show the window",
+            binding.accelerator
+        ));
+    }
+    Ok(())
+}
+
+/// Parses and normalizes a binding list, dropping empty accelerators and rejecting
+/// malformed or duplicate ones.
+fn parse_bindings(bindings: Vec<HotkeyBinding>) -> Result<Vec<(Trigger, HotkeyBinding)>, String> {
+    let mut parsed: Vec<(Trigger, HotkeyBinding)> = Vec::new();
+    for mut binding in bindings {
+        if binding.accelerator.trim().is_empty() {
+            continue;
+        }
+        check_mode(&binding)?;
+        let trigger = parse_trigger(&binding.accelerator, binding.mode)?;
+        let accelerator = canonical_trigger(&trigger);
+        if parsed.iter().any(|(t, _)| *t == trigger) {
+            return Err(format!("duplicate hotkey: {}", accelerator));
+        }
+        binding.accelerator = accelerator;
+        parsed.push((trigger, binding));
+    }
+    Ok(parsed)
 }
 
-pub fn apply_hotkey(app: &AppHandle, hotkey_state: &HotkeyState, hotkey: String) -> Result<(), String> {
-    let normalized = hotkey.trim().to_lowercase();
-    let requested = if normalized.is_empty() {
-        None
-    } else {
-        Some(normalized)
+/// Swaps the registered shortcuts for `bindings` as one step: new shortcuts are registered
+/// first, and if any of them fails the ones already registered are rolled back and the
+/// previous set stays active.
+fn apply_registry(
+    app: &AppHandle,
+    hotkey_state: &HotkeyState,
+    bindings: Vec<HotkeyBinding>,
+) -> Result<Vec<HotkeyBinding>, String> {
+    let parsed = parse_bindings(bindings)?;
+    if parsed
+        .iter()
+        .any(|(t, _)| matches!(t, Trigger::Modifier(_)))
+    {
+        ensure_modifier_hook(app, hotkey_state)?;
+    }
+    let _rebinding = hotkey_state
+        .rebinding
+        .lock()
+        .map_err(|_| "lock failed".to_string())?;
+    let current: Vec<Shortcut> = hotkey_state
+        .registry
+        .lock()
+        .map_err(|_| "lock failed".to_string())?
+        .keys()
+        .copied()
+        .collect();
+    let gs = app.global_shortcut();
+
+    let shortcuts: Vec<(Shortcut, &HotkeyBinding)> = parsed
+        .iter()
+        .filter_map(|(t, b)| match t {
+            Trigger::Shortcut(s) => Some((*s, b)),
+            Trigger::Modifier(_) => None,
+        })
+        .collect();
+    let mut registered: Vec<Shortcut> = Vec::new();
+    for (shortcut, binding) in &shortcuts {
+        if current.contains(shortcut) {
+            continue;
+        }
+        if let Err(e) = gs.register(*shortcut) {
+            for s in &registered {
+                let _ = gs.unregister(*s);
+            }
+            return Err(format!("{}: {}", binding.accelerator, e));
+        }
+        registered.push(*shortcut);
+    }
+    for shortcut in &current {
+        if !shortcuts.iter().any(|(s, _)| s == shortcut) {
+            let _ = gs.unregister(*shortcut);
+        }
+    }
+
+    let next: HashMap<Shortcut, HotkeyBinding> =
+        shortcuts.iter().map(|(s, b)| (*s, (*b).clone())).collect();
+    let next_taps: HashMap<TapModifier, HotkeyBinding> = parsed
+        .iter()
+        .filter_map(|(t, b)| match t {
+            Trigger::Modifier(m) => Some((*m, b.clone())),
+            Trigger::Shortcut(_) => None,
+        })
+        .collect();
+    if let Ok(mut registry) = hotkey_state.registry.lock() {
+        *registry = next;
+    }
+    if let Ok(mut taps) = hotkey_state.modifier_taps.lock() {
+        *taps = next_taps;
+    }
+    if let Ok(mut gestures) = hotkey_state.gestures.lock() {
+        gestures.clear();
+    }
+    Ok(parsed.into_iter().map(|(_, b)| b).collect())
+}
+
+/// Replaces every binding, registering the new set and persisting it to the `hotkeys` table.
+pub fn set_bindings(
+    app: &AppHandle,
+    hotkey_state: &HotkeyState,
+    bindings: Vec<HotkeyBinding>,
+) -> Result<Vec<HotkeyBinding>, String> {
+    let previous = crate::storage::load_hotkey_bindings(app)?;
+    let applied = apply_registry(app, hotkey_state, bindings)?;
+    if let Err(e) = crate::storage::save_hotkey_bindings(app, &applied) {
+        let _ = apply_registry(app, hotkey_state, previous);
+        return Err(e);
+    }
+    Ok(applied)
+}
+
+/// Replaces the toggle-window binding, keeping every other binding as it is.
+pub fn apply_hotkey(
+    app: &AppHandle,
+    hotkey_state: &HotkeyState,
+    hotkey: String,
+) -> Result<(), String> {
+    let requested = hotkey.trim().to_string();
+    let mut bindings: Vec<HotkeyBinding> = crate::storage::load_hotkey_bindings(app)?
+        .into_iter()
+        .filter(|b| b.action != HotkeyAction::ToggleWindow)
+        .collect();
+    if !requested.is_empty() {
+        bindings.insert(
+            0,
+            HotkeyBinding {
+                accelerator: requested,
+                action: HotkeyAction::ToggleWindow,
+                mode: HotkeyMode::Press,
+            },
+        );
+    }
+    set_bindings(app, hotkey_state, bindings).map(|_| ())
+}
+
+/// Parses `hotkey` and checks whether it could be registered right now, without
+/// changing any binding. A shortcut the launcher already owns is reported through
+/// `bound_to`; one held by another program fails the trial registration.
+pub fn validate_hotkey(
+    app: &AppHandle,
+    hotkey_state: &HotkeyState,
+    hotkey: String,
+    mode: HotkeyMode,
+) -> HotkeyValidation {
+    let mut result = HotkeyValidation {
+        input: hotkey.clone(),
+        normalized: None,
+        valid: false,
+        registrable: false,
+        bound_to: None,
+        error: None,
+    };
+    let trigger = match parse_trigger(&hotkey, mode) {
+        Ok(t) => t,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
     };
+    result.valid = true;
+    result.normalized = Some(canonical_trigger(&trigger));
 
-    let mut current = hotkey_state.0.lock().map_err(|_| "lock failed".to_string())?;
-    if *current == requested {
-        return Ok(());
+    let shortcut = match trigger {
+        Trigger::Shortcut(s) => s,
+        Trigger::Modifier(m) => {
+            // The keyboard hook doesn't claim the key, so nothing can conflict with it.
+            result.bound_to = hotkey_state
+                .modifier_taps
+                .lock()
+                .ok()
+                .and_then(|t| t.get(&m).map(|b| b.action.clone()));
+            result.registrable = cfg!(target_os = "windows");
+            if !result.registrable {
+                result.error =
+                    Some("double-tap modifier hotkeys are only supported on Windows".to_string());
+            }
+            return result;
+        }
+    };
+
+    let bound = hotkey_state
+        .registry
+        .lock()
+        .ok()
+        .and_then(|r| r.get(&shortcut).map(|b| b.action.clone()));
+    if let Some(action) = bound {
+        result.registrable = true;
+        result.bound_to = Some(action);
+        return result;
+    }
+
+    let gs = app.global_shortcut();
+    match gs.register(shortcut) {
+        Ok(()) => {
+            let _ = gs.unregister(shortcut);
+            result.registrable = true;
+        }
+        Err(e) => {
+            result.error = Some(format!("already in use by another program: {}", e));
+        }
     }
+    result
+}
 
+/// Registers the saved bindings one by one at startup. Bindings that fail are kept in
+/// `startup_failures` and announced with [`HOTKEY_FAILED_EVENT`] instead of being dropped.
+pub fn init_from_saved_bindings(
+    app: &AppHandle,
+    hotkey_state: &HotkeyState,
+    saved: Vec<HotkeyBinding>,
+) {
+    let Ok(_rebinding) = hotkey_state.rebinding.lock() else {
+        return;
+    };
     let gs = app.global_shortcut();
-    match requested.as_ref() {
-        None => {
-            if let Some(existing) = current.as_ref() {
-                let _ = gs.unregister(existing.as_str());
+    let mut failures: Vec<HotkeyFailure> = Vec::new();
+    for binding in saved {
+        let trigger =
+            check_mode(&binding).and_then(|_| parse_trigger(&binding.accelerator, binding.mode));
+        let shortcut = match trigger {
+            Ok(Trigger::Shortcut(s)) => s,
+            Ok(Trigger::Modifier(m)) => {
+                match ensure_modifier_hook(app, hotkey_state) {
+                    Ok(()) => {
+                        if let Ok(mut taps) = hotkey_state.modifier_taps.lock() {
+                            taps.entry(m).or_insert(HotkeyBinding {
+                                accelerator: m.as_str().to_string(),
+                                ..binding
+                            });
+                        }
+                    }
+                    Err(e) => failures.push(HotkeyFailure {
+                        accelerator: binding.accelerator,
+                        action: binding.action,
+                        error: e,
+                    }),
+                }
+                continue;
             }
-            *current = None;
+            Err(e) => {
+                failures.push(HotkeyFailure {
+                    accelerator: binding.accelerator,
+                    action: binding.action,
+                    error: e,
+                });
+                continue;
+            }
+        };
+        let known = match hotkey_state.registry.lock() {
+            Ok(current) => current.contains_key(&shortcut),
+            Err(_) => return,
+        };
+        if known {
+            continue;
         }
-        Some(next) => {
-            gs.register(next.as_str()).map_err(|e| e.to_string())?;
-            if let Some(existing) = current.as_ref() {
-                let _ = gs.unregister(existing.as_str());
+        match gs.register(shortcut) {
+            Ok(()) => {
+                let Ok(mut current) = hotkey_state.registry.lock() else {
+                    return;
+                };
+                current.insert(
+                    shortcut,
+                    HotkeyBinding {
+                        accelerator: canonical_accelerator(&shortcut),
+                        ..binding
+                    },
+                );
             }
-            *current = Some(next.clone());
+            Err(e) => failures.push(HotkeyFailure {
+                accelerator: binding.accelerator,
+                action: binding.action,
+                error: e.to_string(),
+            }),
         }
     }
-    Ok(())
+
+    if failures.is_empty() {
+        return;
+    }
+    let _ = app.emit(HOTKEY_FAILED_EVENT, &failures);
+    if let Ok(mut pending) = hotkey_state.startup_failures.lock() {
+        pending.extend(failures);
+    }
 }
 
-pub fn init_from_saved_hotkey(app: &AppHandle, hotkey_state: &HotkeyState, saved: Option<String>) {
-    if let Some(hotkey) = saved {
-        let _ = apply_hotkey(app, hotkey_state, hotkey);
+/// Drops every registered binding and registers the saved ones again, after the
+/// active profile changed. Bindings that fail are reported as at startup.
+pub fn reload_saved_bindings(app: &AppHandle, hotkey_state: &HotkeyState) {
+    if let Ok(_rebinding) = hotkey_state.rebinding.lock() {
+        let previous = hotkey_state
+            .registry
+            .lock()
+            .map(|mut current| std::mem::take(&mut *current))
+            .unwrap_or_default();
+        let gs = app.global_shortcut();
+        for shortcut in previous.keys() {
+            let _ = gs.unregister(*shortcut);
+        }
+    }
+    if let Ok(mut taps) = hotkey_state.modifier_taps.lock() {
+        taps.clear();
     }
+    if let Ok(mut gestures) = hotkey_state.gestures.lock() {
+        gestures.clear();
+    }
+    let saved = crate::storage::load_hotkey_bindings(app).unwrap_or_default();
+    init_from_saved_bindings(app, hotkey_state, saved);
+}
+
+/// Hands the startup failures to the UI once; the event above may fire before the
+/// webview is listening.
+pub fn take_startup_failures(hotkey_state: &HotkeyState) -> Vec<HotkeyFailure> {
+    hotkey_state
+        .startup_failures
+        .lock()
+        .map(|mut f| std::mem::take(&mut *f))
+        .unwrap_or_default()
 }
@@ -0,0 +1,434 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Timing thresholds for the double-tap and hold hotkey modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GestureTiming {
+    /// Longest gap between two taps that still counts as a double tap; a single tap
+    /// held longer than this is not a tap at all.
+    pub double_tap: Duration,
+    /// How long a hold-mode hotkey must stay down before releasing it hides the window again.
+    pub hold_peek: Duration,
+}
+
+impl GestureTiming {
+    pub fn from_millis(double_tap_ms: u32, hold_peek_ms: u32) -> Self {
+        Self {
+            double_tap: Duration::from_millis(u64::from(double_tap_ms)),
+            hold_peek: Duration::from_millis(u64::from(hold_peek_ms)),
+        }
+    }
+}
+
+impl Default for GestureTiming {
+    fn default() -> Self {
+        Self::from_millis(300, 350)
+    }
+}
+
+/// A modifier that can be bound on its own as a double-tap hotkey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TapModifier {
+    Ctrl,
+    Alt,
+    Shift,
+}
+
+impl TapModifier {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "ctrl" | "control" => Some(TapModifier::Ctrl),
+            "alt" | "option" => Some(TapModifier::Alt),
+            "shift" => Some(TapModifier::Shift),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TapModifier::Ctrl => "ctrl",
+            TapModifier::Alt => "alt",
+            TapModifier::Shift => "shift",
+        }
+    }
+}
+
+/// Fires on the second press of a shortcut that follows the first within
+/// `GestureTiming::double_tap`.
+#[derive(Debug, Default)]
+pub struct DoubleTapDetector {
+    last_press: Option<Instant>,
+}
+
+impl DoubleTapDetector {
+    pub fn press(&mut self, now: Instant, timing: &GestureTiming) -> bool {
+        match self.last_press.take() {
+            Some(prev) if now.saturating_duration_since(prev) <= timing.double_tap => true,
+            _ => {
+                self.last_press = Some(now);
+                false
+            }
+        }
+    }
+}
+
+/// What releasing a hold-mode hotkey means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoldRelease {
+    /// Released quickly: behave like an ordinary press and leave the window alone.
+    Tap,
+    /// Held past the threshold: the window was only being peeked at and should hide.
+    Peek,
+    /// A release without a matching press (e.g. the binding changed mid-hold).
+    Stray,
+}
+
+#[derive(Debug, Default)]
+pub struct HoldDetector {
+    pressed_at: Option<Instant>,
+}
+
+impl HoldDetector {
+    /// Returns `false` for key repeats while the hotkey is already down.
+    pub fn press(&mut self, now: Instant) -> bool {
+        if self.pressed_at.is_some() {
+            return false;
+        }
+        self.pressed_at = Some(now);
+        true
+    }
+
+    pub fn release(&mut self, now: Instant, timing: &GestureTiming) -> HoldRelease {
+        match self.pressed_at.take() {
+            Some(at) if now.saturating_duration_since(at) >= timing.hold_peek => HoldRelease::Peek,
+            Some(_) => HoldRelease::Tap,
+            None => HoldRelease::Stray,
+        }
+    }
+}
+
+/// Raw keyboard input as seen by the low-level hook, reduced to what the
+/// modifier double-tap detector needs.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyInput {
+    ModifierDown(TapModifier),
+    ModifierUp(TapModifier),
+    /// Any non-modifier key going down; it turns a pending tap into a chord.
+    OtherDown,
+}
+
+/// Recognizes "tap Ctrl, tap Ctrl" style gestures. A tap is a press and release of a
+/// single modifier with no other key in between, released within the double-tap window;
+/// two taps of the same modifier inside that window fire.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+#[derive(Debug, Default)]
+pub struct ModifierTapDetector {
+    down: Option<(TapModifier, Instant, bool)>,
+    last_tap: Option<(TapModifier, Instant)>,
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+impl ModifierTapDetector {
+    pub fn feed(
+        &mut self,
+        input: KeyInput,
+        now: Instant,
+        timing: &GestureTiming,
+    ) -> Option<TapModifier> {
+        match input {
+            KeyInput::OtherDown => {
+                if let Some(down) = self.down.as_mut() {
+                    down.2 = false;
+                }
+                self.last_tap = None;
+                None
+            }
+            KeyInput::ModifierDown(m) => {
+                match self.down {
+                    // Auto-repeat of the modifier already held.
+                    Some((held, _, _)) if held == m => {}
+                    // A second modifier joins in: neither counts as a tap.
+                    Some(_) => {
+                        self.down = None;
+                        self.last_tap = None;
+                    }
+                    None => self.down = Some((m, now, true)),
+                }
+                None
+            }
+            KeyInput::ModifierUp(m) => {
+                let (held, pressed_at, clean) = self.down.take()?;
+                let is_tap = held == m
+                    && clean
+                    && now.saturating_duration_since(pressed_at) <= timing.double_tap;
+                if !is_tap {
+                    self.last_tap = None;
+                    return None;
+                }
+                match self.last_tap.take() {
+                    Some((prev, at))
+                        if prev == m && now.saturating_duration_since(at) <= timing.double_tap =>
+                    {
+                        Some(m)
+                    }
+                    _ => {
+                        self.last_tap = Some((m, now));
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Feeds a [`ModifierTapDetector`] from a low-level keyboard hook running on its own
+/// message-loop thread. Bare modifiers can't be registered with `RegisterHotKey`, so
+/// the global-shortcut plugin never reports them.
+#[cfg(target_os = "windows")]
+pub mod modifier_hook {
+    use std::sync::{Mutex, OnceLock};
+    use std::time::Instant;
+
+    use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetMessageW, SetWindowsHookExW, KBDLLHOOKSTRUCT, MSG, WH_KEYBOARD_LL,
+    };
+
+    use super::{GestureTiming, KeyInput, ModifierTapDetector, TapModifier};
+
+    const WM_KEYDOWN: usize = 0x0100;
+    const WM_KEYUP: usize = 0x0101;
+    const WM_SYSKEYDOWN: usize = 0x0104;
+    const WM_SYSKEYUP: usize = 0x0105;
+
+    type TapCallback = Box<dyn Fn(TapModifier) + Send + Sync>;
+
+    struct HookContext {
+        detector: Mutex<ModifierTapDetector>,
+        timing: Mutex<GestureTiming>,
+        on_double_tap: TapCallback,
+    }
+
+    static CONTEXT: OnceLock<HookContext> = OnceLock::new();
+    static INSTALLED: OnceLock<Result<(), String>> = OnceLock::new();
+
+    fn modifier_for_vk(vk: u32) -> Option<TapModifier> {
+        match vk {
+            0x10 | 0xA0 | 0xA1 => Some(TapModifier::Shift),
+            0x11 | 0xA2 | 0xA3 => Some(TapModifier::Ctrl),
+            0x12 | 0xA4 | 0xA5 => Some(TapModifier::Alt),
+            _ => None,
+        }
+    }
+
+    unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code >= 0 {
+            if let Some(ctx) = CONTEXT.get() {
+                let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+                let modifier = modifier_for_vk(info.vkCode);
+                let input = match (wparam.0, modifier) {
+                    (WM_KEYDOWN | WM_SYSKEYDOWN, Some(m)) => Some(KeyInput::ModifierDown(m)),
+                    (WM_KEYUP | WM_SYSKEYUP, Some(m)) => Some(KeyInput::ModifierUp(m)),
+                    (WM_KEYDOWN | WM_SYSKEYDOWN, None) => Some(KeyInput::OtherDown),
+                    _ => None,
+                };
+                if let Some(input) = input {
+                    let timing = ctx.timing.lock().map(|t| *t).unwrap_or_default();
+                    let fired = ctx
+                        .detector
+                        .lock()
+                        .ok()
+                        .and_then(|mut d| d.feed(input, Instant::now(), &timing));
+                    if let Some(m) = fired {
+                        (ctx.on_double_tap)(m);
+                    }
+                }
+            }
+        }
+        CallNextHookEx(None, code, wparam, lparam)
+    }
+
+    /// Installs the hook once; later calls only update the timing.
+    pub fn install(
+        timing: GestureTiming,
+        on_double_tap: impl Fn(TapModifier) + Send + Sync + 'static,
+    ) -> Result<(), String> {
+        if let Some(result) = INSTALLED.get() {
+            set_timing(timing);
+            return result.clone();
+        }
+        let _ = CONTEXT.set(HookContext {
+            detector: Mutex::new(ModifierTapDetector::default()),
+            timing: Mutex::new(timing),
+            on_double_tap: Box::new(on_double_tap),
+        });
+        let (tx, rx) = std::sync::mpsc::channel::<Result<(), String>>();
+        std::thread::spawn(move || unsafe {
+            match SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), None, 0) {
+                Ok(_) => {
+                    let _ = tx.send(Ok(()));
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(e.to_string()));
+                    return;
+                }
+            }
+            // Low-level hooks are called through this thread's message loop.
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {}
+        });
+        let result = rx.recv().map_err(|e| e.to_string()).and_then(|r| r);
+        INSTALLED.get_or_init(|| result).clone()
+    }
+
+    pub fn set_timing(timing: GestureTiming) {
+        if let Some(ctx) = CONTEXT.get() {
+            if let Ok(mut t) = ctx.timing.lock() {
+                *t = timing;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use KeyInput::{ModifierDown as Down, ModifierUp as Up, OtherDown};
+    use TapModifier::{Alt, Ctrl};
+
+    fn ms(start: Instant, offset: u64) -> Instant {
+        start + Duration::from_millis(offset)
+    }
+
+    #[test]
+    fn double_tap_fires_only_inside_the_window() {
+        let timing = GestureTiming::default();
+        let start = Instant::now();
+        let mut d = DoubleTapDetector::default();
+        assert!(!d.press(start, &timing));
+        assert!(d.press(ms(start, 250), &timing));
+
+        let mut d = DoubleTapDetector::default();
+        assert!(!d.press(start, &timing));
+        assert!(!d.press(ms(start, 400), &timing));
+        // The late press starts a new pair
+        assert!(d.press(ms(start, 500), &timing));
+    }
+
+    #[test]
+    fn hold_tells_tap_from_peek_and_stray() {
+        let timing = GestureTiming::default();
+        let start = Instant::now();
+        let mut h = HoldDetector::default();
+        assert!(h.press(start));
+        // Auto-repeat while held
+        assert!(!h.press(ms(start, 50)));
+        assert_eq!(h.release(ms(start, 100), &timing), HoldRelease::Tap);
+
+        assert!(h.press(ms(start, 1000)));
+        assert_eq!(h.release(ms(start, 1400), &timing), HoldRelease::Peek);
+
+        assert_eq!(h.release(ms(start, 1500), &timing), HoldRelease::Stray);
+    }
+
+    fn feed_all(
+        d: &mut ModifierTapDetector,
+        start: Instant,
+        inputs: &[(u64, KeyInput)],
+    ) -> Vec<Option<TapModifier>> {
+        let timing = GestureTiming::default();
+        inputs
+            .iter()
+            .map(|(offset, input)| d.feed(*input, ms(start, *offset), &timing))
+            .collect()
+    }
+
+    #[test]
+    fn modifier_double_tap_inside_and_outside_the_window() {
+        let start = Instant::now();
+        let mut d = ModifierTapDetector::default();
+        let out = feed_all(
+            &mut d,
+            start,
+            &[
+                (0, Down(Ctrl)),
+                (50, Up(Ctrl)),
+                (150, Down(Ctrl)),
+                (200, Up(Ctrl)),
+            ],
+        );
+        assert_eq!(out.last(), Some(&Some(Ctrl)));
+
+        let mut d = ModifierTapDetector::default();
+        let out = feed_all(
+            &mut d,
+            start,
+            &[
+                (0, Down(Ctrl)),
+                (50, Up(Ctrl)),
+                (500, Down(Ctrl)),
+                (550, Up(Ctrl)),
+            ],
+        );
+        assert!(out.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn a_chord_cancels_the_tap() {
+        let start = Instant::now();
+        let mut d = ModifierTapDetector::default();
+        let out = feed_all(
+            &mut d,
+            start,
+            &[
+                (0, Down(Ctrl)),
+                (20, OtherDown),
+                (50, Up(Ctrl)),
+                (100, Down(Ctrl)),
+                (150, Up(Ctrl)),
+            ],
+        );
+        assert!(out.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn a_second_modifier_cancels_the_tap() {
+        let start = Instant::now();
+        let mut d = ModifierTapDetector::default();
+        let out = feed_all(
+            &mut d,
+            start,
+            &[
+                (0, Down(Ctrl)),
+                (50, Up(Ctrl)),
+                (100, Down(Ctrl)),
+                (110, Down(Alt)),
+                (150, Up(Ctrl)),
+                (160, Up(Alt)),
+            ],
+        );
+        assert!(out.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn auto_repeat_keeps_the_tap() {
+        let start = Instant::now();
+        let mut d = ModifierTapDetector::default();
+        let out = feed_all(
+            &mut d,
+            start,
+            &[
+                (0, Down(Ctrl)),
+                (30, Down(Ctrl)),
+                (60, Down(Ctrl)),
+                (80, Up(Ctrl)),
+                (150, Down(Ctrl)),
+                (200, Up(Ctrl)),
+            ],
+        );
+        assert_eq!(out.last(), Some(&Some(Ctrl)));
+    }
+}
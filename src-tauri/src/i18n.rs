@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Strings shown by the native side, keyed the same way as the frontend's `i18n.ts`,
+/// which merges this file into its own dictionaries.
+const NATIVE_STRINGS: &str = include_str!("../../src/launcher/locales/native.json");
+
+type Dictionary = HashMap<String, String>;
+
+fn dictionaries() -> &'static HashMap<String, Dictionary> {
+    static TABLE: OnceLock<HashMap<String, Dictionary>> = OnceLock::new();
+    TABLE.get_or_init(|| serde_json::from_str(NATIVE_STRINGS).unwrap_or_default())
+}
+
+/// Maps a stored `UiSettings.language` to a dictionary name, like `normalizeUiLanguage`.
+pub fn normalize_language(raw: &str) -> &'static str {
+    let raw = raw.trim().to_lowercase();
+    if raw == "zh" || raw == "zh-cn" || raw.starts_with("zh-") {
+        "zh-CN"
+    } else {
+        "en"
+    }
+}
+
+/// Looks up `key` for `language`, falling back to English and then the key itself.
+pub fn tr(language: &str, key: &str) -> String {
+    let table = dictionaries();
+    table
+        .get(normalize_language(language))
+        .and_then(|d| d.get(key))
+        .or_else(|| table.get("en").and_then(|d| d.get(key)))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
@@ -9,7 +9,7 @@ struct IconKey {
 }
 
 struct CacheEntry {
-    data: Option<String>,
+    data: Option<Vec<u8>>,
     created_at: Instant,
 }
 
@@ -29,129 +29,464 @@ fn hash_key(key: &IconKey) -> String {
 }
 
 fn get_cache_dir(_app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    let base = crate::paths::app_base_dir().ok_or("Cannot determine exe directory")?;
-    let path = base.join("data").join("icon-cache");
+    let path = quick_launcher_core::data_dir::current().icon_cache_dir();
     if !path.exists() {
         std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
     }
     Ok(path)
 }
 
-fn disk_get(app: &tauri::AppHandle, key: &IconKey) -> Option<String> {
+fn disk_get(app: &tauri::AppHandle, key: &IconKey) -> Option<Vec<u8>> {
     let cache_dir = get_cache_dir(app).ok()?;
     let hash = hash_key(key);
     let file_path = cache_dir.join(format!("{}.png", hash));
 
     if file_path.exists() {
-        let bytes = std::fs::read(file_path).ok()?;
-        use base64::Engine;
-        return Some(format!(
-            "data:image/png;base64,{}",
-            base64::engine::general_purpose::STANDARD.encode(bytes)
-        ));
+        return std::fs::read(file_path).ok();
     }
     None
 }
 
-fn disk_put(app: &tauri::AppHandle, key: &IconKey, data: &str) {
+fn disk_put(app: &tauri::AppHandle, key: &IconKey, bytes: &[u8]) {
     if let Ok(cache_dir) = get_cache_dir(app) {
         let hash = hash_key(key);
         let file_path = cache_dir.join(format!("{}.png", hash));
         if file_path.exists() {
             return;
         }
+        let _ = std::fs::write(file_path, bytes);
+    }
+}
 
-        if let Some(base64_part) = data.strip_prefix("data:image/png;base64,") {
-            use base64::Engine;
-            if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(base64_part) {
-                let _ = std::fs::write(file_path, bytes);
-            }
-        }
+fn png_data_url(bytes: &[u8]) -> String {
+    use base64::Engine;
+    format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+/// Sizes the icon pipeline renders; requests are served from the closest one.
+const ICON_SIZES: [u32; 6] = [16, 32, 48, 64, 128, 256];
+
+/// Picks the smallest rendered size that covers `logical` px at the given scale factor.
+fn best_icon_size(logical: u32, scale_factor: f64) -> u32 {
+    let physical = (logical as f64 * scale_factor.max(1.0)).ceil() as u32;
+    ICON_SIZES
+        .iter()
+        .copied()
+        .find(|s| *s >= physical)
+        .unwrap_or(MAX_ICON_SIZE)
+}
+
+/// Downscales a PNG to `size` px. Sources already at or below that size are returned
+/// as they are, since upscaling only adds blur.
+fn resize_png(png: &[u8], size: u32) -> Result<Vec<u8>, String> {
+    use image::imageops::FilterType;
+    let img = image::load_from_memory_with_format(png, image::ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    if img.width() <= size && img.height() <= size {
+        return Ok(png.to_vec());
     }
+    let resized = img.resize(size, size, FilterType::Lanczos3);
+    let mut out = std::io::Cursor::new(Vec::new());
+    resized
+        .write_to(&mut out, image::ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(out.into_inner())
 }
 
-#[tauri::command]
-pub async fn get_file_icon(
-    app: tauri::AppHandle,
-    path: String,
-    size: Option<u32>,
-) -> Result<Option<String>, String> {
-    let icon_size = size.unwrap_or(32);
-    let key = IconKey {
-        path: path.clone(),
-        size: icon_size,
-    };
+fn cache_put(key: IconKey, data: Option<Vec<u8>>) {
+    if let Ok(mut cache) = get_icon_cache().lock() {
+        cache.insert(
+            key,
+            CacheEntry {
+                data,
+                created_at: Instant::now(),
+            },
+        );
+    }
+}
 
-    // 1. Check memory cache (fast)
-    {
-        let cache = get_icon_cache().lock().map_err(|e| e.to_string())?;
-        if let Some(entry) = cache.get(&key) {
+/// Looks one size up in the memory cache, then the disk cache. `Some(None)` is a
+/// remembered extraction failure.
+fn cached_icon(app: &tauri::AppHandle, key: &IconKey) -> Option<Option<Vec<u8>>> {
+    if let Ok(cache) = get_icon_cache().lock() {
+        if let Some(entry) = cache.get(key) {
             if entry.data.is_some() {
-                return Ok(entry.data.clone());
+                return Some(entry.data.clone());
             }
             if entry.created_at.elapsed().as_secs() < NEGATIVE_CACHE_TTL_SECS {
-                return Ok(None);
+                return Some(None);
             }
         }
     }
+    let data = disk_get(app, key)?;
+    cache_put(key.clone(), Some(data.clone()));
+    Some(Some(data))
+}
 
-    // 2. Check disk cache
-    let app_clone = app.clone();
-    let key_clone = key.clone();
-    if let Some(data) = tauri::async_runtime::spawn_blocking(move || disk_get(&app_clone, &key_clone))
-        .await
-        .map_err(|e| e.to_string())?
-    {
-        // Fill memory cache
-        if let Ok(mut cache) = get_icon_cache().lock() {
-            cache.insert(
-                key,
-                CacheEntry {
-                    data: Some(data.clone()),
-                    created_at: Instant::now(),
-                },
-            );
+#[cfg(target_os = "windows")]
+fn extract_icon_png(path: &str, size: u32) -> Option<Vec<u8>> {
+    match split_icon_index(path) {
+        Some((file, index)) => get_indexed_icon_windows(file, index, size).ok(),
+        None => get_file_icon_windows(path, size).ok(),
+    }
+}
+
+/// Splits an icon location written `file,index` into the file and the icon's index.
+#[cfg(target_os = "windows")]
+fn split_icon_index(location: &str) -> Option<(&str, i32)> {
+    let (file, index) = location.rsplit_once(',')?;
+    Some((file, index.trim().parse().ok()?))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn extract_icon_png(_path: &str, _size: u32) -> Option<Vec<u8>> {
+    None
+}
+
+/// Returns the icon for `path` at one of [`ICON_SIZES`], from the memory cache, the disk
+/// cache, or a fresh extraction. An extraction renders the largest size once and fills
+/// the caches with every smaller size. Blocking; call from a worker thread.
+fn load_icon_png(app: &tauri::AppHandle, path: &str, size: u32) -> Option<Vec<u8>> {
+    let key = |size: u32| IconKey {
+        path: path.to_string(),
+        size,
+    };
+    if let Some(data) = cached_icon(app, &key(size)) {
+        return data;
+    }
+
+    let master = match cached_icon(app, &key(MAX_ICON_SIZE)) {
+        Some(data) => data,
+        None => {
+            let data = extract_icon_png(path, MAX_ICON_SIZE);
+            if let Some(bytes) = &data {
+                disk_put(app, &key(MAX_ICON_SIZE), bytes);
+            }
+            cache_put(key(MAX_ICON_SIZE), data.clone());
+            data
+        }
+    };
+    let master = master?;
+
+    let mut result = None;
+    for s in ICON_SIZES.iter().copied().filter(|s| *s < MAX_ICON_SIZE) {
+        let Ok(bytes) = resize_png(&master, s) else {
+            continue;
+        };
+        disk_put(app, &key(s), &bytes);
+        cache_put(key(s), Some(bytes.clone()));
+        if s == size {
+            result = Some(bytes);
         }
-        return Ok(Some(data));
     }
+    if size == MAX_ICON_SIZE {
+        return Some(master);
+    }
+    result.or(Some(master))
+}
 
-    // 3. Extract icon (slow, must be in blocking thread)
-    #[cfg(target_os = "windows")]
-    {
-        let path_for_spawn = path.clone();
-        let result =
-            tauri::async_runtime::spawn_blocking(move || get_file_icon_windows(&path_for_spawn, icon_size))
-                .await
-                .map_err(|e| e.to_string())?;
-
-        // Update caches
-        if let Ok(data) = &result {
-            let app_for_put = app.clone();
-            let key_for_put = key.clone();
-            let data_for_put = data.clone();
-            tauri::async_runtime::spawn_blocking(move || {
-                disk_put(&app_for_put, &key_for_put, &data_for_put)
-            });
+/// How many scaled blobs [`blob_variant`] keeps; the least recently used go first.
+const VARIANT_CACHE_LIMIT: usize = 512;
+
+/// Scaled blobs by (hash, size), with the tick of their last use.
+#[derive(Default)]
+struct VariantCache {
+    entries: HashMap<(String, u32), (u64, Vec<u8>)>,
+    tick: u64,
+}
+
+impl VariantCache {
+    fn get(&mut self, key: &(String, u32)) -> Option<Vec<u8>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|(used, bytes)| {
+            *used = tick;
+            bytes.clone()
+        })
+    }
+
+    fn insert(&mut self, key: (String, u32), bytes: Vec<u8>) {
+        if self.entries.len() >= VARIANT_CACHE_LIMIT && !self.entries.contains_key(&key) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
         }
+        self.tick += 1;
+        self.entries.insert(key, (self.tick, bytes));
+    }
+}
 
-        let cache_entry = CacheEntry {
-            data: result.as_ref().ok().cloned(),
-            created_at: Instant::now(),
+static VARIANT_CACHE: OnceLock<Mutex<VariantCache>> = OnceLock::new();
+
+/// Scales a stored icon blob to `size`, remembering the result per blob hash.
+fn blob_variant(hash: &str, data: &[u8], size: u32) -> Vec<u8> {
+    let cache = VARIANT_CACHE.get_or_init(|| Mutex::new(VariantCache::default()));
+    let key = (hash.to_string(), size);
+    if let Some(hit) = cache.lock().ok().and_then(|mut c| c.get(&key)) {
+        return hit;
+    }
+    let bytes = resize_png(data, size).unwrap_or_else(|_| data.to_vec());
+    if let Ok(mut c) = cache.lock() {
+        c.insert(key, bytes.clone());
+    }
+    bytes
+}
+
+type MenuImage = tauri::image::Image<'static>;
+
+/// 16px tray images by blob hash, as of the last menu build.
+static MENU_IMAGES: OnceLock<Mutex<HashMap<String, MenuImage>>> = OnceLock::new();
+
+fn menu_image(blob: &crate::storage::IconBlob) -> Option<MenuImage> {
+    let png = blob_variant(&blob.hash, &blob.data, 16);
+    let rgba = image::load_from_memory(&png).ok()?.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    Some(tauri::image::Image::new_owned(rgba.into_raw(), width, height))
+}
+
+/// The stored icons of `app_ids` as 16px images for the tray menu, by app id. Only icons
+/// already in the database are used, so rebuilding the menu never waits on extraction,
+/// and icons scaled for an earlier build are reused.
+pub(crate) fn menu_icons(app: &tauri::AppHandle, app_ids: &[&str]) -> HashMap<String, MenuImage> {
+    let blobs = crate::storage::load_app_icons(app, app_ids).unwrap_or_default();
+    let cache = MENU_IMAGES.get_or_init(|| Mutex::new(HashMap::new()));
+    let Ok(mut cache) = cache.lock() else {
+        return HashMap::new();
+    };
+    let mut images = HashMap::new();
+    let mut kept = HashMap::new();
+    for (app_id, blob) in blobs {
+        let image = match kept.get(&blob.hash).or_else(|| cache.get(&blob.hash)) {
+            Some(image) => image.clone(),
+            None => match menu_image(&blob) {
+                Some(image) => image,
+                None => continue,
+            },
         };
-        if let Ok(mut cache) = get_icon_cache().lock() {
-            cache.insert(key, cache_entry);
+        kept.insert(blob.hash, image.clone());
+        images.insert(app_id, image);
+    }
+    // Only what this menu shows is kept, so removed icons don't pile up
+    *cache = kept;
+    images
+}
+
+/// What to extract an app's icon from: its icon location when it has one, else its path.
+fn icon_source(path: &str, icon_location: Option<&str>) -> String {
+    match icon_location {
+        Some(location) => crate::paths::resolve_launch_path(location.trim()),
+        None => icon_lookup_path(path),
+    }
+}
+
+/// Maps a stored app path to something the shell can extract an icon from.
+fn icon_lookup_path(path: &str) -> String {
+    let trimmed = path.trim();
+    match trimmed.get(..4) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("uwp:") => {
+            format!("shell:AppsFolder\\{}", &trimmed[4..])
         }
-        return result.map(Some).or(Ok(None));
+        _ => crate::paths::resolve_launch_path(trimmed),
     }
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = app;
-        let _ = path;
-        let _ = icon_size;
-        Ok(None)
+}
+
+pub const ICON_PROTOCOL: &str = "qlicon";
+
+const DEFAULT_ICON_SIZE: u32 = 32;
+const MAX_ICON_SIZE: u32 = 256;
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(b) = raw
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+fn query_param(request: &tauri::http::Request<Vec<u8>>, name: &str) -> Option<String> {
+    request.uri().query()?.split('&').find_map(|pair| {
+        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+        if k == name {
+            Some(percent_decode(v))
+        } else {
+            None
+        }
+    })
+}
+
+fn protocol_status(status: u16) -> tauri::http::Response<Vec<u8>> {
+    tauri::http::Response::builder()
+        .status(status)
+        .header("Cache-Control", "no-store")
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+fn protocol_image(
+    request: &tauri::http::Request<Vec<u8>>,
+    mime: &str,
+    hash: &str,
+    data: Vec<u8>,
+) -> tauri::http::Response<Vec<u8>> {
+    let etag = format!("\"{}\"", hash);
+    // A `v` parameter pins the URL to one stored icon, so the webview may keep it;
+    // unversioned URLs are revalidated against the ETag.
+    let cache_control = if query_param(request, "v").is_some() {
+        "public, max-age=31536000, immutable"
+    } else {
+        "no-cache"
+    };
+    let not_modified = request
+        .headers()
+        .get("If-None-Match")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v == etag);
+    let builder = tauri::http::Response::builder()
+        .header("ETag", etag)
+        .header("Cache-Control", cache_control);
+    if not_modified {
+        return builder.status(304).body(Vec::new()).unwrap_or_default();
+    }
+    builder
+        .status(200)
+        .header("Content-Type", mime)
+        .body(data)
+        .unwrap_or_default()
+}
+
+/// Serves `qlicon://localhost/<appId>?size=48` to the webview.
+///
+/// `size` is in CSS pixels and is mapped to a rendered size through the window's
+/// `scale_factor`. The stored icon blob is returned when there is one. Otherwise the icon
+/// is extracted from the app's icon location or path (or the `icon` and `path` parameters,
+/// for entries not saved yet) through the icon cache. Serving never writes the database;
+/// extracted icons are stored after the next save, by [`request_icon_persist`].
+pub fn icon_protocol_response(
+    app: &tauri::AppHandle,
+    request: &tauri::http::Request<Vec<u8>>,
+    scale_factor: f64,
+) -> tauri::http::Response<Vec<u8>> {
+    let app_id = percent_decode(request.uri().path().trim_start_matches('/'));
+    if app_id.trim().is_empty() {
+        return protocol_status(400);
+    }
+    let size = query_param(request, "size")
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_ICON_SIZE)
+        .clamp(16, MAX_ICON_SIZE);
+    let size = best_icon_size(size, scale_factor);
+    let requested_path = query_param(request, "path").filter(|p| !p.trim().is_empty());
+
+    if requested_path.is_none() {
+        match crate::storage::load_app_icon(app, &app_id) {
+            Ok(Some(blob)) => {
+                let data = blob_variant(&blob.hash, &blob.data, size);
+                let etag = format!("{}-{}", blob.hash, size);
+                return protocol_image(request, &blob.mime, &etag, data);
+            }
+            Ok(None) => {}
+            Err(_) => return protocol_status(500),
+        }
+    }
+
+    let (app_path, icon_location) = match requested_path {
+        Some(p) => (
+            p,
+            query_param(request, "icon").filter(|i| !i.trim().is_empty()),
+        ),
+        None => match crate::storage::load_app_icon_source(app, &app_id) {
+            Ok(Some(source)) => source,
+            Ok(None) => return protocol_status(404),
+            Err(_) => return protocol_status(500),
+        },
+    };
+    let lookup = icon_source(&app_path, icon_location.as_deref());
+    let Some(data) = load_icon_png(app, &lookup, size) else {
+        return protocol_status(404);
+    };
+    let hash = hash_key(&IconKey {
+        path: lookup,
+        size: MAX_ICON_SIZE,
+    });
+    protocol_image(request, "image/png", &format!("{}-{}", hash, size), data)
+}
+
+/// Extracts and stores the icons of saved apps that have none in the database yet.
+fn persist_missing_icons(app: &tauri::AppHandle) {
+    let Ok(missing) = crate::storage::load_apps_without_icon(app) else {
+        return;
+    };
+    let icons: Vec<(String, String, Vec<u8>)> = missing
+        .into_iter()
+        .filter_map(|(app_id, path, icon_location)| {
+            let lookup = icon_source(&path, icon_location.as_deref());
+            let master = load_icon_png(app, &lookup, MAX_ICON_SIZE)?;
+            Some((app_id, path, master))
+        })
+        .collect();
+    if !icons.is_empty() {
+        let _ = crate::storage::store_app_icons(app, &icons);
     }
 }
 
+/// Queues storing the icons of newly saved apps on a worker thread. Called after every
+/// save; the extraction mostly hits the cache the icon protocol already filled.
+pub(crate) fn request_icon_persist(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || persist_missing_icons(&app));
+}
+
+/// Answers icon protocol requests off the webview thread, since extraction can be slow.
+pub fn handle_icon_protocol(
+    ctx: tauri::UriSchemeContext<'_, tauri::Wry>,
+    request: tauri::http::Request<Vec<u8>>,
+    responder: tauri::UriSchemeResponder,
+) {
+    use tauri::Manager;
+    let app = ctx.app_handle().clone();
+    let scale_factor = app
+        .get_webview_window(ctx.webview_label())
+        .and_then(|w| w.scale_factor().ok())
+        .unwrap_or(1.0);
+    tauri::async_runtime::spawn_blocking(move || {
+        responder.respond(icon_protocol_response(&app, &request, scale_factor));
+    });
+}
+
+#[tauri::command]
+pub async fn get_file_icon(
+    app: tauri::AppHandle,
+    path: String,
+    size: Option<u32>,
+) -> Result<Option<String>, String> {
+    let icon_size = best_icon_size(size.unwrap_or(DEFAULT_ICON_SIZE), 1.0);
+    let data = tauri::async_runtime::spawn_blocking(move || load_icon_png(&app, &path, icon_size))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(data.as_deref().map(png_data_url))
+}
+
 #[cfg(target_os = "windows")]
 mod gdi_guards {
     use windows::Win32::Foundation::HWND;
@@ -208,10 +543,9 @@ mod gdi_guards {
 }
 
 #[cfg(target_os = "windows")]
-fn hbitmap_to_png_data_url(
+fn hbitmap_to_png(
     color: windows::Win32::Graphics::Gdi::HBITMAP,
-) -> Result<String, String> {
-    use base64::Engine;
+) -> Result<Vec<u8>, String> {
     use gdi_guards::HdcGuard;
     use image::codecs::png::PngEncoder;
     use image::ImageEncoder;
@@ -298,50 +632,60 @@ fn hbitmap_to_png_data_url(
         )
         .map_err(|e| e.to_string())?;
 
-    Ok(format!(
-        "data:image/png;base64,{}",
-        base64::engine::general_purpose::STANDARD.encode(png)
-    ))
+    Ok(png)
 }
 
 #[cfg(target_os = "windows")]
-fn get_file_icon_windows(path: &str, size: u32) -> Result<String, String> {
-    use gdi_guards::{CoGuard, HbitmapGuard, HiconGuard};
+fn shell_item_image_png(wide: &[u16], size: u32) -> Result<Vec<u8>, String> {
+    use gdi_guards::HbitmapGuard;
     use windows::core::PCWSTR;
     use windows::Win32::Foundation::SIZE;
-    use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
     use windows::Win32::System::Com::IBindCtx;
+    use windows::Win32::UI::Shell::{
+        IShellItemImageFactory, SHCreateItemFromParsingName, SIIGBF_BIGGERSIZEOK, SIIGBF_ICONONLY,
+    };
+
+    let factory: IShellItemImageFactory = unsafe {
+        SHCreateItemFromParsingName(PCWSTR(wide.as_ptr()), None::<&IBindCtx>)
+            .map_err(|e| e.to_string())?
+    };
+    let hbmp = unsafe {
+        factory
+            .GetImage(
+                SIZE {
+                    cx: size as i32,
+                    cy: size as i32,
+                },
+                SIIGBF_ICONONLY | SIIGBF_BIGGERSIZEOK,
+            )
+            .map_err(|e| e.to_string())?
+    };
+    let _hbmp_guard = HbitmapGuard(hbmp);
+    hbitmap_to_png(hbmp)
+}
+
+#[cfg(target_os = "windows")]
+fn get_file_icon_windows(path: &str, size: u32) -> Result<Vec<u8>, String> {
+    use gdi_guards::CoGuard;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
     use windows::Win32::System::Com::{CoInitializeEx, COINIT_APARTMENTTHREADED};
     use windows::Win32::UI::Shell::{
-        IShellItemImageFactory, SHCreateItemFromParsingName, SHGetFileInfoW, SHFILEINFOW,
-        SHGFI_ICON, SHGFI_LARGEICON, SHGFI_SMALLICON, SIIGBF_BIGGERSIZEOK, SIIGBF_ICONONLY,
+        SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON, SHGFI_SMALLICON,
     };
-    use windows::Win32::UI::WindowsAndMessaging::{GetIconInfo, ICONINFO};
 
     let mut wide: Vec<u16> = path.encode_utf16().collect();
     wide.push(0);
 
-    if path.to_ascii_lowercase().starts_with("shell:appsfolder\\") {
+    // IShellItemImageFactory renders at the requested size for files, folders and
+    // shell items alike; SHGetFileInfoW tops out at 32px and is only the fallback.
+    {
         let co_init_result = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) };
         let _co_guard = CoGuard(co_init_result.is_ok());
-
-        let factory: IShellItemImageFactory = unsafe {
-            SHCreateItemFromParsingName(PCWSTR(wide.as_ptr()), None::<&IBindCtx>)
-                .map_err(|e| e.to_string())?
-        };
-        let hbmp = unsafe {
-            factory
-                .GetImage(
-                    SIZE {
-                        cx: size as i32,
-                        cy: size as i32,
-                    },
-                    SIIGBF_ICONONLY | SIIGBF_BIGGERSIZEOK,
-                )
-                .map_err(|e| e.to_string())?
-        };
-        let _hbmp_guard = HbitmapGuard(hbmp);
-        return hbitmap_to_png_data_url(hbmp);
+        let rendered = shell_item_image_png(&wide, size);
+        if rendered.is_ok() || path.to_ascii_lowercase().starts_with("shell:appsfolder\\") {
+            return rendered;
+        }
     }
 
     let mut info = SHFILEINFOW::default();
@@ -362,10 +706,45 @@ fn get_file_icon_windows(path: &str, size: u32) -> Result<String, String> {
     if res == 0 || info.hIcon.0.is_null() {
         return Err("icon not found".to_string());
     }
+    hicon_to_png(info.hIcon)
+}
+
+/// The icon at `index` in an `.ico`, `.exe` or `.dll`; a negative index is a resource id.
+#[cfg(target_os = "windows")]
+fn get_indexed_icon_windows(file: &str, index: i32, size: u32) -> Result<Vec<u8>, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::SHDefExtractIconW;
+    use windows::Win32::UI::WindowsAndMessaging::HICON;
+
+    let mut wide: Vec<u16> = file.encode_utf16().collect();
+    wide.push(0);
+    let mut hicon = HICON::default();
+    unsafe {
+        SHDefExtractIconW(
+            PCWSTR(wide.as_ptr()),
+            index,
+            0,
+            Some(&mut hicon),
+            None,
+            size,
+        )
+    }
+    .map_err(|e| e.to_string())?;
+    if hicon.0.is_null() {
+        return Err("icon not found".to_string());
+    }
+    hicon_to_png(hicon)
+}
+
+/// Renders `hicon` to PNG and destroys it.
+#[cfg(target_os = "windows")]
+fn hicon_to_png(hicon: windows::Win32::UI::WindowsAndMessaging::HICON) -> Result<Vec<u8>, String> {
+    use gdi_guards::{HbitmapGuard, HiconGuard};
+    use windows::Win32::UI::WindowsAndMessaging::{GetIconInfo, ICONINFO};
 
-    let _hicon_guard = HiconGuard(info.hIcon);
+    let _hicon_guard = HiconGuard(hicon);
     let mut icon_info = ICONINFO::default();
-    let got_icon_info = unsafe { GetIconInfo(info.hIcon, &mut icon_info) };
+    let got_icon_info = unsafe { GetIconInfo(hicon, &mut icon_info) };
 
     // Wrap bitmaps as soon as possible, even if GetIconInfo failed
     let _mask_guard = HbitmapGuard(icon_info.hbmMask);
@@ -377,5 +756,5 @@ fn get_file_icon_windows(path: &str, size: u32) -> Result<String, String> {
         return Err("no color bitmap".to_string());
     }
 
-    hbitmap_to_png_data_url(icon_info.hbmColor)
+    hbitmap_to_png(icon_info.hbmColor)
 }
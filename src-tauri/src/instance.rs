@@ -0,0 +1,279 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::window_utils::show_main_window;
+
+pub const INSTANCE_COMMAND_EVENT: &str = "instance-command";
+pub const INSTANCE_COMMAND_FAILED_EVENT: &str = "instance-command-failed";
+
+/// A command passed on the command line, either to this process or forwarded from a
+/// second instance:
+///
+/// - `--launch <name or id>`
+/// - `--add <path>... [--group <name>]`
+/// - `--search <query>`
+/// - `--show-group <name>`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InstanceCommand {
+    Launch {
+        target: String,
+    },
+    Add {
+        paths: Vec<String>,
+        group: Option<String>,
+    },
+    Search {
+        query: String,
+    },
+    ShowGroup {
+        name: String,
+    },
+}
+
+/// Commands from this process's own arguments, and the errors they ran into, held
+/// until the UI has loaded and asks for them with `take_instance_commands`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingCommands {
+    pub commands: Vec<InstanceCommand>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct PendingInstanceCommands(pub Mutex<PendingCommands>);
+
+/// Reports a failed command to the UI, or queues it with `defer_ui` while the UI is
+/// still loading.
+fn report_failure(app: &AppHandle, error: String, defer_ui: bool) {
+    if !defer_ui {
+        let _ = app.emit(INSTANCE_COMMAND_FAILED_EVENT, error);
+        return;
+    }
+    eprintln!("error: {}", error);
+    if let Some(pending) = app.try_state::<PendingInstanceCommands>() {
+        if let Ok(mut queue) = pending.0.lock() {
+            queue.errors.push(error);
+        }
+    }
+}
+
+fn take_value(args: &mut std::slice::Iter<'_, String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .filter(|v| !v.starts_with("--"))
+        .cloned()
+        .ok_or_else(|| format!("{} needs a value", flag))
+}
+
+/// Parses `args` (without the program name). Relative `--add` paths are resolved
+/// against `cwd`, the directory the invoking process was started in.
+pub fn parse_args(args: &[String], cwd: &str) -> Result<Vec<InstanceCommand>, String> {
+    let mut commands: Vec<InstanceCommand> = Vec::new();
+    let mut add_paths: Vec<String> = Vec::new();
+    let mut add_group: Option<String> = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--launch" => commands.push(InstanceCommand::Launch {
+                target: take_value(&mut iter, arg)?,
+            }),
+            "--add" => {
+                let first = take_value(&mut iter, arg)?;
+                add_paths.push(crate::paths::resolve_against(&first, cwd));
+                // "Send To" appends every selected file after the flag.
+                let rest = iter.clone().take_while(|a| !a.starts_with("--")).count();
+                for path in iter.by_ref().take(rest) {
+                    add_paths.push(crate::paths::resolve_against(path, cwd));
+                }
+            }
+            "--group" => add_group = Some(take_value(&mut iter, arg)?),
+            "--search" => commands.push(InstanceCommand::Search {
+                query: take_value(&mut iter, arg)?,
+            }),
+            "--show-group" => commands.push(InstanceCommand::ShowGroup {
+                name: take_value(&mut iter, arg)?,
+            }),
+            // Only read at startup; a running instance keeps its data directory.
+            quick_launcher_core::data_dir::DATA_DIR_FLAG => {
+                iter.next();
+            }
+            other if other.starts_with("--data-dir=") => {}
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+    if !add_paths.is_empty() {
+        commands.push(InstanceCommand::Add {
+            paths: add_paths,
+            group: add_group,
+        });
+    } else if add_group.is_some() {
+        return Err("--group only applies to --add".to_string());
+    }
+    Ok(commands)
+}
+
+/// Finds a saved app by id, or else by its name ignoring case.
+fn find_app_id(app: &AppHandle, target: &str) -> Result<String, String> {
+    let state = crate::storage::read_state(app)?.ok_or_else(|| "no apps saved".to_string())?;
+    let apps: Vec<_> = state.groups.iter().flat_map(|g| g.apps.iter()).collect();
+    if let Some(entry) = apps.iter().find(|a| a.id == target) {
+        return Ok(entry.id.clone());
+    }
+    let wanted = target.trim().to_lowercase();
+    apps.iter()
+        .find(|a| a.name.trim().to_lowercase() == wanted)
+        .map(|a| a.id.clone())
+        .ok_or_else(|| format!("app not found: {}", target))
+}
+
+/// Runs the commands. Launches happen here; the others change what the UI shows, so
+/// they go to the webview, or wait for it with `defer_ui` while it is still loading.
+fn run_commands(app: &AppHandle, commands: Vec<InstanceCommand>, defer_ui: bool) {
+    for command in commands {
+        match command {
+            InstanceCommand::Launch { target } => {
+                let result = find_app_id(app, &target)
+                    .and_then(|id| crate::launch::launch_app_by_id(app, &id));
+                if let Err(e) = result {
+                    report_failure(app, e, defer_ui);
+                }
+            }
+            ui_command => {
+                show_main_window(app);
+                if defer_ui {
+                    if let Some(pending) = app.try_state::<PendingInstanceCommands>() {
+                        if let Ok(mut queue) = pending.0.lock() {
+                            queue.commands.push(ui_command);
+                        }
+                    }
+                } else {
+                    let _ = app.emit(INSTANCE_COMMAND_EVENT, ui_command);
+                }
+            }
+        }
+    }
+}
+
+/// Handles arguments forwarded by `tauri_plugin_single_instance`. Without any
+/// command the window is just brought up, as before.
+pub fn handle_forwarded_args(app: &AppHandle, args: Vec<String>, cwd: String) {
+    let rest = args.get(1..).unwrap_or_default();
+    if rest.is_empty() {
+        show_main_window(app);
+        return;
+    }
+    match parse_args(rest, &cwd) {
+        Ok(commands) => run_commands(app, commands, false),
+        Err(e) => {
+            show_main_window(app);
+            report_failure(app, e, false);
+        }
+    }
+}
+
+/// Handles the commands this process was started with, once setup is done.
+pub fn handle_startup_args(app: &AppHandle) {
+    app.manage(PendingInstanceCommands::default());
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        return;
+    }
+    let cwd = std::env::current_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match parse_args(&args, &cwd) {
+        Ok(commands) => run_commands(app, commands, true),
+        Err(e) => report_failure(app, e, true),
+    }
+}
+
+pub fn take_pending(app: &AppHandle) -> PendingCommands {
+    app.try_state::<PendingInstanceCommands>()
+        .and_then(|p| p.0.lock().ok().map(|mut q| std::mem::take(&mut *q)))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Vec<InstanceCommand>, String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        parse_args(&args, "/work")
+    }
+
+    #[test]
+    fn each_flag_becomes_a_command() {
+        assert_eq!(
+            parse(&[
+                "--launch",
+                "Editor",
+                "--search",
+                "fire fox",
+                "--show-group",
+                "Games"
+            ]),
+            Ok(vec![
+                InstanceCommand::Launch {
+                    target: "Editor".to_string()
+                },
+                InstanceCommand::Search {
+                    query: "fire fox".to_string()
+                },
+                InstanceCommand::ShowGroup {
+                    name: "Games".to_string()
+                },
+            ])
+        );
+        assert_eq!(parse(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn add_takes_every_path_up_to_the_next_flag() {
+        let absolute = if cfg!(windows) {
+            "C:\\tools\\b.exe"
+        } else {
+            "/tools/b.exe"
+        };
+        let relative = std::path::Path::new("/work").join("a.exe");
+        assert_eq!(
+            parse(&["--add", "a.exe", absolute, "--group", "Tools"]),
+            Ok(vec![InstanceCommand::Add {
+                paths: vec![relative.to_string_lossy().to_string(), absolute.to_string()],
+                group: Some("Tools".to_string()),
+            }])
+        );
+    }
+
+    #[test]
+    fn the_data_dir_flag_is_skipped() {
+        assert_eq!(
+            parse(&["--data-dir", "/d", "--data-dir=/e", "--search", "x"]),
+            Ok(vec![InstanceCommand::Search {
+                query: "x".to_string()
+            }])
+        );
+    }
+
+    #[test]
+    fn bad_arguments_are_errors() {
+        assert_eq!(
+            parse(&["--launch"]),
+            Err("--launch needs a value".to_string())
+        );
+        assert_eq!(
+            parse(&["--search", "--launch", "x"]),
+            Err("--search needs a value".to_string())
+        );
+        assert_eq!(
+            parse(&["--group", "Tools"]),
+            Err("--group only applies to --add".to_string())
+        );
+        assert_eq!(
+            parse(&["--bogus"]),
+            Err("unexpected argument: --bogus".to_string())
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+use quick_launcher_core::model::{AppEntry, EntryKind};
+
+pub(crate) use quick_launcher_core::launch::spawn_path;
+
+/// Launches a saved entry; URLs go through the opener plugin, everything else through
+/// the core launcher.
+pub(crate) fn launch_entry(app: &AppHandle, entry: &AppEntry) -> Result<(), String> {
+    if entry.resolved_kind() != EntryKind::Url {
+        return quick_launcher_core::launch::launch_entry(entry);
+    }
+    let browser = entry
+        .browser
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+        .map(crate::paths::resolve_program);
+    app.opener()
+        .open_url(entry.path.trim(), browser)
+        .map_err(|e| e.to_string())
+}
+
+/// Launches an entry from the window by its fields, so unsaved edits launch as shown.
+#[tauri::command]
+pub fn launch_app(
+    app: AppHandle,
+    path: String,
+    args: Option<String>,
+    kind: Option<EntryKind>,
+    browser: Option<String>,
+    working_dir: Option<String>,
+) -> Result<(), String> {
+    let entry = AppEntry {
+        id: String::new(),
+        name: String::new(),
+        path,
+        args,
+        icon: None,
+        icon_hash: None,
+        pinned: false,
+        added_at: 0,
+        updated_at: 0,
+        kind,
+        browser,
+        working_dir,
+        description: None,
+        icon_location: None,
+    };
+    launch_entry(&app, &entry)?;
+    crate::window_utils::hide_after_launch(&app);
+    Ok(())
+}
+
+/// Launches the saved app `app_id` and records it in the launch history.
+pub(crate) fn launch_app_by_id(app: &AppHandle, app_id: &str) -> Result<(), String> {
+    let entry = crate::storage::load_app_entry(app, app_id)?
+        .ok_or_else(|| format!("app not found: {}", app_id))?;
+    launch_entry(app, &entry)?;
+    note_launch(app, app_id);
+    Ok(())
+}
+
+/// Adds a launch to the history and refreshes the tray's Recent section. History is
+/// best effort; a failed write never fails the launch itself.
+pub(crate) fn note_launch(app: &AppHandle, app_id: &str) {
+    if crate::storage::record_app_launch(app, app_id).is_ok() {
+        crate::tray::refresh_tray_menu(app);
+    }
+}
@@ -2,13 +2,25 @@
 use std::path::Path;
 use tauri::Manager;
 
+mod cli;
+mod clipboard;
+mod history;
+mod i18n;
 mod icon;
+mod instance;
 mod hotkey;
+mod hotkey_gesture;
+mod launch;
+mod live_reload;
 mod tray;
 mod uwp;
 mod window_utils;
 mod paths;
+mod profiles;
+mod scan;
+mod settings;
 mod storage;
+mod sync;
 
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -16,31 +28,17 @@ fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-fn spawn_app(path: String, args: Vec<String>) -> Result<(), String> {
-    let resolved_path = paths::resolve_launch_path(&path);
-    if args.is_empty() {
-        #[cfg(target_os = "windows")]
-        {
-            std::process::Command::new("explorer")
-                .arg(resolved_path)
-                .spawn()
-                .map(|_| ())
-                .map_err(|e| e.to_string())
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            std::process::Command::new(resolved_path)
-                .spawn()
-                .map(|_| ())
-                .map_err(|e| e.to_string())
-        }
-    } else {
-        std::process::Command::new(resolved_path)
-            .args(args)
-            .spawn()
-            .map(|_| ())
-            .map_err(|e| e.to_string())
-    }
+fn spawn_app(app: tauri::AppHandle, path: String, args: Vec<String>) -> Result<(), String> {
+    launch::spawn_path(&path, args)?;
+    window_utils::hide_after_launch(&app);
+    Ok(())
+}
+
+#[tauri::command]
+fn spawn_uwp_app(app: tauri::AppHandle, app_id: String) -> Result<(), String> {
+    uwp::spawn_uwp_app(app_id)?;
+    window_utils::hide_after_launch(&app);
+    Ok(())
 }
 
 #[tauri::command]
@@ -132,14 +130,81 @@ fn set_toggle_hotkey(
     hotkey::apply_hotkey(&app, &hotkey_state, hotkey)
 }
 
+#[tauri::command]
+fn validate_hotkey(
+    app: tauri::AppHandle,
+    hotkey_state: tauri::State<'_, hotkey::HotkeyState>,
+    hotkey: String,
+    mode: Option<storage::HotkeyMode>,
+) -> hotkey::HotkeyValidation {
+    hotkey::validate_hotkey(&app, &hotkey_state, hotkey, mode.unwrap_or_default())
+}
+
+#[tauri::command]
+fn take_hotkey_failures(
+    hotkey_state: tauri::State<'_, hotkey::HotkeyState>,
+) -> Vec<hotkey::HotkeyFailure> {
+    hotkey::take_startup_failures(&hotkey_state)
+}
+
+#[tauri::command]
+fn record_launch(app: tauri::AppHandle, app_id: String) {
+    launch::note_launch(&app, &app_id);
+}
+
+#[tauri::command]
+fn take_instance_commands(app: tauri::AppHandle) -> instance::PendingCommands {
+    instance::take_pending(&app)
+}
+
+#[tauri::command]
+fn get_data_location() -> quick_launcher_core::data_dir::DataLocation {
+    quick_launcher_core::data_dir::current().clone()
+}
+
+/// Moves the data and restarts, so everything reopens from the new place.
+#[tauri::command]
+fn move_data_location(
+    app: tauri::AppHandle,
+    target: quick_launcher_core::data_dir::DataDirTarget,
+) -> Result<(), String> {
+    let from = quick_launcher_core::data_dir::current();
+    live_reload::stop(&app);
+    quick_launcher_core::data_dir::move_data(from, target)?;
+    app.restart()
+}
+
+#[tauri::command]
+fn list_hotkeys(app: tauri::AppHandle) -> Result<Vec<storage::HotkeyBinding>, String> {
+    storage::load_hotkey_bindings(&app)
+}
+
+#[tauri::command]
+fn set_hotkeys(
+    app: tauri::AppHandle,
+    hotkey_state: tauri::State<'_, hotkey::HotkeyState>,
+    bindings: Vec<storage::HotkeyBinding>,
+) -> Result<Vec<storage::HotkeyBinding>, String> {
+    hotkey::set_bindings(&app, &hotkey_state, bindings)
+}
+
+/// Runs a command-line subcommand such as `list` or `backup` when one was given,
+/// returning its exit code; `None` means the launcher should start normally.
+pub fn run_cli() -> Option<i32> {
+    cli::run_from_env()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let args: Vec<String> = std::env::args().collect();
+    quick_launcher_core::data_dir::init(quick_launcher_core::data_dir::flag_from_args(&args));
+
     let builder = tauri::Builder::default();
 
     #[cfg(desktop)]
     let builder = builder.plugin(tauri_plugin_single_instance::init(
-        |app, _args, _cwd| {
-            window_utils::show_main_window(app);
+        |app, args, cwd| {
+            instance::handle_forwarded_args(app, args, cwd);
         },
     ));
 
@@ -148,50 +213,98 @@ pub fn run() {
 
     builder
         .setup(|app| {
+            #[cfg(desktop)]
+            app.manage(hotkey::HotkeyState::default());
+            if let Err(e) = storage::init_db(app.handle()) {
+                eprintln!("error: cannot open the database: {}", e);
+            }
+            app.manage(history::HistoryState::default());
+            history::clear(app.handle());
+            // Settings are read once here; saves re-apply them through `settings::update`
+            let ui_settings = settings::init(app.handle());
             #[cfg(desktop)]
             {
-                tray::setup_tray(&app.handle())?;
-                app.manage(hotkey::HotkeyState(std::sync::Mutex::new(None)));
+                tray::setup_tray(app.handle())?;
                 app.handle().plugin(
                     tauri_plugin_global_shortcut::Builder::new()
-                        .with_handler(|app, _shortcut, event| {
-                            hotkey::handle_shortcut_event(app, event.state);
+                        .with_handler(|app, shortcut, event| {
+                            if let Some(state) = app.try_state::<hotkey::HotkeyState>() {
+                                hotkey::handle_shortcut_event(app, &state, shortcut, event.state);
+                            }
                         })
                         .build(),
                 )?;
 
-                let saved = storage::load_saved_hotkey(&app.handle());
+                let saved = storage::load_hotkey_bindings(app.handle()).unwrap_or_default();
                 if let Some(state) = app.try_state::<hotkey::HotkeyState>() {
-                    hotkey::init_from_saved_hotkey(&app.handle(), &state, saved);
+                    hotkey::init_from_saved_bindings(app.handle(), &state, saved);
                 }
             }
-            if storage::should_hide_on_startup(&app.handle()) {
+            // The window starts hidden so it can be placed before it is first shown
+            window_utils::restore_window_geometry(app.handle());
+            if !ui_settings.hide_on_startup {
                 if let Some(w) = app.get_webview_window("main") {
-                    let _ = w.hide();
+                    let _ = w.show();
                 }
             }
+            instance::handle_startup_args(app.handle());
+            live_reload::start(app.handle());
+            sync::start(app.handle());
             Ok(())
         })
-        .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } => {
                 api.prevent_close();
                 let _ = window.hide();
             }
+            tauri::WindowEvent::Focused(focused) if window.label() == "main" => {
+                window_utils::on_main_focus_changed(window.app_handle(), *focused);
+            }
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)
+                if window.label() == "main" =>
+            {
+                window_utils::schedule_geometry_save(window.app_handle());
+            }
+            _ => {}
         })
+        .register_asynchronous_uri_scheme_protocol(icon::ICON_PROTOCOL, icon::handle_icon_protocol)
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, None))
         .invoke_handler(tauri::generate_handler![
             greet,
             spawn_app,
+            record_launch,
+            take_instance_commands,
+            get_data_location,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::clone_profile,
+            profiles::switch_profile,
+            move_data_location,
             uwp::list_uwp_apps,
-            uwp::spawn_uwp_app,
+            spawn_uwp_app,
+            launch::launch_app,
             icon::get_file_icon,
             set_toggle_hotkey,
+            validate_hotkey,
+            take_hotkey_failures,
+            list_hotkeys,
+            set_hotkeys,
             paths::make_relative_path,
+            paths::resolve_shortcut,
+            scan::scan_folder_for_apps,
+            scan::import_launcher_config,
+            scan::import_scanned_apps,
+            scan::suggested_scan_roots,
             open_app_folder,
             storage::load_launcher_state,
             storage::save_launcher_state,
+            storage::load_state_revision,
+            sync::sync_library,
+            history::undo,
+            history::redo,
+            history::history,
             paths::validate_paths
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rusqlite::{Connection, OpenFlags};
+use tauri::{AppHandle, Emitter, Manager};
+
+use quick_launcher_core::storage as core;
+
+/// Carries the fresh `LauncherState` after another process changed the database.
+pub const STATE_CHANGED_EVENT: &str = "state-changed";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Watched {
+    path: PathBuf,
+    conn: Connection,
+    data_version: i64,
+}
+
+/// What the watcher compares against to tell other writers from this app.
+#[derive(Default)]
+pub struct LiveReloadState {
+    /// Revision of the state the UI holds.
+    revision: Mutex<u64>,
+    watched: Mutex<Option<Watched>>,
+    stopped: AtomicBool,
+}
+
+/// Remembers that the UI now holds `revision`.
+pub fn note_revision(app: &AppHandle, revision: u64) {
+    if let Some(state) = app.try_state::<LiveReloadState>() {
+        if let Ok(mut known) = state.revision.lock() {
+            *known = revision;
+        }
+    }
+}
+
+/// Runs a write of the UI's state and remembers the revision it returns. The watcher
+/// waits meanwhile, so it never mistakes this write for somebody else's.
+pub fn write_state(
+    app: &AppHandle,
+    write: impl FnOnce() -> Result<u64, String>,
+) -> Result<u64, String> {
+    let Some(state) = app.try_state::<LiveReloadState>() else {
+        return write();
+    };
+    let mut known = state.revision.lock().map_err(|e| e.to_string())?;
+    let revision = write()?;
+    *known = revision;
+    Ok(revision)
+}
+
+/// Stops watching and closes the watcher's connection, e.g. before the data moves.
+pub fn stop(app: &AppHandle) {
+    if let Some(state) = app.try_state::<LiveReloadState>() {
+        state.stopped.store(true, Ordering::SeqCst);
+        if let Ok(mut watched) = state.watched.lock() {
+            *watched = None;
+        }
+    }
+}
+
+/// Starts polling `PRAGMA data_version` of the active profile's database.
+pub fn start(app: &AppHandle) {
+    app.manage(LiveReloadState::default());
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let Some(state) = app.try_state::<LiveReloadState>() else {
+            return;
+        };
+        if state.stopped.load(Ordering::SeqCst) {
+            return;
+        }
+        // A busy or briefly missing database is simply tried again next time
+        let _ = poll(&app, &state);
+    });
+}
+
+fn open_watched(path: PathBuf) -> Result<Option<Watched>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open_with_flags(
+        &path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| e.to_string())?;
+    let data_version = core::read_data_version(&conn)?;
+    Ok(Some(Watched {
+        path,
+        conn,
+        data_version,
+    }))
+}
+
+fn poll(app: &AppHandle, state: &LiveReloadState) -> Result<(), String> {
+    let path = core::default_db_path()?;
+    let mut watched = state.watched.lock().map_err(|e| e.to_string())?;
+    // A profile switch points the watcher at another database
+    if watched.as_ref().map(|w| &w.path) != Some(&path) {
+        *watched = open_watched(path)?;
+        return Ok(());
+    }
+    let Some(w) = watched.as_mut() else {
+        return Ok(());
+    };
+    let data_version = core::read_data_version(&w.conn)?;
+    if data_version == w.data_version {
+        return Ok(());
+    }
+    w.data_version = data_version;
+
+    let mut known = state.revision.lock().map_err(|e| e.to_string())?;
+    if core::read_revision(&w.conn) == *known {
+        return Ok(());
+    }
+    let Some(fresh) = core::read_launcher_state(&w.conn)? else {
+        return Ok(());
+    };
+    *known = fresh.revision;
+    drop(known);
+    drop(watched);
+
+    crate::settings::update(app, &fresh.settings);
+    crate::tray::refresh_tray_menu(app);
+    let _ = app.emit(STATE_CHANGED_EVENT, &fresh);
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use tauri::{AppHandle, Emitter, Manager};
+
+use quick_launcher_core::profiles::{self, Profile, ProfileList};
+
+pub const PROFILE_SWITCHED_EVENT: &str = "profile-switched";
+pub const PROFILE_SWITCH_FAILED_EVENT: &str = "profile-switch-failed";
+
+#[tauri::command]
+pub fn list_profiles() -> ProfileList {
+    profiles::list()
+}
+
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String) -> Result<Profile, String> {
+    let profile = profiles::create(&name)?;
+    crate::tray::refresh_tray_menu(&app);
+    Ok(profile)
+}
+
+/// Copies `source`, or the active profile when it is not given, into a new profile.
+#[tauri::command]
+pub fn clone_profile(
+    app: AppHandle,
+    source: Option<String>,
+    name: String,
+) -> Result<Profile, String> {
+    let source = source.unwrap_or_else(profiles::active_id);
+    let profile = profiles::clone_profile(&source, &name)?;
+    crate::tray::refresh_tray_menu(&app);
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, id: String) -> Result<Profile, String> {
+    switch_to(&app, &id)
+}
+
+/// Makes `id_or_name` active and brings the backend over to it: its settings are
+/// applied, its hotkeys replace the old ones, and the UI is told to reload.
+pub fn switch_to(app: &AppHandle, id_or_name: &str) -> Result<Profile, String> {
+    let profile = profiles::switch(id_or_name)?;
+    crate::history::clear(app);
+    let settings = crate::storage::load_settings(app);
+    crate::settings::update(app, &settings);
+    if let Some(state) = app.try_state::<crate::hotkey::HotkeyState>() {
+        crate::hotkey::reload_saved_bindings(app, &state);
+    }
+    crate::tray::refresh_tray_menu(app);
+    let _ = app.emit(PROFILE_SWITCHED_EVENT, &profile);
+    Ok(profile)
+}
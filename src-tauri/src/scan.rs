@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use tauri::AppHandle;
+
+use quick_launcher_core::importers;
+use quick_launcher_core::scan::{self, ScanOptions, ScanPreview, ScanRoot};
+use quick_launcher_core::storage as core;
+
+use crate::storage::LauncherState;
+
+/// Walks `root` and returns what it would add, without saving anything.
+#[tauri::command]
+pub async fn scan_folder_for_apps(
+    root: String,
+    options: Option<ScanOptions>,
+) -> Result<ScanPreview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = core::read_launcher_state(&core::open(&core::default_db_path()?)?)?;
+        scan::scan_folder(
+            &PathBuf::from(root.trim()),
+            &options.unwrap_or_default(),
+            state.as_ref(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Reads another launcher's configuration or a bookmark export and returns what it
+/// would add. `format` picks the importer; without it the file's contents decide.
+#[tauri::command]
+pub async fn import_launcher_config(
+    path: String,
+    format: Option<String>,
+) -> Result<ScanPreview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = core::read_launcher_state(&core::open(&core::default_db_path()?)?)?;
+        importers::import_file(
+            &PathBuf::from(path.trim()),
+            format.as_deref(),
+            state.as_ref(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Adds the apps of an accepted preview in one save and returns the state after it.
+#[tauri::command]
+pub fn import_scanned_apps(app: AppHandle, preview: ScanPreview) -> Result<LauncherState, String> {
+    let mut conn = core::open(&core::default_db_path()?)?;
+    let current = core::read_launcher_state(&conn)?.ok_or("nothing saved yet")?;
+    let (mut next, added) = scan::apply_preview(&current, &preview);
+    if added == 0 {
+        return Ok(current);
+    }
+    next.revision =
+        crate::live_reload::write_state(&app, || core::write_launcher_state(&mut conn, &next))?;
+    crate::history::record(&app, &conn, next.revision);
+    crate::tray::refresh_tray_menu(&app);
+    crate::sync::request(&app);
+    Ok(next)
+}
+
+/// Start Menu and Desktop folders to offer for scanning.
+#[tauri::command]
+pub fn suggested_scan_roots() -> Vec<ScanRoot> {
+    scan::suggested_roots()
+}
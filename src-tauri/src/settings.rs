@@ -0,0 +1,70 @@
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::storage::UiSettings;
+
+pub const SETTINGS_APPLY_FAILED_EVENT: &str = "settings-apply-failed";
+
+/// The settings last applied to the window and the OS. The rest of the backend reads
+/// them from here instead of going back to the database on every event.
+#[derive(Default)]
+pub struct SettingsState(pub Mutex<UiSettings>);
+
+/// The current settings, or the saved ones if the state isn't managed yet.
+pub fn current(app: &AppHandle) -> UiSettings {
+    app.try_state::<SettingsState>()
+        .and_then(|s| s.0.lock().ok().map(|g| g.clone()))
+        .unwrap_or_else(|| crate::storage::load_settings(app))
+}
+
+/// Reads the saved settings once at startup, keeps them, and applies them.
+pub fn init(app: &AppHandle) -> UiSettings {
+    let settings = crate::storage::load_settings(app);
+    app.manage(SettingsState(Mutex::new(settings.clone())));
+    apply(app, &settings);
+    settings
+}
+
+/// Stores freshly saved settings and applies them, so what is in the database and
+/// what the app actually does stay the same.
+pub fn update(app: &AppHandle, settings: &UiSettings) {
+    if let Some(state) = app.try_state::<SettingsState>() {
+        if let Ok(mut current) = state.0.lock() {
+            *current = settings.clone();
+        }
+    }
+    apply(app, settings);
+}
+
+/// Applies every window-level and OS-level setting. Failures are reported to the UI
+/// with [`SETTINGS_APPLY_FAILED_EVENT`]; the settings themselves stay saved.
+fn apply(app: &AppHandle, settings: &UiSettings) {
+    let mut errors: Vec<String> = Vec::new();
+
+    if let Some(w) = app.get_webview_window("main") {
+        if let Err(e) = w.set_always_on_top(settings.always_on_top) {
+            errors.push(format!("alwaysOnTop: {}", e));
+        }
+    }
+
+    let autolaunch = app.autolaunch();
+    let enabled = autolaunch.is_enabled().unwrap_or(!settings.auto_start);
+    if enabled != settings.auto_start {
+        let result = if settings.auto_start {
+            autolaunch.enable()
+        } else {
+            autolaunch.disable()
+        };
+        if let Err(e) = result {
+            errors.push(format!("autoStart: {}", e));
+        }
+    }
+
+    crate::hotkey::apply_settings_timing(app, settings);
+
+    if !errors.is_empty() {
+        let _ = app.emit(SETTINGS_APPLY_FAILED_EVENT, errors.join("; "));
+    }
+}
@@ -1,170 +1,24 @@
-use rusqlite::{params, Connection};
-use serde::{Deserialize, Serialize};
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-    path::PathBuf,
-};
+use rusqlite::Connection;
+use std::{fs, path::PathBuf};
 use tauri::Manager;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LauncherState {
-    pub version: u8,
-    #[serde(rename = "activeGroupId")]
-    pub active_group_id: String,
-    pub groups: Vec<Group>,
-    #[serde(default)]
-    pub settings: UiSettings,
-}
-
-fn default_card_size() -> u32 {
-    120
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UiSettings {
-    #[serde(rename = "language", default = "default_language")]
-    pub language: String,
-    #[serde(rename = "cardWidth", alias = "cardSize", default = "default_card_size")]
-    pub card_width: u32,
-    #[serde(rename = "cardHeight", default = "default_card_height")]
-    pub card_height: u32,
-    #[serde(rename = "toggleHotkey", default)]
-    pub toggle_hotkey: String,
-    #[serde(rename = "theme", default = "default_theme")]
-    pub theme: String,
-    #[serde(rename = "sidebarWidth", default = "default_sidebar_width")]
-    pub sidebar_width: u32,
-    #[serde(rename = "fontFamily", default = "default_font_family")]
-    pub font_family: String,
-    #[serde(rename = "fontSize", default = "default_font_size")]
-    pub font_size: u32,
-    #[serde(rename = "cardFontSize", default = "default_card_font_size")]
-    pub card_font_size: u32,
-    #[serde(rename = "cardIconScale", default = "default_card_icon_scale")]
-    pub card_icon_scale: u32,
-    #[serde(
-        rename = "dblClickBlankToHide",
-        default = "default_dbl_click_blank_to_hide"
-    )]
-    pub dbl_click_blank_to_hide: bool,
-    #[serde(rename = "alwaysOnTop", default = "default_always_on_top")]
-    pub always_on_top: bool,
-    #[serde(rename = "hideOnStartup", default = "default_hide_on_startup")]
-    pub hide_on_startup: bool,
-    #[serde(rename = "useRelativePath", default = "default_use_relative_path")]
-    pub use_relative_path: bool,
-    #[serde(
-        rename = "enableGroupDragSort",
-        default = "default_enable_group_drag_sort"
-    )]
-    pub enable_group_drag_sort: bool,
-    #[serde(rename = "autoStart", default)]
-    pub auto_start: bool,
-}
-
-fn default_language() -> String {
-    String::new()
-}
-
-fn default_card_height() -> u32 {
-    96
-}
-
-fn default_theme() -> String {
-    "dark".to_string()
-}
-
-fn default_sidebar_width() -> u32 {
-    140
-}
-
-fn default_font_family() -> String {
-    "maye".to_string()
-}
-
-fn default_font_size() -> u32 {
-    13
-}
-
-fn default_card_font_size() -> u32 {
-    11
-}
-
-fn default_card_icon_scale() -> u32 {
-    56
-}
+use quick_launcher_core::storage as core;
 
-fn default_dbl_click_blank_to_hide() -> bool {
-    true
-}
-
-fn default_always_on_top() -> bool {
-    true
-}
-
-fn default_hide_on_startup() -> bool {
-    false
-}
-
-fn default_use_relative_path() -> bool {
-    false
-}
-
-fn default_enable_group_drag_sort() -> bool {
-    false
-}
-
-impl Default for UiSettings {
-    fn default() -> Self {
-        Self {
-            language: default_language(),
-            card_width: default_card_size(),
-            card_height: default_card_height(),
-            toggle_hotkey: String::new(),
-            theme: default_theme(),
-            sidebar_width: default_sidebar_width(),
-            font_family: default_font_family(),
-            font_size: default_font_size(),
-            card_font_size: default_card_font_size(),
-            card_icon_scale: default_card_icon_scale(),
-            dbl_click_blank_to_hide: default_dbl_click_blank_to_hide(),
-            always_on_top: default_always_on_top(),
-            hide_on_startup: default_hide_on_startup(),
-            use_relative_path: default_use_relative_path(),
-            enable_group_drag_sort: default_enable_group_drag_sort(),
-            auto_start: false,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Group {
-    pub id: String,
-    pub name: String,
-    pub apps: Vec<AppEntry>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AppEntry {
-    pub id: String,
-    pub name: String,
-    pub path: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub args: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub icon: Option<String>,
-    #[serde(rename = "addedAt")]
-    pub added_at: i64,
-}
+pub use quick_launcher_core::model::{
+    AppEntry, HotkeyAction, HotkeyBinding, HotkeyMode, IconBlob, LauncherState, UiSettings,
+    WindowGeometry,
+};
 
 fn db_path(_app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let base = crate::paths::app_base_dir().ok_or("Cannot determine exe directory")?;
-    Ok(base.join("data").join("launcher.db"))
+    core::default_db_path()
 }
 
 fn legacy_db_paths(app: &tauri::AppHandle) -> Vec<PathBuf> {
     let mut paths = Vec::new();
+    // `data/` next to the exe, when the data now lives somewhere else
+    if let Some(p) = quick_launcher_core::data_dir::portable_dir() {
+        paths.push(p.join("launcher.db"));
+    }
     // 旧版: %localappdata%/my-quickstart/launcher.db
     if let Ok(p) = app.path().local_data_dir() {
         paths.push(p.join("my-quickstart").join("launcher.db"));
@@ -216,292 +70,141 @@ fn migrate_legacy_db_if_needed(app: &tauri::AppHandle, new_path: &PathBuf) -> Re
     Ok(())
 }
 
-fn open_db(app: &tauri::AppHandle) -> Result<Connection, String> {
+/// Brings the database over from older installs and its schema up to date. Called once
+/// at startup, before anything else opens the database.
+pub(crate) fn init_db(app: &tauri::AppHandle) -> Result<(), String> {
     let path = db_path(app)?;
-    migrate_legacy_db_if_needed(app, &path)?;
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    // Older installs only ever had the one database the default profile still uses.
+    if path == quick_launcher_core::data_dir::current().db_path() {
+        migrate_legacy_db_if_needed(app, &path)?;
     }
-    let conn = Connection::open(path).map_err(|e| e.to_string())?;
-    conn.pragma_update(None, "journal_mode", "WAL")
-        .map_err(|e| e.to_string())?;
-    conn.pragma_update(None, "synchronous", "NORMAL")
-        .map_err(|e| e.to_string())?;
-    conn.pragma_update(None, "busy_timeout", "5000")
-        .map_err(|e| e.to_string())?;
-    conn.pragma_update(None, "foreign_keys", "ON")
-        .map_err(|e| e.to_string())?;
-    conn.pragma_update(None, "cache_size", "-8000")
-        .map_err(|e| e.to_string())?;
-    conn.pragma_update(None, "temp_store", "2")
-        .map_err(|e| e.to_string())?;
-    conn.execute_batch(
-        r#"
-CREATE TABLE IF NOT EXISTS meta (
-  key TEXT PRIMARY KEY,
-  value TEXT NOT NULL
-);
-CREATE TABLE IF NOT EXISTS groups (
-  id TEXT PRIMARY KEY,
-  name TEXT NOT NULL,
-  position INTEGER NOT NULL
-);
-CREATE TABLE IF NOT EXISTS apps (
-  id TEXT PRIMARY KEY,
-  group_id TEXT NOT NULL,
-  name TEXT NOT NULL,
-  path TEXT NOT NULL,
-  args TEXT NOT NULL,
-  icon TEXT NOT NULL DEFAULT '',
-  position INTEGER NOT NULL,
-  added_at INTEGER NOT NULL,
-  FOREIGN KEY(group_id) REFERENCES groups(id) ON DELETE CASCADE
-);
-CREATE INDEX IF NOT EXISTS idx_apps_group_position ON apps(group_id, position);
-CREATE TABLE IF NOT EXISTS app_icons (
-  app_id TEXT PRIMARY KEY,
-  icon TEXT NOT NULL,
-  updated_at INTEGER NOT NULL
-);
-"#,
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Migration: apps.icon -> app_icons
-    conn.execute(
-        "INSERT OR IGNORE INTO app_icons (app_id, icon, updated_at)
-         SELECT id, icon, added_at FROM apps WHERE icon != ''",
-        [],
-    )
-    .map_err(|e| e.to_string())?;
+    core::open(&path).map(|_| ())
+}
 
-    let has_icon: i64 = conn
-        .query_row(
-            "SELECT COUNT(1) FROM pragma_table_info('apps') WHERE name = 'icon'",
-            [],
-            |r| r.get(0),
-        )
-        .unwrap_or(0);
-    if has_icon == 0 {
-        let _ = conn.execute("ALTER TABLE apps ADD COLUMN icon TEXT NOT NULL DEFAULT ''", []);
-    }
-    Ok(conn)
+/// Opens the database for writing, creating it when the active profile has none yet.
+fn open_db(app: &tauri::AppHandle) -> Result<Connection, String> {
+    core::open(&db_path(app)?)
 }
 
+/// The UI's load: the revision read here is what the window now shows, so the
+/// live-reload poller stops reporting it as an outside change.
 #[tauri::command]
 pub fn load_launcher_state(app: tauri::AppHandle) -> Result<Option<LauncherState>, String> {
     let conn = open_db(&app)?;
+    let state = core::read_launcher_state(&conn)?;
+    crate::live_reload::note_revision(&app, core::read_revision(&conn));
+    Ok(state)
+}
 
-    let has_any: i64 = conn
-        .query_row("SELECT COUNT(1) FROM groups", [], |r| r.get(0))
-        .map_err(|e| e.to_string())?;
-    if has_any == 0 {
-        return Ok(None);
-    }
-
-    let active_group_id: String = conn
-        .query_row(
-            "SELECT value FROM meta WHERE key = 'active_group_id' LIMIT 1",
-            [],
-            |r| r.get(0),
-        )
-        .unwrap_or_else(|_| String::new());
-
-    let mut groups_stmt = conn
-        .prepare("SELECT id, name FROM groups ORDER BY position ASC")
-        .map_err(|e| e.to_string())?;
-    let group_rows = groups_stmt
-        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
-        .map_err(|e| e.to_string())?;
-
-    let mut groups: Vec<Group> = Vec::new();
-    for row in group_rows {
-        let (id, name) = row.map_err(|e| e.to_string())?;
-        groups.push(Group {
-            id,
-            name,
-            apps: Vec::new(),
-        });
-    }
-
-    let mut apps_stmt = conn
-        .prepare(
-            "SELECT a.id, a.group_id, a.name, a.path, a.args, COALESCE(i.icon, a.icon) as icon, a.added_at
-             FROM apps a
-             LEFT JOIN app_icons i ON a.id = i.app_id
-             ORDER BY a.position ASC",
-        )
-        .map_err(|e| e.to_string())?;
-    let app_rows = apps_stmt
-        .query_map([], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, i64>(6)?,
-            ))
-        })
-        .map_err(|e| e.to_string())?;
-
-    let mut apps_by_group: HashMap<String, Vec<AppEntry>> = HashMap::new();
-    for row in app_rows {
-        let (id, group_id, name, path, args, icon, added_at) = row.map_err(|e| e.to_string())?;
-        let args_opt = if args.trim().is_empty() { None } else { Some(args) };
-        let icon_opt = if icon.trim().is_empty() { None } else { Some(icon) };
-        apps_by_group.entry(group_id).or_default().push(AppEntry {
-            id,
-            name,
-            path,
-            args: args_opt,
-            icon: icon_opt,
-            added_at,
-        });
-    }
-
-    for g in &mut groups {
-        if let Some(apps) = apps_by_group.remove(&g.id) {
-            g.apps = apps;
-        }
-    }
-
-    let active = if !active_group_id.is_empty() && groups.iter().any(|g| g.id == active_group_id) {
-        active_group_id
-    } else {
-        groups
-            .first()
-            .map(|g| g.id.clone())
-            .unwrap_or_else(String::new)
-    };
-
-    let settings = load_ui_settings(&conn);
+/// Reads the saved state for backend use, such as the tray menu. Unlike
+/// [`load_launcher_state`] it leaves the live-reload revision alone, so an outside
+/// write it happens to see still reaches the window.
+pub(crate) fn read_state(app: &tauri::AppHandle) -> Result<Option<LauncherState>, String> {
+    core::read_launcher_state(&open_existing_db(app)?)
+}
 
-    Ok(Some(LauncherState {
-        version: 1,
-        active_group_id: active,
-        groups,
-        settings,
-    }))
+/// The current revision, for a first save into a database without groups.
+#[tauri::command]
+pub fn load_state_revision(app: tauri::AppHandle) -> Result<u64, String> {
+    Ok(core::read_revision(&open_db(&app)?))
 }
 
+/// Saves `state` and returns its new revision. A state read before somebody else's
+/// write is rejected with [`core::STALE_STATE_ERROR`].
 #[tauri::command]
-pub fn save_launcher_state(app: tauri::AppHandle, state: LauncherState) -> Result<(), String> {
+pub fn save_launcher_state(app: tauri::AppHandle, state: LauncherState) -> Result<u64, String> {
     let mut conn = open_db(&app)?;
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let revision = crate::live_reload::write_state(&app, || {
+        core::write_launcher_state(&mut conn, &state)
+    })?;
+    crate::history::record(&app, &conn, revision);
+    crate::settings::update(&app, &state.settings);
+    crate::tray::refresh_tray_menu(&app);
+    crate::sync::request(&app);
+    crate::icon::request_icon_persist(&app);
+    Ok(revision)
+}
 
-    // UPSERT meta
-    tx.execute(
-        "INSERT INTO meta(key, value) VALUES('active_group_id', ?1)
-         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
-        params![state.active_group_id],
-    )
-    .map_err(|e| e.to_string())?;
+/// The database as it already is, for the icon protocol and backend reads such as the
+/// tray menu: every card's icon is a request, and none of them should re-run the schema
+/// checks [`init_db`] did at startup.
+fn open_existing_db(app: &tauri::AppHandle) -> Result<Connection, String> {
+    core::open_existing(&db_path(app)?)
+}
 
-    let settings_json = serde_json::to_string(&state.settings).map_err(|e| e.to_string())?;
-    tx.execute(
-        "INSERT INTO meta(key, value) VALUES('ui_settings', ?1)
-         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
-        params![settings_json],
-    )
-    .map_err(|e| e.to_string())?;
+pub(crate) fn load_app_icon(app: &tauri::AppHandle, app_id: &str) -> Result<Option<IconBlob>, String> {
+    core::read_app_icon(&open_existing_db(app)?, app_id)
+}
 
-    // Collect current IDs for diff delete
-    let mut new_group_ids: HashSet<String> = HashSet::new();
-    let mut new_app_ids: HashSet<String> = HashSet::new();
+/// The stored icons of `app_ids`, by app id, read over one connection.
+pub(crate) fn load_app_icons(
+    app: &tauri::AppHandle,
+    app_ids: &[&str],
+) -> Result<std::collections::HashMap<String, IconBlob>, String> {
+    core::read_app_icons(&open_existing_db(app)?, app_ids)
+}
 
-    // UPSERT groups
-    for (group_pos, group) in state.groups.iter().enumerate() {
-        new_group_ids.insert(group.id.clone());
-        tx.execute(
-            "INSERT INTO groups(id, name, position) VALUES(?1, ?2, ?3)
-             ON CONFLICT(id) DO UPDATE SET name = excluded.name, position = excluded.position",
-            params![group.id, group.name, group_pos as i64],
-        )
-        .map_err(|e| e.to_string())?;
+/// The saved path of `app_id` and the icon location set for it, if any.
+pub(crate) fn load_app_icon_source(
+    app: &tauri::AppHandle,
+    app_id: &str,
+) -> Result<Option<(String, Option<String>)>, String> {
+    core::read_app_icon_source(&open_existing_db(app)?, app_id)
+}
 
-        // UPSERT apps
-        for (app_pos, app_entry) in group.apps.iter().enumerate() {
-            new_app_ids.insert(app_entry.id.clone());
-            tx.execute(
-                "INSERT INTO apps(id, group_id, name, path, args, icon, position, added_at)
-                 VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-                 ON CONFLICT(id) DO UPDATE SET
-                   group_id = excluded.group_id,
-                   name = excluded.name,
-                   path = excluded.path,
-                   args = excluded.args,
-                   icon = excluded.icon,
-                   position = excluded.position",
-                params![
-                    app_entry.id,
-                    group.id,
-                    app_entry.name,
-                    app_entry.path,
-                    app_entry.args.as_deref().unwrap_or(""),
-                    app_entry.icon.as_deref().unwrap_or(""),
-                    app_pos as i64,
-                    app_entry.added_at
-                ],
-            )
-            .map_err(|e| e.to_string())?;
-        }
-    }
+/// The saved apps without a stored icon, as `(id, path, icon location)`.
+pub(crate) fn load_apps_without_icon(
+    app: &tauri::AppHandle,
+) -> Result<Vec<(String, String, Option<String>)>, String> {
+    core::read_apps_without_icon(&open_existing_db(app)?)
+}
 
-    // Delete removed apps (diff delete)
-    if !new_app_ids.is_empty() {
-        let placeholders: String = new_app_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-        let sql = format!("DELETE FROM apps WHERE id NOT IN ({})", placeholders);
-        let params: Vec<&dyn rusqlite::ToSql> = new_app_ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
-        tx.execute(&sql, params.as_slice()).map_err(|e| e.to_string())?;
-    } else {
-        tx.execute("DELETE FROM apps", []).map_err(|e| e.to_string())?;
+/// Persists PNG icons extracted for apps, as `(id, path, bytes)`, over one connection.
+/// An icon is skipped when its app is gone or no longer saved with that path.
+pub(crate) fn store_app_icons(
+    app: &tauri::AppHandle,
+    icons: &[(String, String, Vec<u8>)],
+) -> Result<(), String> {
+    let mut conn = open_existing_db(app)?;
+    for (app_id, path, bytes) in icons {
+        core::write_app_icon(&mut conn, app_id, path, "image/png", bytes)?;
     }
+    Ok(())
+}
 
-    // Delete removed groups (diff delete)
-    if !new_group_ids.is_empty() {
-        let placeholders: String = new_group_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-        let sql = format!("DELETE FROM groups WHERE id NOT IN ({})", placeholders);
-        let params: Vec<&dyn rusqlite::ToSql> = new_group_ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
-        tx.execute(&sql, params.as_slice()).map_err(|e| e.to_string())?;
-    } else {
-        tx.execute("DELETE FROM groups", []).map_err(|e| e.to_string())?;
-    }
+pub(crate) fn load_hotkey_bindings(app: &tauri::AppHandle) -> Result<Vec<HotkeyBinding>, String> {
+    core::read_hotkey_bindings(&open_db(app)?)
+}
 
-    tx.commit().map_err(|e| e.to_string())
+pub(crate) fn save_hotkey_bindings(app: &tauri::AppHandle, bindings: &[HotkeyBinding]) -> Result<(), String> {
+    core::write_hotkey_bindings(&mut open_db(app)?, bindings)
 }
 
-fn load_ui_settings(conn: &Connection) -> UiSettings {
-    let settings_raw: String = conn
-        .query_row(
-            "SELECT value FROM meta WHERE key = 'ui_settings' LIMIT 1",
-            [],
-            |r| r.get(0),
-        )
-        .unwrap_or_else(|_| String::new());
-    if settings_raw.trim().is_empty() {
-        return UiSettings::default();
-    }
-    serde_json::from_str::<UiSettings>(&settings_raw).unwrap_or_else(|_| UiSettings::default())
+pub(crate) fn load_app_entry(app: &tauri::AppHandle, app_id: &str) -> Result<Option<AppEntry>, String> {
+    core::read_app_entry(&open_existing_db(app)?, app_id)
 }
 
-pub(crate) fn load_saved_hotkey(app: &tauri::AppHandle) -> Option<String> {
-    let conn = open_db(app).ok()?;
-    let settings = load_ui_settings(&conn);
-    let v = settings.toggle_hotkey.trim().to_string();
-    if v.is_empty() {
-        None
-    } else {
-        Some(v)
-    }
+pub(crate) fn record_app_launch(app: &tauri::AppHandle, app_id: &str) -> Result<(), String> {
+    core::insert_app_launch(&open_db(app)?, app_id)
 }
 
-pub(crate) fn should_hide_on_startup(app: &tauri::AppHandle) -> bool {
-    let conn = match open_db(app) {
-        Ok(c) => c,
-        Err(_) => return false,
-    };
-    let settings = load_ui_settings(&conn);
-    settings.hide_on_startup
+/// Most recently launched app ids, newest first, skipping apps that no longer exist.
+pub(crate) fn load_recent_app_ids(app: &tauri::AppHandle, limit: usize) -> Result<Vec<String>, String> {
+    core::read_recent_app_ids(&open_existing_db(app)?, limit)
+}
+
+pub(crate) fn load_window_geometry(app: &tauri::AppHandle) -> Option<WindowGeometry> {
+    core::read_window_geometry(&open_db(app).ok()?)
+}
+
+pub(crate) fn save_window_geometry(app: &tauri::AppHandle, geometry: &WindowGeometry) -> Result<(), String> {
+    core::write_window_geometry(&open_db(app)?, geometry)
+}
+
+/// Reads the saved UI settings, falling back to the defaults when the database can't be opened.
+pub(crate) fn load_settings(app: &tauri::AppHandle) -> UiSettings {
+    match open_db(app) {
+        Ok(conn) => core::read_ui_settings(&conn),
+        Err(_) => UiSettings::default(),
+    }
 }
@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use quick_launcher_core::storage as core;
+use quick_launcher_core::sync::{self, SyncOutcome};
+
+pub const SYNC_FAILED_EVENT: &str = "sync-failed";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// The library file as the last sync left it, to notice when a sync tool replaces it.
+#[derive(PartialEq)]
+struct FileStamp {
+    folder: PathBuf,
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    fn read(folder: PathBuf) -> Self {
+        let meta = std::fs::metadata(sync::library_file(&folder)).ok();
+        FileStamp {
+            modified: meta.as_ref().and_then(|m| m.modified().ok()),
+            len: meta.map(|m| m.len()).unwrap_or(0),
+            folder,
+        }
+    }
+}
+
+/// Held while a sync runs, so only one runs at a time.
+#[derive(Default)]
+pub struct SyncState {
+    synced: Mutex<Option<FileStamp>>,
+    last_error: Mutex<Option<String>>,
+}
+
+fn sync_folder_setting(app: &AppHandle) -> Option<PathBuf> {
+    let folder = crate::settings::current(app).sync_dir;
+    let folder = folder.trim();
+    (!folder.is_empty()).then(|| PathBuf::from(folder))
+}
+
+/// Syncs the active profile with its sync folder, if it has one. Changes imported into
+/// the database reach the UI through the live reload watcher.
+fn run(app: &AppHandle) -> Result<SyncOutcome, String> {
+    let Some(folder) = sync_folder_setting(app) else {
+        return Ok(SyncOutcome::default());
+    };
+    let state = app.state::<SyncState>();
+    let mut synced = state.synced.lock().map_err(|e| e.to_string())?;
+    let db_path = core::default_db_path()?;
+    let outcome = sync::sync_folder(&mut core::open(&db_path)?, &db_path, &folder)?;
+    *synced = Some(FileStamp::read(folder));
+    Ok(outcome)
+}
+
+/// Reports a failure once, not on every poll while the folder stays unreachable.
+fn report(app: &AppHandle, result: Result<SyncOutcome, String>) {
+    let state = app.state::<SyncState>();
+    let Ok(mut last_error) = state.last_error.lock() else {
+        return;
+    };
+    match result {
+        // Somebody saved in between; that save syncs again
+        Err(e) if e.starts_with(core::STALE_STATE_ERROR) => {}
+        Err(e) => {
+            if last_error.as_ref() != Some(&e) {
+                let _ = app.emit(SYNC_FAILED_EVENT, &e);
+            }
+            *last_error = Some(e);
+        }
+        Ok(_) => *last_error = None,
+    }
+}
+
+/// Syncs in the background after the library was saved.
+pub fn request(app: &AppHandle) {
+    if sync_folder_setting(app).is_none() {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || report(&app, run(&app)));
+}
+
+/// Watches the library file in the sync folder and imports it whenever it changes.
+pub fn start(app: &AppHandle) {
+    app.manage(SyncState::default());
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let Some(folder) = sync_folder_setting(&app) else {
+            continue;
+        };
+        let changed = match app.state::<SyncState>().synced.lock() {
+            Ok(synced) => synced.as_ref() != Some(&FileStamp::read(folder)),
+            Err(_) => return,
+        };
+        if changed {
+            report(&app, run(&app));
+        }
+    });
+}
+
+#[tauri::command]
+pub fn sync_library(app: AppHandle) -> Result<SyncOutcome, String> {
+    run(&app)
+}
@@ -1,37 +1,230 @@
-use tauri::menu::{MenuBuilder, MenuEvent, MenuItem};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use tauri::image::Image;
+use tauri::menu::{
+    CheckMenuItem, IconMenuItem, IsMenuItem, Menu, MenuBuilder, MenuEvent, MenuItem, Submenu,
+    SubmenuBuilder,
+};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
-use tauri::{AppHandle, Manager, Wry};
+use tauri::{AppHandle, Emitter, Manager, Wry};
 
+use crate::i18n::tr;
+use crate::storage::{AppEntry, LauncherState};
 use crate::window_utils::show_main_window;
 
 const TRAY_ID: &str = "main";
 const MENU_SHOW: &str = "tray_show";
 const MENU_EXIT: &str = "tray_exit";
+/// App items are `tray_app:<section>:<app id>`; the section keeps ids unique when an
+/// app shows up both under Pinned/Recent and in its group.
+const MENU_APP_PREFIX: &str = "tray_app:";
+const MENU_PROFILE_PREFIX: &str = "tray_profile:";
+const RECENT_LIMIT: usize = 8;
+/// Quiet time after the last save or launch before the menu is rebuilt.
+const REFRESH_DELAY: Duration = Duration::from_millis(200);
+
+pub const TRAY_LAUNCH_FAILED_EVENT: &str = "tray-launch-failed";
 
 #[allow(dead_code)]
 pub struct TrayState(pub tauri::tray::TrayIcon<Wry>);
 
+/// Tray icons by app id, loaded once per menu build.
+type Icons = HashMap<String, Image<'static>>;
+
+fn app_item(
+    app: &AppHandle,
+    icons: &Icons,
+    section: &str,
+    entry: &AppEntry,
+) -> tauri::Result<IconMenuItem<Wry>> {
+    let id = format!("{}{}:{}", MENU_APP_PREFIX, section, entry.id);
+    let icon = icons.get(&entry.id).cloned();
+    IconMenuItem::with_id(app, id, &entry.name, true, icon, None::<&str>)
+}
+
+fn app_submenu(
+    app: &AppHandle,
+    icons: &Icons,
+    language: &str,
+    section: &str,
+    title: &str,
+    entries: &[&AppEntry],
+) -> tauri::Result<Submenu<Wry>> {
+    let mut builder = SubmenuBuilder::new(app, title);
+    if entries.is_empty() {
+        let empty = MenuItem::new(app, tr(language, "tray.empty"), false, None::<&str>)?;
+        builder = builder.item(&empty);
+    }
+    for entry in entries {
+        builder = builder.item(&app_item(app, icons, section, entry)?);
+    }
+    builder.build()
+}
+
+/// One checkable item per profile, the active one checked.
+fn profiles_submenu(app: &AppHandle, language: &str) -> tauri::Result<Option<Submenu<Wry>>> {
+    let list = quick_launcher_core::profiles::list();
+    if list.profiles.len() < 2 {
+        return Ok(None);
+    }
+    let mut builder = SubmenuBuilder::new(app, tr(language, "tray.profiles"));
+    for profile in &list.profiles {
+        let id = format!("{}{}", MENU_PROFILE_PREFIX, profile.id);
+        let checked = profile.id == list.active;
+        let item = CheckMenuItem::with_id(app, id, &profile.name, true, checked, None::<&str>)?;
+        builder = builder.item(&item);
+    }
+    builder.build().map(Some)
+}
+
+/// Builds the tray menu in `language`: pinned apps first, then recently launched apps,
+/// one submenu per group, the profiles, and finally the window and exit commands.
+fn build_menu(app: &AppHandle, language: &str) -> tauri::Result<Menu<Wry>> {
+    let state: Option<LauncherState> = crate::storage::read_state(app).ok().flatten();
+    let groups = state.map(|s| s.groups).unwrap_or_default();
+    let all_apps: Vec<&AppEntry> = groups.iter().flat_map(|g| g.apps.iter()).collect();
+    let ids: Vec<&str> = all_apps.iter().map(|a| a.id.as_str()).collect();
+    let icons = crate::icon::menu_icons(app, &ids);
+
+    let mut builder = MenuBuilder::new(app);
+
+    let pinned: Vec<IconMenuItem<Wry>> = all_apps
+        .iter()
+        .filter(|a| a.pinned)
+        .map(|a| app_item(app, &icons, "pinned", a))
+        .collect::<tauri::Result<_>>()?;
+    if !pinned.is_empty() {
+        let refs: Vec<&dyn IsMenuItem<Wry>> =
+            pinned.iter().map(|i| i as &dyn IsMenuItem<Wry>).collect();
+        builder = builder.items(&refs).separator();
+    }
+
+    let recent_ids = crate::storage::load_recent_app_ids(app, RECENT_LIMIT).unwrap_or_default();
+    let recent: Vec<&AppEntry> = recent_ids
+        .iter()
+        .filter_map(|id| all_apps.iter().find(|a| &a.id == id).copied())
+        .collect();
+    if !recent.is_empty() {
+        builder = builder
+            .item(&app_submenu(
+                app,
+                &icons,
+                language,
+                "recent",
+                &tr(language, "tray.recent"),
+                &recent,
+            )?)
+            .separator();
+    }
+
+    if !groups.is_empty() {
+        for group in &groups {
+            let entries: Vec<&AppEntry> = group.apps.iter().collect();
+            builder = builder.item(&app_submenu(
+                app,
+                &icons,
+                language,
+                "group",
+                &group.name,
+                &entries,
+            )?);
+        }
+        builder = builder.separator();
+    }
+
+    if let Some(profiles) = profiles_submenu(app, language)? {
+        builder = builder.item(&profiles).separator();
+    }
+
+    let show = MenuItem::with_id(
+        app,
+        MENU_SHOW,
+        tr(language, "tray.show"),
+        true,
+        None::<String>,
+    )?;
+    let exit = MenuItem::with_id(
+        app,
+        MENU_EXIT,
+        tr(language, "tray.exit"),
+        true,
+        None::<String>,
+    )?;
+    builder.items(&[&show, &exit]).build()
+}
+
+fn rebuild(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let language = crate::settings::current(app).language;
+    if let Ok(menu) = build_menu(app, &language) {
+        let _ = tray.set_menu(Some(menu));
+    }
+    let _ = tray.set_tooltip(Some(tr(&language, "tray.tooltip")));
+}
+
+fn menu_refresher() -> &'static Mutex<Sender<AppHandle>> {
+    static REFRESHER: OnceLock<Mutex<Sender<AppHandle>>> = OnceLock::new();
+    REFRESHER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<AppHandle>();
+        std::thread::spawn(move || {
+            while let Ok(mut app) = rx.recv() {
+                // A burst of saves and launches rebuilds the menu once.
+                while let Ok(next) = rx.recv_timeout(REFRESH_DELAY) {
+                    app = next;
+                }
+                rebuild(&app);
+            }
+        });
+        Mutex::new(tx)
+    })
+}
+
+/// Queues a rebuild of the tray menu and tooltip from the saved state, in the saved UI
+/// language. Called after every save and launch, so a language change applies right
+/// away; the rebuild runs on a worker thread so the caller never waits on it.
+pub fn refresh_tray_menu(app: &AppHandle) {
+    if let Ok(tx) = menu_refresher().lock() {
+        let _ = tx.send(app.clone());
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    if id == MENU_SHOW {
+        show_main_window(app);
+    } else if id == MENU_EXIT {
+        app.exit(0);
+    } else if let Some(profile_id) = id.strip_prefix(MENU_PROFILE_PREFIX) {
+        if let Err(e) = crate::profiles::switch_to(app, profile_id) {
+            let _ = app.emit(crate::profiles::PROFILE_SWITCH_FAILED_EVENT, e);
+        }
+    } else if let Some(rest) = id.strip_prefix(MENU_APP_PREFIX) {
+        let app_id = rest.split_once(':').map(|(_, id)| id).unwrap_or(rest);
+        if let Err(e) = crate::launch::launch_app_by_id(app, app_id) {
+            let _ = app.emit(TRAY_LAUNCH_FAILED_EVENT, e);
+        }
+    }
+}
+
 pub fn setup_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
-    let show = MenuItem::with_id(app, MENU_SHOW, "显示主窗口", true, None::<String>)?;
-    let exit = MenuItem::with_id(app, MENU_EXIT, "退出", true, None::<String>)?;
-    let menu = MenuBuilder::new(app).items(&[&show, &exit]).build()?;
+    let language = crate::settings::current(app).language;
+    let menu = build_menu(app, &language)?;
 
     let mut builder = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
-        .tooltip("Quick-Launcher")
+        .tooltip(tr(&language, "tray.tooltip"))
         .show_menu_on_left_click(false);
     if let Some(icon) = app.default_window_icon() {
         builder = builder.icon(icon.clone());
     }
 
     let tray = builder
-        .on_menu_event(|app: &AppHandle<Wry>, event: MenuEvent| {
-            if event.id() == MENU_SHOW {
-                show_main_window(app);
-            } else if event.id() == MENU_EXIT {
-                app.exit(0);
-            }
-        })
+        .on_menu_event(|app: &AppHandle<Wry>, event: MenuEvent| handle_menu_event(app, event))
         .on_tray_icon_event(|tray: &TrayIcon<Wry>, event: TrayIconEvent| {
             if let TrayIconEvent::Click {
                 button: MouseButton::Left,
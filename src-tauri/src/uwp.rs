@@ -124,24 +124,6 @@ pub fn list_uwp_apps() -> Result<Vec<UwpAppInfo>, String> {
     }
 }
 
-#[tauri::command]
 pub fn spawn_uwp_app(app_id: String) -> Result<(), String> {
-    let aumid = app_id.trim();
-    if aumid.is_empty() {
-        return Err("empty app id".to_string());
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        Err("UWP is only supported on Windows".to_string())
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer.exe")
-            .arg(format!("shell:AppsFolder\\{}", aumid))
-            .spawn()
-            .map(|_| ())
-            .map_err(|e| e.to_string())
-    }
+    quick_launcher_core::launch::spawn_uwp_app(&app_id)
 }
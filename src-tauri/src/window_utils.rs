@@ -1,7 +1,32 @@
-use tauri::{AppHandle, Manager};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use tauri::{
+    AppHandle, Manager, Monitor, PhysicalPosition, PhysicalRect, PhysicalSize, WebviewWindow,
+};
+
+use crate::storage::WindowGeometry;
+
+/// Quiet time after the last move/resize before the geometry is written.
+const GEOMETRY_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// How long the window may stay unfocused before `hideOnBlur` hides it. Covers focus
+/// briefly passing to a child dialog or the taskbar.
+const BLUR_HIDE_GRACE: Duration = Duration::from_millis(300);
+
+/// Bumped on every focus change so a pending blur-hide can tell it went stale.
+static FOCUS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Gap between the cursor and the window's top edge for the `cursor` position, in logical px.
+const CURSOR_OFFSET: f64 = 12.0;
 
 pub fn show_main_window(app: &AppHandle) {
     if let Some(w) = app.get_webview_window("main") {
+        if !w.is_visible().unwrap_or(false) {
+            place_for_show(app, &w);
+        }
         let _ = w.unminimize();
         let _ = w.show();
         let _ = w.set_focus();
@@ -22,7 +47,242 @@ pub fn toggle_main_window(app: &AppHandle) {
             let _ = w.hide();
             return;
         }
+        place_for_show(app, &w);
         let _ = w.show();
         let _ = w.set_focus();
     }
 }
+
+/// The monitor the user is working on, taken as the one under the mouse cursor.
+fn active_monitor(app: &AppHandle) -> Option<Monitor> {
+    let cursor = app.cursor_position().ok()?;
+    app.monitor_from_point(cursor.x, cursor.y)
+        .ok()
+        .flatten()
+        .or_else(|| app.primary_monitor().ok().flatten())
+}
+
+/// Moves the hidden window according to the `showPosition` setting before it is shown.
+/// Sizes are converted through each monitor's scale factor, so a window keeps its
+/// logical size when it jumps to a monitor with different DPI.
+fn place_for_show(app: &AppHandle, w: &WebviewWindow) {
+    let mode = crate::settings::current(app).show_position;
+    if mode == "last" || w.is_maximized().unwrap_or(false) {
+        return;
+    }
+    let Some(monitor) = active_monitor(app) else {
+        return;
+    };
+    let Ok(size) = w.outer_size() else {
+        return;
+    };
+    let current_scale = w.scale_factor().unwrap_or(1.0);
+    let ratio = monitor.scale_factor() / current_scale;
+    let size = PhysicalSize::new(
+        (f64::from(size.width) * ratio).round() as u32,
+        (f64::from(size.height) * ratio).round() as u32,
+    );
+    let area = monitor.work_area();
+    let centered_x = area.position.x + (area.size.width as i32 - size.width as i32) / 2;
+    let position = match mode.as_str() {
+        "center" => PhysicalPosition::new(
+            centered_x,
+            area.position.y + (area.size.height as i32 - size.height as i32) / 2,
+        ),
+        "cursor" => {
+            let Ok(cursor) = app.cursor_position() else {
+                return;
+            };
+            let offset = (CURSOR_OFFSET * monitor.scale_factor()).round() as i32;
+            PhysicalPosition::new(
+                cursor.x.round() as i32 - size.width as i32 / 2,
+                cursor.y.round() as i32 + offset,
+            )
+        }
+        // Docked to the top edge of the work area, like a drop-down terminal.
+        "edge" => PhysicalPosition::new(centered_x, area.position.y),
+        _ => return,
+    };
+    let bounds = clamp_into(PhysicalRect { position, size }, area);
+    let _ = w.set_position(bounds.position);
+    if bounds.size != size || ratio != 1.0 {
+        let _ = w.set_size(bounds.size);
+    }
+}
+
+/// Moves `bounds` so it lies inside `area`, shrinking it first if it is larger.
+pub(crate) fn clamp_into(
+    bounds: PhysicalRect<i32, u32>,
+    area: &PhysicalRect<i32, u32>,
+) -> PhysicalRect<i32, u32> {
+    let width = bounds.size.width.min(area.size.width);
+    let height = bounds.size.height.min(area.size.height);
+    let max_x = area.position.x + (area.size.width - width) as i32;
+    let max_y = area.position.y + (area.size.height - height) as i32;
+    PhysicalRect {
+        position: PhysicalPosition::new(
+            bounds.position.x.clamp(area.position.x, max_x),
+            bounds.position.y.clamp(area.position.y, max_y),
+        ),
+        size: PhysicalSize::new(width, height),
+    }
+}
+
+fn contains_point(area: &PhysicalRect<i32, u32>, x: i32, y: i32) -> bool {
+    x >= area.position.x
+        && y >= area.position.y
+        && x < area.position.x + area.size.width as i32
+        && y < area.position.y + area.size.height as i32
+}
+
+fn capture_geometry(app: &AppHandle) -> Option<WindowGeometry> {
+    let w = app.get_webview_window("main")?;
+    // A minimized window reports a parking position far off-screen.
+    if w.is_minimized().unwrap_or(false) || !w.is_visible().unwrap_or(false) {
+        return None;
+    }
+    let monitor = w
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+    if w.is_maximized().unwrap_or(false) {
+        // Keep the bounds to restore to when the window is un-maximized.
+        let previous = crate::storage::load_window_geometry(app)?;
+        return Some(WindowGeometry {
+            maximized: true,
+            monitor,
+            ..previous
+        });
+    }
+    let position = w.outer_position().ok()?;
+    let size = w.inner_size().ok()?;
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: false,
+        monitor,
+    })
+}
+
+fn geometry_saver() -> &'static Mutex<Sender<AppHandle>> {
+    static SAVER: OnceLock<Mutex<Sender<AppHandle>>> = OnceLock::new();
+    SAVER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<AppHandle>();
+        std::thread::spawn(move || {
+            while let Ok(mut app) = rx.recv() {
+                // Wait until the moves stop for a while, then save once.
+                while let Ok(next) = rx.recv_timeout(GEOMETRY_SAVE_DELAY) {
+                    app = next;
+                }
+                if let Some(geometry) = capture_geometry(&app) {
+                    let _ = crate::storage::save_window_geometry(&app, &geometry);
+                }
+            }
+        });
+        Mutex::new(tx)
+    })
+}
+
+/// Queues a debounced save of the main window's geometry. Called on move and resize.
+pub fn schedule_geometry_save(app: &AppHandle) {
+    if let Ok(tx) = geometry_saver().lock() {
+        let _ = tx.send(app.clone());
+    }
+}
+
+/// Puts the main window back where it was last time. When the saved monitor is gone,
+/// the window lands on the monitor under its old center, or the primary one, and is
+/// clamped into that monitor's work area.
+pub fn restore_window_geometry(app: &AppHandle) {
+    let Some(w) = app.get_webview_window("main") else {
+        return;
+    };
+    let Some(geometry) = crate::storage::load_window_geometry(app) else {
+        return;
+    };
+    let monitors = w.available_monitors().unwrap_or_default();
+    let center_x = geometry.x + (geometry.width / 2) as i32;
+    let center_y = geometry.y + (geometry.height / 2) as i32;
+    let target = geometry
+        .monitor
+        .as_ref()
+        .and_then(|name| monitors.iter().find(|m| m.name() == Some(name)))
+        .or_else(|| {
+            monitors
+                .iter()
+                .find(|m| contains_point(m.work_area(), center_x, center_y))
+        })
+        .cloned()
+        .or_else(|| w.primary_monitor().ok().flatten())
+        .or_else(|| monitors.first().cloned());
+
+    let saved = PhysicalRect {
+        position: PhysicalPosition::new(geometry.x, geometry.y),
+        size: PhysicalSize::new(geometry.width, geometry.height),
+    };
+    let bounds = match &target {
+        Some(m) => clamp_into(saved, m.work_area()),
+        None => saved,
+    };
+    let _ = w.set_size(bounds.size);
+    let _ = w.set_position(bounds.position);
+    if geometry.maximized {
+        let _ = w.maximize();
+    }
+}
+
+/// Whether the foreground window belongs to this process, e.g. an open file dialog.
+#[cfg(target_os = "windows")]
+fn foreground_is_own_window() -> bool {
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.is_invalid() {
+        return false;
+    }
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    pid == std::process::id()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn foreground_is_own_window() -> bool {
+    false
+}
+
+/// Handles `WindowEvent::Focused` for the main window. Losing focus hides the window
+/// after [`BLUR_HIDE_GRACE`] when `hideOnBlur` is on, unless focus came back or moved
+/// to one of our own dialogs in the meantime.
+pub fn on_main_focus_changed(app: &AppHandle, focused: bool) {
+    let generation = FOCUS_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    if focused {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(BLUR_HIDE_GRACE);
+        if FOCUS_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if !crate::settings::current(&app).hide_on_blur || foreground_is_own_window() {
+            return;
+        }
+        if let Some(w) = app.get_webview_window("main") {
+            if !w.is_focused().unwrap_or(false) {
+                let _ = w.hide();
+            }
+        }
+    });
+}
+
+/// Hides the main window after a successful launch when `hideAfterLaunch` is on.
+pub fn hide_after_launch(app: &AppHandle) {
+    if !crate::settings::current(app).hide_after_launch {
+        return;
+    }
+    if let Some(w) = app.get_webview_window("main") {
+        let _ = w.hide();
+    }
+}
@@ -20,6 +20,7 @@ export function createAddAppFlow(opts: {
   scheduleSave: () => void;
   onStructureChanged?: () => void;
   transformPaths?: (paths: string[]) => Promise<string[]>;
+  resolveEntries?: (entries: AppEntry[]) => Promise<void>;
 }) {
   const open = ref(false);
 
@@ -37,6 +38,7 @@ export function createAddAppFlow(opts: {
     const normalized = opts.transformPaths ? await opts.transformPaths(paths) : paths;
     const added = addAppsToGroup(group, normalized);
     if (added.length > 0) {
+      await opts.resolveEntries?.(added);
       opts.showToast(t("toast.addedItems", { count: added.length }));
       opts.hydrateEntryIcons(added);
       opts.onStructureChanged?.();
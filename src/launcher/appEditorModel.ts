@@ -1,5 +1,5 @@
 import { reactive } from "vue";
-import type { AppEntry, Group } from "./types";
+import type { AppEntry, AppEntryKind, Group } from "./types";
 
 export type EditorState = {
   open: boolean;
@@ -7,6 +7,9 @@ export type EditorState = {
   name: string;
   path: string;
   args: string;
+  // Empty for "work it out from the path"
+  kind: AppEntryKind | "";
+  browser: string;
 };
 
 export function createAppEditorModel(opts: {
@@ -21,6 +24,8 @@ export function createAppEditorModel(opts: {
     name: "",
     path: "",
     args: "",
+    kind: "",
+    browser: "",
   });
 
   function openEditor(entry: AppEntry): void {
@@ -29,6 +34,8 @@ export function createAppEditorModel(opts: {
     editor.name = entry.name;
     editor.path = entry.path;
     editor.args = entry.args ?? "";
+    editor.kind = entry.kind ?? "";
+    editor.browser = entry.browser ?? "";
   }
 
   function closeEditor(): void {
@@ -44,9 +51,14 @@ export function createAppEditorModel(opts: {
     if (!entry) return;
     entry.name = editor.name.trim() || entry.name;
     const nextPath = editor.path.trim() || entry.path;
+    // A new path with the kind left as it was gets its kind inferred again
+    const keptKind = (editor.kind || undefined) === entry.kind;
+    entry.kind = nextPath !== entry.path && keptKind ? undefined : editor.kind || undefined;
+    entry.browser = editor.browser.trim() || undefined;
     if (nextPath !== entry.path) {
       entry.path = nextPath;
       entry.icon = undefined;
+      entry.iconHash = undefined;
       opts.hydrateEntryIcons([entry]);
     } else {
       entry.path = nextPath;
@@ -61,10 +73,14 @@ export function createAppEditorModel(opts: {
     name: string;
     path: string;
     args: string;
+    kind: AppEntryKind | "";
+    browser: string;
   }): void {
     editor.name = payload.name;
     editor.path = payload.path;
     editor.args = payload.args;
+    editor.kind = payload.kind;
+    editor.browser = payload.browser;
     saveEditor();
   }
 
@@ -0,0 +1,35 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { t } from "./i18n";
+
+type HotkeyFailure = { accelerator: string; error: string };
+
+function reportFailures(failures: HotkeyFailure[], showToast: (message: string) => void): void {
+  for (const f of failures) {
+    showToast(t("error.hotkeyStartupFailed", { hotkey: f.accelerator, error: f.error }));
+  }
+}
+
+export async function installHotkeyEventListeners(opts: {
+  focusSearch: () => void;
+  openGroup: (groupId: string) => void;
+  showToast: (message: string) => void;
+}): Promise<UnlistenFn[]> {
+  const unlisten = await Promise.all([
+    listen<HotkeyFailure[]>("hotkey-registration-failed", (ev) =>
+      reportFailures(ev.payload, opts.showToast),
+    ),
+    listen("hotkey-focus-search", () => opts.focusSearch()),
+    listen<string>("hotkey-open-group", (ev) => opts.openGroup(ev.payload)),
+    listen<string>("hotkey-error", (ev) =>
+      opts.showToast(t("error.hotkeyActionFailed", { error: ev.payload || t("error.unknown") })),
+    ),
+  ]);
+  // Startup registration may have failed before these listeners existed
+  try {
+    reportFailures((await invoke("take_hotkey_failures")) as HotkeyFailure[], opts.showToast);
+  } catch {
+    // ignore
+  }
+  return unlisten;
+}
@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+import { t } from "./i18n";
+
+export type HotkeyValidation = {
+  input: string;
+  normalized: string | null;
+  valid: boolean;
+  registrable: boolean;
+  boundTo: { type: string } | null;
+  error: string | null;
+};
+
+export type HotkeyMode = "press" | "doubleTap" | "hold";
+
+export async function validateHotkey(
+  value: string,
+  mode: HotkeyMode = "press",
+): Promise<HotkeyValidation | null> {
+  if (!value.trim()) return null;
+  try {
+    return (await invoke("validate_hotkey", { hotkey: value, mode })) as HotkeyValidation;
+  } catch {
+    return null;
+  }
+}
+
+export function describeHotkeyValidation(v: HotkeyValidation): string {
+  if (!v.valid) return t("settings.hotkeyStatus.invalid", { error: v.error ?? t("error.unknown") });
+  if (v.boundTo) return t("settings.hotkeyStatus.bound", { hotkey: v.normalized ?? v.input });
+  if (!v.registrable) return t("settings.hotkeyStatus.inUse", { error: v.error ?? t("error.unknown") });
+  return t("settings.hotkeyStatus.available", { hotkey: v.normalized ?? v.input });
+}
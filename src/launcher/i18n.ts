@@ -1,5 +1,7 @@
 import { ref } from "vue";
 import type { UiLanguage } from "./types";
+// Strings the Rust side also shows (tray menu); keep them in the shared file
+import nativeStrings from "./locales/native.json";
 
 export function normalizeUiLanguage(value: unknown): UiLanguage {
   if (typeof value !== "string") return "en";
@@ -20,6 +22,7 @@ export const uiLanguage = ref<UiLanguage>(guessSystemLanguage());
 type Dictionary = Record<string, string>;
 
 const en: Dictionary = {
+  ...nativeStrings.en,
   "app.title": "Quick-Launcher",
 
   "topbar.searchPlaceholder": "Search...",
@@ -31,9 +34,12 @@ const en: Dictionary = {
 
   "menu.addApp": "Add App",
   "menu.addUwpApp": "Add UWP App",
+  "menu.importFolder": "Import Apps...",
   "menu.addGroup": "Add Group",
   "menu.open": "Open",
   "menu.openFolder": "Open Folder",
+  "menu.pin": "Pin to Tray",
+  "menu.unpin": "Unpin from Tray",
   "menu.edit": "Edit",
   "menu.remove": "Remove",
   "menu.rename": "Rename",
@@ -51,6 +57,17 @@ const en: Dictionary = {
   "editor.path": "Path",
   "editor.args": "Args",
   "editor.argsPlaceholder": "--flag \"value with spaces\"",
+  "editor.kind": "Type",
+  "editor.kindAuto": "Automatic",
+  "editor.kind.executable": "Program",
+  "editor.kind.folder": "Folder",
+  "editor.kind.document": "Document",
+  "editor.kind.url": "Web link",
+  "editor.kind.uwp": "Store app",
+  "editor.kind.shell": "Shell folder",
+  "editor.kind.command": "Command line",
+  "editor.browser": "Browser",
+  "editor.browserPlaceholder": "Default browser",
   "common.cancel": "Cancel",
   "common.save": "Save",
   "common.close": "Close",
@@ -63,6 +80,7 @@ const en: Dictionary = {
   "settings.tabs.layout": "Layout",
   "settings.tabs.behavior": "Behavior",
   "settings.tabs.hotkey": "Hotkey",
+  "settings.tabs.profiles": "Profiles",
   "settings.language": "Language",
   "settings.theme": "Theme",
   "settings.theme.dark": "Dark",
@@ -77,27 +95,89 @@ const en: Dictionary = {
   "settings.behavior.dblClickBlankToHide": "Double click blank area to hide window",
   "settings.behavior.alwaysOnTop": "Always on top",
   "settings.behavior.hideOnStartup": "Hide window on startup",
+  "settings.behavior.hideOnBlur": "Hide window when it loses focus",
+  "settings.behavior.hideAfterLaunch": "Hide window after launching an app",
+  "settings.behavior.showPosition": "Show window at",
+  "settings.showPosition.last": "Last position",
+  "settings.showPosition.center": "Center of active monitor",
+  "settings.showPosition.cursor": "Mouse cursor",
+  "settings.showPosition.edge": "Top edge of active monitor",
   "settings.behavior.useRelativePath": "Use relative paths when adding apps",
   "settings.behavior.enableGroupDragSort": "Enable drag sorting for groups",
   "settings.behavior.autoStart": "Launch at system startup",
+  "settings.data.location": "Data folder",
+  "settings.data.source.flag": "Set with --data-dir",
+  "settings.data.source.env": "Set with QUICK_LAUNCHER_DATA_DIR",
+  "settings.data.source.portable": "Portable mode (portable.flag next to the program)",
+  "settings.data.source.exeDir": "Next to the program",
+  "settings.data.source.appData": "User app data folder",
+  "settings.data.moveToPortable": "Move next to the program",
+  "settings.data.moveToAppData": "Move to app data folder",
+  "settings.sync.folder": "Sync folder",
+  "settings.sync.off": "Off",
+  "settings.sync.hint": "Groups and apps are mirrored to a JSON file there and merged with edits from other machines",
+  "settings.sync.choose": "Choose folder",
+  "settings.sync.now": "Sync now",
+  "settings.sync.turnOff": "Turn off",
+  "settings.profiles.active": "Active profile",
+  "settings.profiles.hint": "Each profile has its own groups, settings and hotkeys",
+  "settings.profiles.newName": "New profile",
+  "settings.profiles.newNamePlaceholder": "e.g. Work",
+  "settings.profiles.create": "Create empty",
+  "settings.profiles.duplicate": "Duplicate current",
+  "settings.profiles.created": "Created profile {name}",
+  "settings.data.moveConfirm": "Move the data and restart the launcher?",
   "settings.toggleHotkey": "Toggle hotkey",
   "settings.toggleHotkeyPlaceholder": "e.g. ctrl+alt+space",
   "settings.toggleHotkeyHintPrefix": "Example:",
+  "settings.hotkeyStatus.available": "Available as {hotkey}",
+  "settings.hotkeyStatus.bound": "{hotkey} is already bound in Quick-Launcher",
+  "settings.hotkeyStatus.inUse": "Taken by another program: {error}",
+  "settings.hotkeyStatus.invalid": "Invalid hotkey: {error}",
   "settings.applyHotkey": "Apply Hotkey",
 
   "addUwp.title": "Add UWP App",
   "addUwp.searchPlaceholder": "Search UWP apps...",
   "addUwp.loading": "Loading...",
   "addUwp.noResults": "No results",
+  "importFolder.title": "Import Apps",
+  "importFolder.root.startMenu": "Start Menu",
+  "importFolder.root.desktop": "Desktop",
+  "importFolder.chooseFolder": "Choose Folder...",
+  "importFolder.chooseFile": "Other Launcher or Bookmarks...",
+  "importFolder.fileFilter": "Launcher settings and bookmarks",
+  "importFolder.singleGroup": "Put everything in one group",
+  "importFolder.skipUninstallers": "Skip uninstallers",
+  "importFolder.scanning": "Scanning...",
+  "importFolder.duplicates": "{count} already added",
+  "importFolder.existingGroup": "Existing group",
+  "importFolder.newGroup": "New group",
+  "importFolder.nothingFound": "No new apps found",
+  "importFolder.accept": "Add {count} app(s)",
   "error.tauriRuntimeRequired": "This action requires the Tauri runtime",
 
   "toast.addedItems": "Added {count} item(s)",
   "toast.movedToGroup": "Moved to {group}",
   "toast.hotkeyUpdated": "Hotkey updated",
+  "toast.stateReloaded": "Reloaded changes made elsewhere",
+  "toast.synced": "Synced",
+  "toast.undone": "Undone",
+  "toast.redone": "Redone",
 
   "error.saveFailed": "Save failed: {error}",
   "error.loadFailed": "Load failed: {error}",
   "error.hotkeyFailed": "Hotkey failed: {error}",
+  "error.hotkeyActionFailed": "Hotkey action failed: {error}",
+  "error.hotkeyStartupFailed": "Hotkey {hotkey} could not be registered: {error}",
+  "error.settingsApplyFailed": "Some settings could not be applied: {error}",
+  "error.instanceCommandFailed": "Command-line request failed: {error}",
+  "error.groupNotFound": "Group not found: {name}",
+  "error.moveDataFailed": "Failed to move data: {error}",
+  "error.profileFailed": "Profile action failed: {error}",
+  "error.staleState": "The data was changed elsewhere; your last change was not saved",
+  "error.syncFailed": "Sync failed: {error}",
+  "error.undoFailed": "Undo failed: {error}",
+  "error.importFailed": "Import failed: {error}",
   "error.openFailed": "Failed to open: {error}",
   "error.openFolderFailed": "Open folder failed: {error}",
   "error.unknown": "unknown error",
@@ -117,6 +197,7 @@ const en: Dictionary = {
 };
 
 const zhCN: Dictionary = {
+  ...nativeStrings["zh-CN"],
   "app.title": "快速启动器",
 
   "topbar.searchPlaceholder": "搜索...",
@@ -128,9 +209,12 @@ const zhCN: Dictionary = {
 
   "menu.addApp": "添加应用",
   "menu.addUwpApp": "添加 UWP 应用",
+  "menu.importFolder": "导入应用...",
   "menu.addGroup": "添加分组",
   "menu.open": "打开",
   "menu.openFolder": "打开文件夹",
+  "menu.pin": "固定到托盘",
+  "menu.unpin": "从托盘取消固定",
   "menu.edit": "编辑",
   "menu.remove": "移除",
   "menu.rename": "重命名",
@@ -148,6 +232,17 @@ const zhCN: Dictionary = {
   "editor.path": "路径",
   "editor.args": "参数",
   "editor.argsPlaceholder": "--flag \"value with spaces\"",
+  "editor.kind": "类型",
+  "editor.kindAuto": "自动",
+  "editor.kind.executable": "程序",
+  "editor.kind.folder": "文件夹",
+  "editor.kind.document": "文档",
+  "editor.kind.url": "网页链接",
+  "editor.kind.uwp": "应用商店应用",
+  "editor.kind.shell": "系统文件夹",
+  "editor.kind.command": "命令行",
+  "editor.browser": "浏览器",
+  "editor.browserPlaceholder": "默认浏览器",
   "common.cancel": "取消",
   "common.save": "保存",
   "common.close": "关闭",
@@ -160,6 +255,7 @@ const zhCN: Dictionary = {
   "settings.tabs.layout": "布局",
   "settings.tabs.behavior": "行为",
   "settings.tabs.hotkey": "快捷键",
+  "settings.tabs.profiles": "配置",
   "settings.language": "界面语言",
   "settings.theme": "主题",
   "settings.theme.dark": "深色",
@@ -174,27 +270,89 @@ const zhCN: Dictionary = {
   "settings.behavior.dblClickBlankToHide": "双击空白区域隐藏窗口",
   "settings.behavior.alwaysOnTop": "窗口置顶",
   "settings.behavior.hideOnStartup": "启动时隐藏窗口",
+  "settings.behavior.hideOnBlur": "窗口失去焦点时隐藏",
+  "settings.behavior.hideAfterLaunch": "启动应用后隐藏窗口",
+  "settings.behavior.showPosition": "窗口显示位置",
+  "settings.showPosition.last": "上次位置",
+  "settings.showPosition.center": "当前显示器中央",
+  "settings.showPosition.cursor": "鼠标位置",
+  "settings.showPosition.edge": "当前显示器顶部边缘",
   "settings.behavior.useRelativePath": "添加应用时使用相对路径",
   "settings.behavior.enableGroupDragSort": "允许拖拽排序左侧分组",
   "settings.behavior.autoStart": "开机自启动",
+  "settings.data.location": "数据目录",
+  "settings.data.source.flag": "由 --data-dir 指定",
+  "settings.data.source.env": "由 QUICK_LAUNCHER_DATA_DIR 指定",
+  "settings.data.source.portable": "便携模式（程序旁有 portable.flag）",
+  "settings.data.source.exeDir": "程序所在目录",
+  "settings.data.source.appData": "用户应用数据目录",
+  "settings.data.moveToPortable": "移动到程序目录",
+  "settings.data.moveToAppData": "移动到应用数据目录",
+  "settings.sync.folder": "同步文件夹",
+  "settings.sync.off": "未开启",
+  "settings.sync.hint": "分组和应用会镜像到该文件夹中的 JSON 文件，并与其他设备的修改合并",
+  "settings.sync.choose": "选择文件夹",
+  "settings.sync.now": "立即同步",
+  "settings.sync.turnOff": "关闭",
+  "settings.profiles.active": "当前配置",
+  "settings.profiles.hint": "每个配置有独立的分组、设置和快捷键",
+  "settings.profiles.newName": "新配置",
+  "settings.profiles.newNamePlaceholder": "例如：工作",
+  "settings.profiles.create": "新建空配置",
+  "settings.profiles.duplicate": "复制当前配置",
+  "settings.profiles.created": "已创建配置 {name}",
+  "settings.data.moveConfirm": "移动数据并重启启动器？",
   "settings.toggleHotkey": "呼出快捷键",
   "settings.toggleHotkeyPlaceholder": "例如 ctrl+alt+space",
   "settings.toggleHotkeyHintPrefix": "示例：",
+  "settings.hotkeyStatus.available": "可用：{hotkey}",
+  "settings.hotkeyStatus.bound": "{hotkey} 已在 Quick-Launcher 中绑定",
+  "settings.hotkeyStatus.inUse": "已被其他程序占用：{error}",
+  "settings.hotkeyStatus.invalid": "快捷键无效：{error}",
   "settings.applyHotkey": "应用快捷键",
 
   "addUwp.title": "添加 UWP 应用",
   "addUwp.searchPlaceholder": "搜索 UWP 应用...",
   "addUwp.loading": "加载中...",
   "addUwp.noResults": "无结果",
+  "importFolder.title": "导入应用",
+  "importFolder.root.startMenu": "开始菜单",
+  "importFolder.root.desktop": "桌面",
+  "importFolder.chooseFolder": "选择文件夹...",
+  "importFolder.chooseFile": "其他启动器或书签...",
+  "importFolder.fileFilter": "启动器配置和书签",
+  "importFolder.singleGroup": "全部放入一个分组",
+  "importFolder.skipUninstallers": "跳过卸载程序",
+  "importFolder.scanning": "正在扫描...",
+  "importFolder.duplicates": "{count} 项已存在",
+  "importFolder.existingGroup": "现有分组",
+  "importFolder.newGroup": "新分组",
+  "importFolder.nothingFound": "没有找到新应用",
+  "importFolder.accept": "添加 {count} 个应用",
   "error.tauriRuntimeRequired": "该操作需要 Tauri 运行时",
 
   "toast.addedItems": "已添加 {count} 个项目",
   "toast.movedToGroup": "已移动到 {group}",
   "toast.hotkeyUpdated": "快捷键已更新",
+  "toast.stateReloaded": "已载入其他地方所做的更改",
+  "toast.synced": "已同步",
+  "toast.undone": "已撤销",
+  "toast.redone": "已重做",
 
   "error.saveFailed": "保存失败：{error}",
   "error.loadFailed": "加载失败：{error}",
   "error.hotkeyFailed": "快捷键设置失败：{error}",
+  "error.hotkeyActionFailed": "快捷键操作失败：{error}",
+  "error.hotkeyStartupFailed": "快捷键 {hotkey} 注册失败：{error}",
+  "error.settingsApplyFailed": "部分设置未能生效：{error}",
+  "error.instanceCommandFailed": "命令行请求失败：{error}",
+  "error.groupNotFound": "未找到分组：{name}",
+  "error.moveDataFailed": "移动数据失败：{error}",
+  "error.profileFailed": "配置操作失败：{error}",
+  "error.staleState": "数据已在其他地方被修改，最近的更改未保存",
+  "error.syncFailed": "同步失败：{error}",
+  "error.undoFailed": "撤销失败：{error}",
+  "error.importFailed": "导入失败：{error}",
   "error.openFailed": "打开失败：{error}",
   "error.openFolderFailed": "打开文件夹失败：{error}",
   "error.unknown": "未知错误",
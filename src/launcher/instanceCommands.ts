@@ -0,0 +1,52 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { t } from "./i18n";
+
+export type InstanceCommand =
+  | { type: "launch"; target: string }
+  | { type: "add"; paths: string[]; group: string | null }
+  | { type: "search"; query: string }
+  | { type: "showGroup"; name: string };
+
+export async function installInstanceCommandListeners(opts: {
+  addPaths: (paths: string[], groupName: string | null) => Promise<void>;
+  search: (query: string) => void;
+  showGroup: (name: string) => boolean;
+  showToast: (message: string) => void;
+}): Promise<UnlistenFn[]> {
+  const run = async (command: InstanceCommand): Promise<void> => {
+    switch (command.type) {
+      case "add":
+        await opts.addPaths(command.paths, command.group);
+        break;
+      case "search":
+        opts.search(command.query);
+        break;
+      case "showGroup":
+        if (!opts.showGroup(command.name)) {
+          opts.showToast(t("error.groupNotFound", { name: command.name }));
+        }
+        break;
+    }
+  };
+  const fail = (error: string) =>
+    opts.showToast(t("error.instanceCommandFailed", { error: error || t("error.unknown") }));
+  const unlisten = await Promise.all([
+    listen<InstanceCommand>("instance-command", (ev) => void run(ev.payload)),
+    listen<string>("instance-command-failed", (ev) => fail(ev.payload)),
+  ]);
+  // Commands this process was started with arrive before the listeners exist
+  try {
+    const pending = (await invoke("take_instance_commands")) as {
+      commands: InstanceCommand[];
+      errors: string[];
+    };
+    pending.errors.forEach(fail);
+    for (const command of pending.commands) {
+      await run(command);
+    }
+  } catch {
+    // ignore
+  }
+  return unlisten;
+}
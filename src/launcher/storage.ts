@@ -1,6 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import type { AppEntry, Group, LauncherState } from "./types";
-import { createDefaultState, createId, suggestAppName } from "./utils";
+import { APP_ENTRY_KINDS, createDefaultState, createId, suggestAppName } from "./utils";
 import { normalizeUiLanguage } from "./i18n";
 import {
   clampCardHeight,
@@ -8,23 +8,29 @@ import {
   clampCardWidth,
   clampCardFontSize,
   clampFontSize,
+  clampGestureMs,
   clampSidebarWidth,
+  normalizeShowPosition,
   normalizeTheme,
 } from "./uiSettings";
 
 const LEGACY_STORAGE_KEY = "launcher_state_v1";
 
+/** Prefix of the save error for a state someone else has changed since it was read. */
+export const STALE_STATE_ERROR = "stale-state";
+
 function isTauriRuntime(): boolean {
   return typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
 }
 
-function coerceLauncherState(value: unknown): LauncherState | null {
+export function coerceLauncherState(value: unknown): LauncherState | null {
   if (!value || typeof value !== "object") return null;
   const raw = value as {
     version?: unknown;
     activeGroupId?: unknown;
     groups?: unknown;
     settings?: unknown;
+    revision?: unknown;
   };
   if (raw.version !== 1) return null;
 
@@ -51,7 +57,14 @@ function coerceLauncherState(value: unknown): LauncherState | null {
             path?: unknown;
             args?: unknown;
             icon?: unknown;
+            iconHash?: unknown;
+            pinned?: unknown;
             addedAt?: unknown;
+            kind?: unknown;
+            browser?: unknown;
+            workingDir?: unknown;
+            description?: unknown;
+            iconLocation?: unknown;
           };
           const path = typeof appRaw.path === "string" ? appRaw.path : "";
           if (!path.trim()) return null;
@@ -64,12 +77,39 @@ function coerceLauncherState(value: unknown): LauncherState | null {
               ? appRaw.name
               : suggestAppName(path);
           const args = typeof appRaw.args === "string" ? appRaw.args : "";
-          const icon = typeof appRaw.icon === "string" ? appRaw.icon : undefined;
+          const iconHash =
+            typeof appRaw.iconHash === "string" && appRaw.iconHash.trim()
+              ? appRaw.iconHash
+              : undefined;
+          // Stored icons are requested lazily once the card scrolls into view
+          const icon =
+            !iconHash && typeof appRaw.icon === "string" ? appRaw.icon : undefined;
           const addedAt =
             typeof appRaw.addedAt === "number" && Number.isFinite(appRaw.addedAt)
               ? appRaw.addedAt
               : Date.now();
-          return { id: appId, name: appName, path, args, icon, addedAt };
+          const pinned = appRaw.pinned === true ? true : undefined;
+          const kind = APP_ENTRY_KINDS.find((k) => k === appRaw.kind);
+          const text = (v: unknown) => (typeof v === "string" && v.trim() ? v : undefined);
+          const browser = text(appRaw.browser);
+          const workingDir = text(appRaw.workingDir);
+          const description = text(appRaw.description);
+          const iconLocation = text(appRaw.iconLocation);
+          return {
+            id: appId,
+            name: appName,
+            path,
+            args,
+            icon,
+            iconHash,
+            pinned,
+            addedAt,
+            kind,
+            browser,
+            workingDir,
+            description,
+            iconLocation,
+          };
         })
         .filter((x: AppEntry | null): x is AppEntry => x !== null);
       return { id, name, apps };
@@ -134,6 +174,22 @@ function coerceLauncherState(value: unknown): LauncherState | null {
   if (typeof rawSettings.autoStart === "boolean") {
     settings.autoStart = rawSettings.autoStart;
   }
+  if (typeof rawSettings.doubleTapMs === "number") {
+    settings.doubleTapMs = clampGestureMs(rawSettings.doubleTapMs);
+  }
+  if (typeof rawSettings.holdPeekMs === "number") {
+    settings.holdPeekMs = clampGestureMs(rawSettings.holdPeekMs);
+  }
+  settings.showPosition = normalizeShowPosition(rawSettings.showPosition);
+  if (typeof rawSettings.hideOnBlur === "boolean") {
+    settings.hideOnBlur = rawSettings.hideOnBlur;
+  }
+  if (typeof rawSettings.hideAfterLaunch === "boolean") {
+    settings.hideAfterLaunch = rawSettings.hideAfterLaunch;
+  }
+  if (typeof rawSettings.syncDir === "string") {
+    settings.syncDir = rawSettings.syncDir;
+  }
 
   const activeGroupId =
     typeof raw.activeGroupId === "string" &&
@@ -141,11 +197,17 @@ function coerceLauncherState(value: unknown): LauncherState | null {
       ? raw.activeGroupId
       : groups[0]?.id ?? createId();
 
+  const revision =
+    typeof raw.revision === "number" && Number.isInteger(raw.revision) && raw.revision >= 0
+      ? raw.revision
+      : 0;
+
   return {
     version: 1,
     activeGroupId,
     groups,
     settings,
+    revision,
   };
 }
 
@@ -167,10 +229,13 @@ export async function loadState(): Promise<LauncherState> {
   const coerced = coerceLauncherState(fromDb);
   if (coerced) return coerced;
 
+  // Nothing to load, but earlier saves may have left a revision behind
+  const revision = (await invoke("load_state_revision")) as number;
   const legacy = loadLegacyState();
   if (legacy) {
+    legacy.revision = revision;
     try {
-      await invoke("save_launcher_state", { state: legacy });
+      legacy.revision = (await invoke("save_launcher_state", { state: legacy })) as number;
       localStorage.removeItem(LEGACY_STORAGE_KEY);
     } catch {
       // keep legacy as fallback
@@ -179,9 +244,10 @@ export async function loadState(): Promise<LauncherState> {
   }
 
   const initial = createDefaultState();
+  initial.revision = revision;
   if (!fromDb) {
     try {
-      await invoke("save_launcher_state", { state: initial });
+      initial.revision = (await invoke("save_launcher_state", { state: initial })) as number;
     } catch {
       // ignore
     }
@@ -189,7 +255,8 @@ export async function loadState(): Promise<LauncherState> {
   return initial;
 }
 
-export async function saveState(state: LauncherState): Promise<void> {
-  if (!isTauriRuntime()) return;
-  await invoke("save_launcher_state", { state });
+/** Saves `state` and returns the revision it was saved as. */
+export async function saveState(state: LauncherState): Promise<number> {
+  if (!isTauriRuntime()) return state.revision;
+  return (await invoke("save_launcher_state", { state })) as number;
 }
@@ -15,6 +15,7 @@ export async function installTauriFileDropListeners(opts: {
   showToast: (message: string) => void;
   onStructureChanged?: () => void;
   transformPaths?: (paths: string[]) => Promise<string[]>;
+  resolveEntries?: (entries: AppEntry[]) => Promise<void>;
 }): Promise<UnlistenFn[]> {
   let isProcessing = false;
   const drop = async (payload: unknown) => {
@@ -36,6 +37,7 @@ export async function installTauriFileDropListeners(opts: {
       const normalized = opts.transformPaths ? await opts.transformPaths(paths) : paths;
       const added = addAppsToGroup(group, normalized, idxBase);
       if (added.length > 0) {
+        await opts.resolveEntries?.(added);
         opts.showToast(t("toast.addedItems", { count: added.length }));
         opts.onStructureChanged?.();
         await opts.hydrateEntryIcons(added);
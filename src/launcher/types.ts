@@ -1,10 +1,24 @@
+export type AppEntryKind = "executable" | "folder" | "document" | "url" | "uwp" | "shell" | "command";
+
 export type AppEntry = {
   id: string;
   name: string;
   path: string;
   args?: string;
   icon?: string;
+  iconHash?: string;
+  pinned?: boolean;
   addedAt: number;
+  // Inferred from the path by the backend when missing
+  kind?: AppEntryKind;
+  // Browser to open a URL entry with instead of the default one
+  browser?: string;
+  // Folder a program starts in, e.g. a shortcut's "Start in"
+  workingDir?: string;
+  // Card tooltip, e.g. a shortcut's comment
+  description?: string;
+  // File the icon comes from instead of the path, as `file,index`
+  iconLocation?: string;
 };
 
 export type UiLanguage = "en" | "zh-CN";
@@ -32,11 +46,60 @@ export type UiSettings = {
   useRelativePath: boolean;
   enableGroupDragSort: boolean;
   autoStart: boolean;
+  doubleTapMs: number;
+  holdPeekMs: number;
+  showPosition: ShowPosition;
+  hideOnBlur: boolean;
+  hideAfterLaunch: boolean;
+  // Folder the library is mirrored to; empty when sync is off
+  syncDir: string;
+};
+
+export type DataDirSource = "flag" | "env" | "portable" | "exeDir" | "appData";
+
+export type DataDirTarget = "portable" | "appData";
+
+export type DataLocation = {
+  dir: string;
+  source: DataDirSource;
+};
+
+export type Profile = {
+  id: string;
+  name: string;
+  createdAt: number;
+};
+
+export type ProfileList = {
+  active: string;
+  profiles: Profile[];
 };
 
+export type ScanRoot = {
+  kind: "startMenu" | "desktop";
+  path: string;
+};
+
+// Apps found in one folder; `groupId` names the existing group they would join
+export type ScanGroup = {
+  name: string;
+  groupId: string | null;
+  apps: AppEntry[];
+};
+
+export type ScanPreview = {
+  root: string;
+  groups: ScanGroup[];
+  duplicates: string[];
+};
+
+export type ShowPosition = "last" | "center" | "cursor" | "edge";
+
 export type LauncherState = {
   version: 1;
   activeGroupId: string;
   groups: Group[];
   settings: UiSettings;
+  // Database revision this state was read at; saves from an older one are rejected
+  revision: number;
 };
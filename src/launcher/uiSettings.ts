@@ -1,4 +1,4 @@
-import type { UiSettings } from "./types";
+import type { ShowPosition, UiSettings } from "./types";
 import { resolveFontFamilyCss } from "./fonts";
 import { normalizeUiLanguage } from "./i18n";
 
@@ -10,6 +10,10 @@ export function normalizeTheme(value: unknown): "dark" | "light" {
   return value === "light" ? "light" : "dark";
 }
 
+export function normalizeShowPosition(value: unknown): ShowPosition {
+  return value === "center" || value === "cursor" || value === "edge" ? value : "last";
+}
+
 export function clampCardWidth(value: number): number {
   return clamp(Math.round(value), 50, 480);
 }
@@ -26,6 +30,10 @@ export function clampFontSize(value: number): number {
   return clamp(Math.round(value), 10, 22);
 }
 
+export function clampGestureMs(value: number): number {
+  return clamp(Math.round(value), 100, 2000);
+}
+
 export function clampCardFontSize(value: number): number {
   return clamp(Math.round(value), 9, 18);
 }
@@ -80,15 +88,42 @@ export function applyLoadedUiSettings(target: UiSettings, loaded: UiSettings): v
 
   const maybeAutoStart = (loaded as any).autoStart;
   if (typeof maybeAutoStart === "boolean") target.autoStart = maybeAutoStart;
+
+  const maybeDoubleTap = (loaded as any).doubleTapMs;
+  if (typeof maybeDoubleTap === "number") target.doubleTapMs = clampGestureMs(maybeDoubleTap);
+
+  const maybeHoldPeek = (loaded as any).holdPeekMs;
+  if (typeof maybeHoldPeek === "number") target.holdPeekMs = clampGestureMs(maybeHoldPeek);
+
+  target.showPosition = normalizeShowPosition((loaded as any).showPosition);
+
+  const maybeHideOnBlur = (loaded as any).hideOnBlur;
+  if (typeof maybeHideOnBlur === "boolean") target.hideOnBlur = maybeHideOnBlur;
+
+  const maybeHideAfterLaunch = (loaded as any).hideAfterLaunch;
+  if (typeof maybeHideAfterLaunch === "boolean") target.hideAfterLaunch = maybeHideAfterLaunch;
+
+  const maybeSyncDir = (loaded as any).syncDir;
+  if (typeof maybeSyncDir === "string") target.syncDir = maybeSyncDir;
+}
+
+function cardIconSize(settings: UiSettings): number {
+  const width = clampCardWidth(settings.cardWidth);
+  const height = clampCardHeight(settings.cardHeight);
+  const iconMax = Math.min(width, height) * 0.82;
+  return clampCardIconScale(settings.cardIconScale, iconMax);
+}
+
+export function cardIconImgSize(settings: UiSettings): number {
+  return Math.max(12, Math.round(cardIconSize(settings) * 0.72));
 }
 
 export function computeAppStyle(settings: UiSettings): Record<string, string> {
   const width = clampCardWidth(settings.cardWidth);
   const height = clampCardHeight(settings.cardHeight);
 
-  const iconMax = Math.min(width, height) * 0.82;
-  const icon = clampCardIconScale(settings.cardIconScale, iconMax);
-  const iconImg = Math.max(12, Math.round(icon * 0.72));
+  const icon = cardIconSize(settings);
+  const iconImg = cardIconImgSize(settings);
 
   return {
     "--card-min-width": `${width}px`,
@@ -1,24 +1,40 @@
 import { computed, onMounted, onUnmounted, reactive, ref, shallowRef, watch } from "vue";
 import { invoke } from "@tauri-apps/api/core";
+import { ask, open as openDialog } from "@tauri-apps/plugin-dialog";
+import { listen } from "@tauri-apps/api/event";
 import { createWindowControls } from "./windowControls";
 
-import { loadState, saveState } from "./storage";
-import type { AppEntry, Group, LauncherState } from "./types";
+import { coerceLauncherState, loadState, saveState, STALE_STATE_ERROR } from "./storage";
+import type {
+  AppEntry,
+  DataDirTarget,
+  DataLocation,
+  Group,
+  LauncherState,
+  Profile,
+  ProfileList,
+  ScanPreview,
+} from "./types";
 import { createAppEditorModel } from "./appEditorModel";
-import { createAddAppFlow, isUwpPath, UWP_PREFIX } from "./addAppFlow";
+import { createAddAppFlow } from "./addAppFlow";
 import { createGroupRenameModel } from "./groupRenameModel";
 import { createExternalFileDropPreview } from "./externalFileDropPreview";
 import { createInternalCardDrag } from "./internalCardDrag";
 import { installSearchShortcuts } from "./searchShortcuts";
 import { installTauriFileDropListeners } from "./tauriFileDrop";
+import { installHotkeyEventListeners } from "./hotkeyEvents";
+import { installInstanceCommandListeners } from "./instanceCommands";
 import { normalizeUiLanguage, setUiLanguage, t } from "./i18n";
 import {
   createDefaultState,
   createId,
-  parseArgs,
+  iconUrl,
+  isUrlEntry,
+  URL_ICON,
 } from "./utils";
 import {
   applyLoadedUiSettings,
+  cardIconImgSize,
   clampCardHeight,
   clampCardIconScale,
   clampCardWidth,
@@ -26,6 +42,7 @@ import {
   clampFontSize,
   clampSidebarWidth,
   computeAppStyle,
+  normalizeShowPosition,
   normalizeTheme,
 } from "./uiSettings";
 
@@ -46,6 +63,8 @@ export function useLauncherModel() {
   const settingsOpen = ref(false);
 
   let saveTimer: number | null = null;
+  // Saves run one after another, so each one carries the revision the last one returned
+  let saving: Promise<void> = Promise.resolve();
   let saveErrorShown = false;
   let suppressGroupClickUntil = 0;
 
@@ -167,10 +186,31 @@ export function useLauncherModel() {
     state.activeGroupId = loaded.activeGroupId;
     state.groups.splice(0, state.groups.length, ...loaded.groups);
     applyLoadedUiSettings(state.settings, loaded.settings);
+    state.revision = loaded.revision;
     setUiLanguage(state.settings.language);
     rebuildSearchIndex();
   }
 
+  // Shows what is in the database now. A pending save is dropped on purpose: it was
+  // made on top of data that is no longer current.
+  function replaceState(loaded: LauncherState): void {
+    if (saveTimer) window.clearTimeout(saveTimer);
+    saveTimer = null;
+    hydrated.value = false;
+    applyLoadedState(loaded);
+    hydrated.value = true;
+    clearSelection();
+    hydrateEntryIcons(activeGroup.value?.apps ?? []);
+  }
+
+  async function reloadState(): Promise<void> {
+    try {
+      replaceState(await loadState());
+    } catch (e) {
+      showToast(t("error.loadFailed", { error: e instanceof Error ? e.message : String(e) }));
+    }
+  }
+
   function showToast(message: string): void {
     toast.value = message;
     window.setTimeout(() => {
@@ -183,36 +223,100 @@ export function useLauncherModel() {
     toggleMaximizeWindow,
     closeWindow,
     startWindowDragging,
-    setAlwaysOnTop,
   } = createWindowControls({ tauriRuntime, showToast });
 
+  function writeState(): Promise<void> {
+    saving = saving.then(async () => {
+      const plain = JSON.parse(JSON.stringify(state)) as LauncherState;
+      try {
+        state.revision = await saveState(plain);
+      } catch (e) {
+        const message = e instanceof Error ? e.message : String(e);
+        if (message.startsWith(STALE_STATE_ERROR)) {
+          await reloadState();
+          showToast(t("error.staleState"));
+          return;
+        }
+        if (saveErrorShown) return;
+        saveErrorShown = true;
+        showToast(t("error.saveFailed", { error: message }));
+      }
+    });
+    return saving;
+  }
+
   function scheduleSave(): void {
     if (!hydrated.value) return;
     if (saveTimer) window.clearTimeout(saveTimer);
     saveTimer = window.setTimeout(() => {
       saveTimer = null;
-      const plain = JSON.parse(JSON.stringify(state)) as LauncherState;
-      saveState(plain).catch((e) => {
-        if (saveErrorShown) return;
-        saveErrorShown = true;
-        showToast(t("error.saveFailed", { error: e instanceof Error ? e.message : String(e) }));
-      });
+      void writeState();
     }, 500);
   }
 
-  watch(() => state.activeGroupId, scheduleSave);
+  // Writes a pending save right away, before the database behind it changes
+  async function flushSave(): Promise<void> {
+    if (!saveTimer) return saving;
+    window.clearTimeout(saveTimer);
+    saveTimer = null;
+    await writeState();
+  }
+
+  // Synchronous, so states applied from the database aren't saved straight back
+  watch(() => state.activeGroupId, scheduleSave, { flush: "sync" });
   watch(
     () => state.settings.language,
     (lang) => setUiLanguage(normalizeUiLanguage(lang)),
     { immediate: true },
   );
 
+  function isShortcutPath(path: string): boolean {
+    return path.trim().toLowerCase().endsWith(".lnk");
+  }
+
+  // Shortcuts stay absolute here; they are made relative once resolved to their target
+  async function relativePaths(paths: string[]): Promise<string[]> {
+    if (!tauriRuntime || !state.settings.useRelativePath) return paths;
+    return Promise.all(
+      paths.map(async (p) => {
+        if (isShortcutPath(p)) return p;
+        try {
+          const v = (await invoke("make_relative_path", { path: p })) as unknown;
+          return typeof v === "string" && v.trim() ? v : p;
+        } catch {
+          return p;
+        }
+      }),
+    );
+  }
+
+  // Points added `.lnk` entries at the shortcut's target and arguments. A shortcut that
+  // can't be read, or has no file target, stays as it is.
+  async function resolveShortcutEntries(entries: AppEntry[]): Promise<void> {
+    if (!tauriRuntime) return;
+    for (const entry of entries) {
+      if (!isShortcutPath(entry.path)) continue;
+      try {
+        const resolved = (await invoke("resolve_shortcut", { path: entry.path })) as AppEntry;
+        entry.path = resolved.path;
+        entry.args = resolved.args ?? "";
+        entry.workingDir = resolved.workingDir;
+        entry.description = resolved.description;
+        entry.iconLocation = resolved.iconLocation;
+      } catch {
+        // keep the shortcut
+      }
+      [entry.path] = await relativePaths([entry.path]);
+    }
+  }
+
   const {
     addAppOpen,
     openAddApp,
     closeAddApp,
     pickAndAddDesktopApps,
     addUwpToActiveGroup,
+    addPathsToActiveGroup,
   } = createAddAppFlow({
     tauriRuntime,
     getActiveGroup: () => activeGroup.value,
@@ -222,20 +326,8 @@ export function useLauncherModel() {
     },
     scheduleSave,
     onStructureChanged: rebuildSearchIndex,
-    transformPaths: async (paths) => {
-      if (!tauriRuntime || !state.settings.useRelativePath) return paths;
-      const mapped = await Promise.all(
-        paths.map(async (p) => {
-          try {
-            const v = (await invoke("make_relative_path", { path: p })) as unknown;
-            return typeof v === "string" && v.trim() ? v : p;
-          } catch {
-            return p;
-          }
-        }),
-      );
-      return mapped;
-    },
+    transformPaths: relativePaths,
+    resolveEntries: resolveShortcutEntries,
   });
   const pickAndAddApps = pickAndAddDesktopApps;
 
@@ -329,13 +421,16 @@ export function useLauncherModel() {
       return;
     }
     try {
-      if (isUwpPath(entry.path)) {
-        const appId = entry.path.slice(UWP_PREFIX.length);
-        await invoke("spawn_uwp_app", { appId });
-      } else {
-        const argText = (entry.args ?? "").trim();
-        await invoke("spawn_app", { path: entry.path, args: parseArgs(argText) });
-      }
+      // The backend picks how to start it from the entry's kind
+      await invoke("launch_app", {
+        path: entry.path,
+        args: entry.args ?? null,
+        kind: entry.kind ?? null,
+        browser: entry.browser ?? null,
+        workingDir: entry.workingDir ?? null,
+      });
+      // Feeds the tray's Recent section
+      void invoke("record_launch", { appId: entry.id }).catch(() => {});
     } catch (e) {
       const details =
         e instanceof Error
@@ -353,41 +448,14 @@ export function useLauncherModel() {
     }
   }
 
-  const pendingIcons = new Map<string, Promise<string | null>>();
-
-  async function loadIcon(entry: AppEntry): Promise<void> {
+  function loadIcon(entry: AppEntry): void {
     if (entry.icon || !tauriRuntime) return;
-
-    const lookupPath = isUwpPath(entry.path)
-      ? `shell:AppsFolder\\${entry.path.slice(UWP_PREFIX.length)}`
-      : entry.path;
-
-    const cacheKey = `${lookupPath}:32`;
-
-    if (pendingIcons.has(cacheKey)) {
-      const icon = await pendingIcons.get(cacheKey);
-      if (icon) entry.icon = icon;
+    // A URL has no file to take an icon from
+    if (!entry.iconHash && isUrlEntry(entry)) {
+      entry.icon = URL_ICON;
       return;
     }
-
-    const promise = (async () => {
-      try {
-        const icon = (await invoke("get_file_icon", {
-          path: lookupPath,
-          size: 32,
-        })) as string | null;
-        return icon;
-      } catch (e) {
-        console.error("Failed to load icon", e);
-        return null;
-      } finally {
-        pendingIcons.delete(cacheKey);
-      }
-    })();
-
-    pendingIcons.set(cacheKey, promise);
-    const icon = await promise;
-    if (icon) entry.icon = icon;
+    entry.icon = iconUrl(entry, cardIconImgSize(state.settings));
   }
 
   function createIconLoader() {
@@ -421,8 +489,9 @@ export function useLauncherModel() {
     };
   }
 
-  async function hydrateEntryIcons(_entries: AppEntry[]): Promise<void> {
-    // Now icons are loaded lazily via IntersectionObserver in AppGrid.vue
+  async function hydrateEntryIcons(entries: AppEntry[]): Promise<void> {
+    // Icons are plain qlicon URLs now; the webview fetches and caches them itself
+    entries.forEach(loadIcon);
   }
 
   const iconLoader = createIconLoader();
@@ -490,6 +559,33 @@ export function useLauncherModel() {
     closeMenu();
   }
 
+  const importFolderOpen = ref(false);
+
+  function menuImportFolder(): void {
+    importFolderOpen.value = true;
+    closeMenu();
+  }
+
+  function closeImportFolder(): void {
+    importFolderOpen.value = false;
+  }
+
+  // Adds an accepted scan in one save on the backend, then shows the result
+  async function importScannedApps(preview: ScanPreview): Promise<void> {
+    if (!tauriRuntime) return;
+    await flushSave();
+    await saving;
+    try {
+      const loaded = coerceLauncherState(await invoke("import_scanned_apps", { preview }));
+      if (loaded) replaceState(loaded);
+      const count = preview.groups.reduce((n, g) => n + g.apps.length, 0);
+      showToast(t("toast.addedItems", { count }));
+      importFolderOpen.value = false;
+    } catch (e) {
+      showToast(t("error.importFailed", { error: e instanceof Error ? e.message : String(e) }));
+    }
+  }
+
   function menuAddGroup(): void {
     addGroup();
     closeMenu();
@@ -518,6 +614,17 @@ export function useLauncherModel() {
     }
   }
 
+  const menuAppPinned = computed(() => menu.kind === "app" && !!getMenuApp()?.pinned);
+
+  function menuTogglePin(): void {
+    const entry = getMenuApp();
+    if (entry) {
+      entry.pinned = entry.pinned ? undefined : true;
+      scheduleSave();
+    }
+    closeMenu();
+  }
+
   function menuEditApp(): void {
     const entry = getMenuApp();
     if (entry) openEditor(entry);
@@ -907,7 +1014,6 @@ export function useLauncherModel() {
 
   function updateAlwaysOnTop(value: boolean): void {
     state.settings.alwaysOnTop = value;
-    void setAlwaysOnTop(value);
     scheduleSave();
   }
 
@@ -925,14 +1031,9 @@ export function useLauncherModel() {
     scheduleSave();
   }
 
-  async function updateAutoStart(value: boolean): Promise<void> {
+  // Window- and OS-level settings are applied by the backend when they are saved
+  function updateAutoStart(value: boolean): void {
     state.settings.autoStart = value;
-    try {
-      const { enable, disable } = await import("@tauri-apps/plugin-autostart");
-      if (value) await enable(); else await disable();
-    } catch (e) {
-      console.error("autostart failed", e);
-    }
     scheduleSave();
   }
 
@@ -941,6 +1042,127 @@ export function useLauncherModel() {
     scheduleSave();
   }
 
+  function updateHideOnBlur(value: boolean): void {
+    state.settings.hideOnBlur = value;
+    scheduleSave();
+  }
+
+  function updateHideAfterLaunch(value: boolean): void {
+    state.settings.hideAfterLaunch = value;
+    scheduleSave();
+  }
+
+  async function chooseSyncDir(): Promise<void> {
+    if (!tauriRuntime) return;
+    const picked = await openDialog({ directory: true, multiple: false });
+    if (typeof picked !== "string" || !picked) return;
+    state.settings.syncDir = picked;
+    scheduleSave();
+  }
+
+  function clearSyncDir(): void {
+    state.settings.syncDir = "";
+    scheduleSave();
+  }
+
+  async function syncNow(): Promise<void> {
+    if (!tauriRuntime || !state.settings.syncDir) return;
+    await flushSave();
+    try {
+      await invoke("sync_library");
+      showToast(t("toast.synced"));
+    } catch (e) {
+      showToast(t("error.syncFailed", { error: e instanceof Error ? e.message : String(e) }));
+    }
+  }
+
+  // Takes back or makes again the latest saved edit of this session
+  async function stepHistory(command: "undo" | "redo"): Promise<void> {
+    if (!tauriRuntime) return;
+    await flushSave();
+    await saving;
+    try {
+      const loaded = coerceLauncherState(await invoke(command));
+      if (!loaded) return;
+      replaceState(loaded);
+      showToast(t(command === "undo" ? "toast.undone" : "toast.redone"));
+    } catch (e) {
+      showToast(t("error.undoFailed", { error: e instanceof Error ? e.message : String(e) }));
+    }
+  }
+
+  const undo = () => stepHistory("undo");
+  const redo = () => stepHistory("redo");
+
+  const dataLocation = ref<DataLocation | null>(null);
+
+  async function loadDataLocation(): Promise<void> {
+    try {
+      dataLocation.value = (await invoke("get_data_location")) as DataLocation;
+    } catch {
+      dataLocation.value = null;
+    }
+  }
+
+  // The backend restarts the app once the data has moved
+  async function moveDataLocation(target: DataDirTarget): Promise<void> {
+    if (!tauriRuntime) return;
+    const confirmed = await ask(t("settings.data.moveConfirm"), { kind: "warning" });
+    if (!confirmed) return;
+    try {
+      await invoke("move_data_location", { target });
+    } catch (e) {
+      showToast(t("error.moveDataFailed", { error: e instanceof Error ? e.message : String(e) }));
+    }
+  }
+
+  const profiles = ref<ProfileList | null>(null);
+
+  async function loadProfiles(): Promise<void> {
+    try {
+      profiles.value = (await invoke("list_profiles")) as ProfileList;
+    } catch {
+      profiles.value = null;
+    }
+  }
+
+  // Everything shown belongs to the active profile, so a switch reloads it all
+  async function reloadProfile(): Promise<void> {
+    await reloadState();
+    search.value = "";
+    invalidAppIds.clear();
+    await loadProfiles();
+  }
+
+  async function switchProfile(id: string): Promise<void> {
+    if (!tauriRuntime || profiles.value?.active === id) return;
+    await flushSave();
+    try {
+      await invoke("switch_profile", { id });
+    } catch (e) {
+      showToast(t("error.profileFailed", { error: e instanceof Error ? e.message : String(e) }));
+    }
+  }
+
+  async function createProfile(name: string, duplicate: boolean): Promise<void> {
+    if (!tauriRuntime) return;
+    try {
+      if (duplicate) await flushSave();
+      const profile = (await invoke(duplicate ? "clone_profile" : "create_profile", {
+        name,
+      })) as Profile;
+      showToast(t("settings.profiles.created", { name: profile.name }));
+      await loadProfiles();
+    } catch (e) {
+      showToast(t("error.profileFailed", { error: e instanceof Error ? e.message : String(e) }));
+    }
+  }
+
+  function updateShowPosition(value: string): void {
+    state.settings.showPosition = normalizeShowPosition(value);
+    scheduleSave();
+  }
+
   function onMainBlankDoubleClick(): void {
     if (!state.settings.dblClickBlankToHide) return;
     closeWindow();
@@ -960,6 +1182,14 @@ export function useLauncherModel() {
   }
 
   function onGlobalKeydown(ev: KeyboardEvent): void {
+    if ((ev.ctrlKey || ev.metaKey) && !ev.altKey && !isEditableTarget(ev.target)) {
+      const key = ev.key.toLowerCase();
+      if (key === "z" || key === "y") {
+        ev.preventDefault();
+        void (key === "y" || ev.shiftKey ? redo() : undo());
+        return;
+      }
+    }
     if (shouldAllowEditShortcut(ev)) return;
     if ((ev.ctrlKey || ev.metaKey) && !ev.altKey) {
       const key = ev.key.toLowerCase();
@@ -1086,12 +1316,10 @@ export function useLauncherModel() {
     }
 
     hydrateEntryIcons(activeGroup.value?.apps ?? []);
-    void setAlwaysOnTop(state.settings.alwaysOnTop);
-    if (state.settings.autoStart) {
-      import("@tauri-apps/plugin-autostart").then(({ enable }) => enable()).catch(() => {});
-    }
 
     if (!isTauriRuntime()) return;
+    void loadDataLocation();
+    void loadProfiles();
     unlistenFns = await installTauriFileDropListeners({
       groups: state.groups,
       getActiveGroup: () => activeGroup.value,
@@ -1101,21 +1329,73 @@ export function useLauncherModel() {
       scheduleSave,
       showToast,
       onStructureChanged: rebuildSearchIndex,
-      transformPaths: async (paths) => {
-        if (!state.settings.useRelativePath) return paths;
-        const mapped = await Promise.all(
-          paths.map(async (p) => {
-            try {
-              const v = (await invoke("make_relative_path", { path: p })) as unknown;
-              return typeof v === "string" && v.trim() ? v : p;
-            } catch {
-              return p;
-            }
-          }),
-        );
-        return mapped;
-      },
+      transformPaths: relativePaths,
+      resolveEntries: resolveShortcutEntries,
     });
+    unlistenFns.push(
+      ...(await installHotkeyEventListeners({
+        focusSearch: () => {
+          const input = document.querySelector<HTMLInputElement>(".topbar__search");
+          input?.focus();
+          input?.select();
+        },
+        openGroup: (groupId) => {
+          if (!state.groups.some((g) => g.id === groupId)) return;
+          search.value = "";
+          setActiveGroup(groupId);
+        },
+        showToast,
+      })),
+      ...(await installInstanceCommandListeners({
+        addPaths: async (paths, groupName) => {
+          if (groupName) {
+            const wanted = groupName.trim().toLowerCase();
+            const group = state.groups.find((g) => g.name.trim().toLowerCase() === wanted);
+            if (group) {
+              state.activeGroupId = group.id;
+              clearSelection();
+            } else {
+              addGroup(groupName);
+            }
+          }
+          search.value = "";
+          await addPathsToActiveGroup(paths);
+        },
+        search: (query) => {
+          search.value = query;
+          const input = document.querySelector<HTMLInputElement>(".topbar__search");
+          input?.focus();
+        },
+        showGroup: (name) => {
+          const wanted = name.trim().toLowerCase();
+          const group = state.groups.find((g) => g.name.trim().toLowerCase() === wanted);
+          if (!group) return false;
+          search.value = "";
+          setActiveGroup(group.id);
+          return true;
+        },
+        showToast,
+      })),
+      await listen<string>("tray-launch-failed", (ev) =>
+        showToast(t("error.openFailed", { error: ev.payload || t("error.unknown") })),
+      ),
+      await listen<unknown>("state-changed", (ev) => {
+        const loaded = coerceLauncherState(ev.payload);
+        if (!loaded || loaded.revision === state.revision) return;
+        replaceState(loaded);
+        showToast(t("toast.stateReloaded"));
+      }),
+      await listen<Profile>("profile-switched", () => void reloadProfile()),
+      await listen<string>("sync-failed", (ev) =>
+        showToast(t("error.syncFailed", { error: ev.payload || t("error.unknown") })),
+      ),
+      await listen<string>("profile-switch-failed", (ev) =>
+        showToast(t("error.profileFailed", { error: ev.payload || t("error.unknown") })),
+      ),
+      await listen<string>("settings-apply-failed", (ev) =>
+        showToast(t("error.settingsApplyFailed", { error: ev.payload || t("error.unknown") })),
+      ),
+    );
   });
 
   onUnmounted(() => {
@@ -1138,15 +1418,18 @@ export function useLauncherModel() {
     settingsOpen, addAppOpen, appStyle, filteredApps, isSearching,
     menu, editor, rename, setActiveGroup, launch,
     selectedAppIds, onAppClick, clearSelection, removeSelectedApps, moveSelectedToGroup,
-    openMenu, closeMenu, menuAddApp, menuAddUwpApp, menuAddGroup,
-    menuOpenApp, menuOpenAppFolder, menuEditApp, menuRemoveApp, menuMoveToGroup, menuRenameGroup, menuRemoveGroup,
+    openMenu, closeMenu, menuAddApp, menuAddUwpApp, menuImportFolder, menuAddGroup,
+    menuOpenApp, menuOpenAppFolder, menuAppPinned, menuTogglePin, menuEditApp, menuRemoveApp, menuMoveToGroup, menuRenameGroup, menuRemoveGroup,
     pickAndAddApps, openAddApp, closeAddApp, addUwpToActiveGroup,
+    importFolderOpen, closeImportFolder, importScannedApps,
     addGroup, removeGroup,
     minimizeWindow, toggleMaximizeWindow, closeWindow, startWindowDragging,
     closeEditor, applyEditorUpdate, openSettings, closeSettings,
     updateCardWidth, updateCardHeight, updateSidebarWidth, updateFontFamily, updateFontSize,
     updateCardFontSize, updateCardIconScale, updateTheme, updateDblClickBlankToHide,
-    updateLanguage, updateAlwaysOnTop, updateHideOnStartup, updateUseRelativePath, updateEnableGroupDragSort, updateAutoStart,
+    updateLanguage, updateAlwaysOnTop, updateHideOnStartup, updateShowPosition, updateHideOnBlur, updateHideAfterLaunch, updateUseRelativePath, updateEnableGroupDragSort, updateAutoStart,
+    dataLocation, moveDataLocation, chooseSyncDir, clearSyncDir, syncNow, undo, redo,
+    profiles, switchProfile, createProfile,
     applyToggleHotkey, onMainBlankDoubleClick,
     openRenameGroup: openRename, closeRenameGroup: closeRename, saveRenameGroup: saveRename,
     draggingAppId, dropBeforeAppId, dropEnd, dropTargetGroupId, draggingGroupId, groupDragReadyId, groupDragOverId,
@@ -1,4 +1,5 @@
-import type { AppEntry, Group, LauncherState, UiSettings } from "./types";
+import { convertFileSrc } from "@tauri-apps/api/core";
+import type { AppEntry, AppEntryKind, Group, LauncherState, UiSettings } from "./types";
 import { guessSystemLanguage } from "./i18n";
 
 export function createId(): string {
@@ -8,6 +9,41 @@ export function createId(): string {
   return `${Date.now().toString(16)}-${Math.random().toString(16).slice(2)}`;
 }
 
+export function iconUrl(
+  entry: Pick<AppEntry, "id" | "path" | "iconHash" | "iconLocation">,
+  size = 32,
+): string {
+  const base = `${convertFileSrc(entry.id, "qlicon")}?size=${size}`;
+  if (entry.iconHash) return `${base}&v=${entry.iconHash.slice(0, 12)}`;
+  const icon = entry.iconLocation ? `&icon=${encodeURIComponent(entry.iconLocation)}` : "";
+  return `${base}&path=${encodeURIComponent(entry.path)}${icon}`;
+}
+
+export const APP_ENTRY_KINDS: AppEntryKind[] = [
+  "executable",
+  "folder",
+  "document",
+  "url",
+  "uwp",
+  "shell",
+  "command",
+];
+
+// Same rule as the backend's `is_url`: a scheme of two or more characters, so drive
+// letters don't count, other than `shell:` and `uwp:`
+export function isUrlPath(path: string): boolean {
+  const scheme = /^([a-z][a-z0-9+.-]+):/i.exec(path.trim())?.[1]?.toLowerCase();
+  return !!scheme && scheme !== "shell" && scheme !== "uwp";
+}
+
+// Card icon for URL entries that have no icon of their own
+export const URL_ICON = "/url-icon.svg";
+
+// URLs and web bookmarks, whether marked as such or not yet saved with a kind
+export function isUrlEntry(entry: Pick<AppEntry, "path" | "kind">): boolean {
+  return entry.kind ? entry.kind === "url" : isUrlPath(entry.path);
+}
+
 export function getBasename(filePath: string): string {
   const normalized = filePath.replace(/\\/g, "/");
   const parts = normalized.split("/");
@@ -39,6 +75,12 @@ export function createDefaultState(): LauncherState {
     useRelativePath: false,
     enableGroupDragSort: true,
     autoStart: false,
+    doubleTapMs: 300,
+    holdPeekMs: 350,
+    showPosition: "last",
+    hideOnBlur: false,
+    hideAfterLaunch: false,
+    syncDir: "",
   };
   const groups: Group[] = [
     { id: createId(), name: "Group-1", apps: [] },
@@ -50,6 +92,7 @@ export function createDefaultState(): LauncherState {
     activeGroupId: groups[0]?.id ?? createId(),
     groups,
     settings,
+    revision: 0,
   };
 }
 